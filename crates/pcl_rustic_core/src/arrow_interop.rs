@@ -0,0 +1,94 @@
+use arrow::array::{Array, RecordBatch};
+use arrow::ffi::{to_ffi, FFI_ArrowArray, FFI_ArrowSchema};
+use polars::prelude::*;
+use polars_arrow::ffi::{import_array_from_c, import_field_from_c, ArrowArray, ArrowSchema};
+
+use crate::{PcdError, TablePointCloud};
+
+impl TablePointCloud {
+    /// Wraps an Arrow `RecordBatch` as a cloud without copying the column
+    /// buffers, which are handed to polars through the Arrow C data
+    /// interface.
+    ///
+    /// Every batch column becomes a cloud column of the same name. The batch
+    /// must hold numeric `x`, `y` and `z` columns, validated and cast to
+    /// `f64` as in [`TablePointCloud::from_dataframe`] (which copies them
+    /// unless they already are `f64`).
+    pub fn from_arrow(batch: RecordBatch) -> Result<Self, PcdError> {
+        let schema = batch.schema();
+        let columns = schema
+            .fields()
+            .iter()
+            .zip(batch.columns())
+            .map(|(field, array)| {
+                let (array, schema) = to_ffi(&array.to_data()).map_err(|e| {
+                    PcdError::InvalidInput(format!(
+                        "cannot export arrow column '{}': {e}",
+                        field.name()
+                    ))
+                })?;
+                // SAFETY: both crates implement the same C data interface, so
+                // the structs are ABI-compatible, and ownership of the
+                // exported buffers moves into polars, which releases them.
+                let series = unsafe {
+                    let schema = std::mem::transmute::<FFI_ArrowSchema, ArrowSchema>(schema);
+                    let array = std::mem::transmute::<FFI_ArrowArray, ArrowArray>(array);
+                    let polars_field = import_field_from_c(&schema)?;
+                    let array = import_array_from_c(array, polars_field.dtype)?;
+                    Series::from_arrow(field.name().as_str().into(), array)?
+                };
+                Ok(series.into())
+            })
+            .collect::<Result<Vec<Column>, PcdError>>()?;
+        let data = DataFrame::new(batch.num_rows(), columns)?;
+        Ok(Self::from_dataframe(data)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow::array::{ArrayRef, Float32Array, Float64Array, Int32Array, UInt16Array};
+
+    use super::*;
+
+    #[test]
+    fn wraps_a_record_batch() {
+        let batch = RecordBatch::try_from_iter([
+            (
+                "x",
+                Arc::new(Float64Array::from(vec![1.0, 2.0, 3.0])) as ArrayRef,
+            ),
+            (
+                "y",
+                Arc::new(Float32Array::from(vec![0.5, -0.5, 1.5])) as ArrayRef,
+            ),
+            (
+                "z",
+                Arc::new(Int32Array::from(vec![10, 20, 30])) as ArrayRef,
+            ),
+            (
+                "intensity",
+                Arc::new(UInt16Array::from(vec![Some(7), None, Some(9)])) as ArrayRef,
+            ),
+        ])
+        .unwrap();
+        let cloud = TablePointCloud::from_arrow(batch).unwrap();
+        assert_eq!(
+            cloud.xyz().unwrap(),
+            [[1.0, 0.5, 10.0], [2.0, -0.5, 20.0], [3.0, 1.5, 30.0]]
+        );
+        assert_eq!(cloud.attribute_names(), ["intensity"]);
+        let intensity = cloud.data().column("intensity").unwrap();
+        assert_eq!(intensity.dtype(), &DataType::UInt16);
+        assert_eq!(intensity.null_count(), 1);
+
+        let flat = RecordBatch::try_from_iter([
+            ("x", Arc::new(Float64Array::from(vec![1.0])) as ArrayRef),
+            ("y", Arc::new(Float64Array::from(vec![2.0])) as ArrayRef),
+        ])
+        .unwrap();
+        assert!(TablePointCloud::from_arrow(flat).is_err());
+    }
+}
@@ -0,0 +1,195 @@
+use nalgebra::{Matrix3, Vector3};
+use polars::prelude::*;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+
+use crate::TablePointCloud;
+
+/// Relative slack when testing whether a point lies inside a sphere, so
+/// points on its surface are not rejected through rounding.
+const CONTAINMENT_TOLERANCE: f64 = 1e-9;
+
+#[derive(Debug, Clone, Copy)]
+struct Sphere {
+    center: Vector3<f64>,
+    radius: f64,
+}
+
+impl Sphere {
+    fn point(p: Vector3<f64>) -> Self {
+        Self {
+            center: p,
+            radius: 0.0,
+        }
+    }
+
+    fn contains(&self, p: &Vector3<f64>) -> bool {
+        (p - self.center).norm() <= self.radius * (1.0 + CONTAINMENT_TOLERANCE) + f64::EPSILON
+    }
+
+    /// Smallest sphere with `a` and `b` on its surface.
+    fn diametral(a: Vector3<f64>, b: Vector3<f64>) -> Self {
+        Self {
+            center: (a + b) / 2.0,
+            radius: (a - b).norm() / 2.0,
+        }
+    }
+
+    /// Smallest sphere with `a`, `b` and `c` on its surface; for
+    /// (near-)collinear points, the sphere over the farthest pair.
+    fn circumscribed3(a: Vector3<f64>, b: Vector3<f64>, c: Vector3<f64>) -> Self {
+        let (ab, ac) = (b - a, c - a);
+        let normal = ab.cross(&ac);
+        let n2 = normal.norm_squared();
+        if n2 <= f64::EPSILON * ab.norm_squared() * ac.norm_squared() {
+            return [(a, b), (a, c), (b, c)]
+                .into_iter()
+                .map(|(p, q)| Self::diametral(p, q))
+                .max_by(|s, t| s.radius.total_cmp(&t.radius))
+                .expect("three candidate pairs");
+        }
+        let offset = (normal.cross(&ab) * ac.norm_squared()
+            + ac.cross(&normal) * ab.norm_squared())
+            / (2.0 * n2);
+        Self {
+            center: a + offset,
+            radius: offset.norm(),
+        }
+    }
+
+    /// Sphere with `a`, `b`, `c` and `d` on its surface; for (near-)coplanar
+    /// points, the smallest sphere through three of them holding the fourth.
+    fn circumscribed4(a: Vector3<f64>, b: Vector3<f64>, c: Vector3<f64>, d: Vector3<f64>) -> Self {
+        let (ab, ac, ad) = (b - a, c - a, d - a);
+        let system = Matrix3::from_rows(&[ab.transpose(), ac.transpose(), ad.transpose()]);
+        let rhs = Vector3::new(ab.norm_squared(), ac.norm_squared(), ad.norm_squared()) / 2.0;
+        let scale = ab.norm() * ac.norm() * ad.norm();
+        if system.determinant().abs() > f64::EPSILON * scale {
+            if let Some(offset) = system.lu().solve(&rhs) {
+                return Self {
+                    center: a + offset,
+                    radius: offset.norm(),
+                };
+            }
+        }
+        let points = [a, b, c, d];
+        let candidates = [[0, 1, 2], [0, 1, 3], [0, 2, 3], [1, 2, 3]]
+            .map(|[i, j, k]| Self::circumscribed3(points[i], points[j], points[k]));
+        candidates
+            .iter()
+            .filter(|s| points.iter().all(|p| s.contains(p)))
+            .min_by(|s, t| s.radius.total_cmp(&t.radius))
+            .or_else(|| {
+                candidates
+                    .iter()
+                    .max_by(|s, t| s.radius.total_cmp(&t.radius))
+            })
+            .copied()
+            .expect("four candidate triples")
+    }
+}
+
+impl TablePointCloud {
+    /// Smallest sphere enclosing every point, as `(center, radius)`.
+    ///
+    /// Uses Welzl's randomized algorithm with a fixed seed; see
+    /// [`TablePointCloud::minimal_enclosing_sphere_with_seed`]. Errors on an
+    /// empty cloud or non-finite coordinates.
+    pub fn minimal_enclosing_sphere(&self) -> Result<([f64; 3], f64), PolarsError> {
+        self.minimal_enclosing_sphere_with_seed(0)
+    }
+
+    /// [`TablePointCloud::minimal_enclosing_sphere`] with an explicit seed
+    /// for the point shuffle. The seed affects running time only, not the
+    /// resulting sphere (up to rounding).
+    pub fn minimal_enclosing_sphere_with_seed(
+        &self,
+        seed: u64,
+    ) -> Result<([f64; 3], f64), PolarsError> {
+        self.ensure_non_empty("minimal enclosing sphere")?;
+        let points = self.xyz()?;
+        if points.iter().flatten().any(|v| !v.is_finite()) {
+            polars_bail!(ComputeError: "minimal enclosing sphere needs finite coordinates");
+        }
+        // Work relative to the first point to keep the arithmetic precise
+        // for clouds far from the origin.
+        let anchor = Vector3::from(points[0]);
+        let mut points: Vec<Vector3<f64>> = points
+            .into_iter()
+            .map(|p| Vector3::from(p) - anchor)
+            .collect();
+        points.shuffle(&mut StdRng::seed_from_u64(seed));
+
+        let mut sphere = Sphere::point(points[0]);
+        for i in 1..points.len() {
+            if sphere.contains(&points[i]) {
+                continue;
+            }
+            sphere = Sphere::point(points[i]);
+            for j in 0..i {
+                if sphere.contains(&points[j]) {
+                    continue;
+                }
+                sphere = Sphere::diametral(points[i], points[j]);
+                for k in 0..j {
+                    if sphere.contains(&points[k]) {
+                        continue;
+                    }
+                    sphere = Sphere::circumscribed3(points[i], points[j], points[k]);
+                    for l in 0..k {
+                        if !sphere.contains(&points[l]) {
+                            sphere =
+                                Sphere::circumscribed4(points[i], points[j], points[k], points[l]);
+                        }
+                    }
+                }
+            }
+        }
+        let center = sphere.center + anchor;
+        Ok(([center.x, center.y, center.z], sphere.radius))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+
+    use super::*;
+
+    #[test]
+    fn recovers_a_sphere_from_its_surface() {
+        let (center, radius) = ([3.0, -2.0, 10.0], 2.5);
+        let mut rng = StdRng::seed_from_u64(4);
+        let points: Vec<[f64; 3]> = (0..400)
+            .map(|_| {
+                let d = Vector3::new(
+                    rng.random_range(-1.0..1.0),
+                    rng.random_range(-1.0..1.0),
+                    rng.random_range(-1.0..1.0),
+                )
+                .normalize()
+                    * radius;
+                [center[0] + d.x, center[1] + d.y, center[2] + d.z]
+            })
+            .collect();
+        let cloud = TablePointCloud::from_point_array(&points).unwrap();
+
+        for seed in [0, 7] {
+            let (found, r) = cloud.minimal_enclosing_sphere_with_seed(seed).unwrap();
+            // 400 random surface points span the sphere up to a small cap.
+            assert!((r - radius).abs() < 0.05 * radius, "radius {r}");
+            for axis in 0..3 {
+                assert!((found[axis] - center[axis]).abs() < 0.05 * radius);
+            }
+            let c = Vector3::from(found);
+            for p in &points {
+                assert!((Vector3::from(*p) - c).norm() <= r * (1.0 + 1e-9));
+            }
+        }
+        assert!(TablePointCloud::from_xyz(vec![], vec![], vec![])
+            .unwrap()
+            .minimal_enclosing_sphere()
+            .is_err());
+    }
+}
@@ -0,0 +1,145 @@
+use std::collections::HashMap;
+
+use polars::prelude::*;
+
+use crate::TablePointCloud;
+
+/// ASPRS (LAS 1.4) name of a standard classification code, or `None` for
+/// reserved and user-definable codes.
+pub fn asprs_class_name(code: i64) -> Option<&'static str> {
+    Some(match code {
+        0 => "Created, Never Classified",
+        1 => "Unclassified",
+        2 => "Ground",
+        3 => "Low Vegetation",
+        4 => "Medium Vegetation",
+        5 => "High Vegetation",
+        6 => "Building",
+        7 => "Low Point (Noise)",
+        9 => "Water",
+        10 => "Rail",
+        11 => "Road Surface",
+        13 => "Wire - Guard (Shield)",
+        14 => "Wire - Conductor (Phase)",
+        15 => "Transmission Tower",
+        16 => "Wire-Structure Connector",
+        17 => "Bridge Deck",
+        18 => "High Noise",
+        _ => return None,
+    })
+}
+
+impl TablePointCloud {
+    /// ASPRS name of each point's `classification` code.
+    ///
+    /// Codes without a standard name are labeled `Unassigned(n)`, and null
+    /// codes `Unassigned`.
+    pub fn classification_names(&self) -> Result<Vec<String>, PolarsError> {
+        let codes = self
+            .data()
+            .column("classification")?
+            .cast(&DataType::Int64)?;
+        Ok(codes
+            .i64()?
+            .iter()
+            .map(|code| match code {
+                Some(code) => asprs_class_name(code)
+                    .map(str::to_string)
+                    .unwrap_or_else(|| format!("Unassigned({code})")),
+                None => "Unassigned".to_string(),
+            })
+            .collect())
+    }
+
+    /// Per-label intersection over union between the ground-truth labels in
+    /// `gt_column` and the predictions in `pred_column`, both integer
+    /// columns.
+    ///
+    /// For each label present in either column, IoU is
+    /// `TP / (TP + FP + FN)`. Rows where either label is null are ignored.
+    pub fn segmentation_metrics(
+        &self,
+        gt_column: &str,
+        pred_column: &str,
+    ) -> Result<HashMap<i64, f64>, PolarsError> {
+        let labels = |name: &str| -> Result<Vec<Option<i64>>, PolarsError> {
+            let column = self.data().column(name)?;
+            if !column.dtype().is_integer() {
+                polars_bail!(SchemaMismatch: "label column '{}' must be integer, got {}", name, column.dtype());
+            }
+            Ok(column.cast(&DataType::Int64)?.i64()?.iter().collect())
+        };
+        // Per label: (true positives, false positives, false negatives).
+        let mut counts: HashMap<i64, (usize, usize, usize)> = HashMap::new();
+        for (gt, pred) in labels(gt_column)?.into_iter().zip(labels(pred_column)?) {
+            let (Some(gt), Some(pred)) = (gt, pred) else {
+                continue;
+            };
+            if gt == pred {
+                counts.entry(gt).or_default().0 += 1;
+            } else {
+                counts.entry(pred).or_default().1 += 1;
+                counts.entry(gt).or_default().2 += 1;
+            }
+        }
+        Ok(counts
+            .into_iter()
+            .map(|(label, (tp, fp, fn_))| (label, tp as f64 / (tp + fp + fn_) as f64))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn with_labels(name: &str, labels: Vec<Option<i32>>) -> TablePointCloud {
+        let n = labels.len();
+        let mut cloud =
+            TablePointCloud::from_xyz(vec![0.0; n], vec![0.0; n], vec![0.0; n]).unwrap();
+        cloud
+            .set_attribute(Series::new(name.into(), labels))
+            .unwrap();
+        cloud
+    }
+
+    #[test]
+    fn asprs_codes_map_to_names() {
+        let cloud = with_labels("classification", vec![Some(2), Some(6), Some(42), None]);
+        assert_eq!(
+            cloud.classification_names().unwrap(),
+            ["Ground", "Building", "Unassigned(42)", "Unassigned"]
+        );
+        assert!(with_labels("label", vec![Some(2)])
+            .classification_names()
+            .is_err());
+    }
+
+    #[test]
+    fn per_class_iou_of_two_classes() {
+        let mut cloud = with_labels(
+            "gt",
+            vec![Some(1), Some(1), Some(1), Some(1), Some(2), Some(2), None],
+        );
+        cloud
+            .set_attribute(Series::new(
+                "pred".into(),
+                vec![
+                    Some(1i64),
+                    Some(1),
+                    Some(1),
+                    Some(2),
+                    Some(2),
+                    Some(2),
+                    Some(1),
+                ],
+            ))
+            .unwrap();
+        // Class 1: TP 3, FN 1. Class 2: TP 2, FP 1. The null row is skipped.
+        let iou = cloud.segmentation_metrics("gt", "pred").unwrap();
+        assert_eq!(iou.len(), 2);
+        assert!((iou[&1] - 3.0 / 4.0).abs() < 1e-12);
+        assert!((iou[&2] - 2.0 / 3.0).abs() < 1e-12);
+        assert!(cloud.segmentation_metrics("gt", "missing").is_err());
+    }
+}
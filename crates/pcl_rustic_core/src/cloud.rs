@@ -0,0 +1,675 @@
+use nalgebra::Matrix4;
+use polars::prelude::*;
+
+/// Names of the mandatory coordinate columns.
+pub const COORDINATE_COLUMNS: [&str; 3] = ["x", "y", "z"];
+
+/// Names of the per-point normal columns.
+pub const NORMAL_COLUMNS: [&str; 3] = ["nx", "ny", "nz"];
+
+/// Names of the per-point color channels.
+pub const COLOR_COLUMNS: [&str; 3] = ["red", "green", "blue"];
+
+/// Names of the per-point acquisition time columns, in order of preference.
+pub(crate) const TIME_COLUMNS: [&str; 4] = ["timestamp", "gps_time", "time", "time_offset"];
+
+/// How a three-column vector attribute responds to [`TablePointCloud::transform`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VectorKind {
+    /// Surface normals: transformed by the inverse-transpose of the linear
+    /// block and re-normalized.
+    Normal,
+    /// Directions or displacements: transformed by the linear block.
+    Direction,
+}
+
+/// A registered vector attribute spanning three columns.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VectorAttribute {
+    pub columns: [String; 3],
+    pub kind: VectorKind,
+}
+
+/// Element offsets of the fields inside one record of an interleaved
+/// buffer, for [`TablePointCloud::from_interleaved`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct XyzOffsets {
+    pub x: usize,
+    pub y: usize,
+    pub z: usize,
+    pub attributes: Vec<(String, usize)>,
+}
+
+impl XyzOffsets {
+    pub fn new(x: usize, y: usize, z: usize) -> Self {
+        Self {
+            x,
+            y,
+            z,
+            attributes: Vec::new(),
+        }
+    }
+
+    /// Adds a named `f64` attribute read at `offset` within each record.
+    pub fn with_attribute(mut self, name: &str, offset: usize) -> Self {
+        self.attributes.push((name.to_string(), offset));
+        self
+    }
+}
+
+/// A point cloud stored column-wise in a polars `DataFrame`.
+///
+/// The frame always holds `x`, `y` and `z` as `f64` columns. Every other
+/// column is a per-point attribute (intensity, colors, normals, ...).
+#[derive(Debug, Clone)]
+pub struct TablePointCloud {
+    data: DataFrame,
+    vectors: Vec<VectorAttribute>,
+    history: Vec<Matrix4<f64>>,
+    origin: [f64; 3],
+    organized: Option<(usize, usize)>,
+}
+
+impl TablePointCloud {
+    /// Builds a cloud from separate coordinate vectors of equal length.
+    pub fn from_xyz(x: Vec<f64>, y: Vec<f64>, z: Vec<f64>) -> Result<Self, PolarsError> {
+        if x.len() != y.len() || x.len() != z.len() {
+            polars_bail!(ShapeMismatch: "coordinate vectors differ in length: x={}, y={}, z={}", x.len(), y.len(), z.len());
+        }
+        let height = x.len();
+        let data = DataFrame::new(
+            height,
+            vec![
+                Column::new("x".into(), x),
+                Column::new("y".into(), y),
+                Column::new("z".into(), z),
+            ],
+        )?;
+        Ok(Self {
+            data,
+            vectors: Vec::new(),
+            history: Vec::new(),
+            origin: [0.0; 3],
+            organized: None,
+        })
+    }
+
+    /// Wraps an existing frame as a cloud.
+    ///
+    /// The frame must hold numeric `x`, `y` and `z` columns, which are cast
+    /// to `f64`; every other column is kept as an attribute.
+    pub fn from_dataframe(mut data: DataFrame) -> Result<Self, PolarsError> {
+        for name in COORDINATE_COLUMNS {
+            let Some(column) = data.column(name).ok() else {
+                polars_bail!(ColumnNotFound: "point cloud frame is missing the '{}' coordinate column", name);
+            };
+            if !column.dtype().is_primitive_numeric() {
+                polars_bail!(SchemaMismatch: "coordinate column '{}' must be numeric, got {}", name, column.dtype());
+            }
+            let column = column.cast(&DataType::Float64)?;
+            data.with_column(column)?;
+        }
+        Ok(Self {
+            data,
+            vectors: Vec::new(),
+            history: Vec::new(),
+            origin: [0.0; 3],
+            organized: None,
+        })
+    }
+
+    /// Builds a cloud from a flat buffer of `stride`-element records, such
+    /// as `[x, y, z, intensity, x, y, z, intensity, ...]`.
+    ///
+    /// `offsets` locates the coordinates and any attributes within a
+    /// record; elements it does not mention are ignored.
+    pub fn from_interleaved(
+        data: &[f64],
+        stride: usize,
+        offsets: &XyzOffsets,
+    ) -> Result<Self, PolarsError> {
+        if stride == 0 {
+            polars_bail!(InvalidOperation: "interleaved stride must be positive");
+        }
+        if !data.len().is_multiple_of(stride) {
+            polars_bail!(ShapeMismatch: "buffer of {} values is not a whole number of {}-value records", data.len(), stride);
+        }
+        let fields = [("x", offsets.x), ("y", offsets.y), ("z", offsets.z)]
+            .into_iter()
+            .chain(
+                offsets
+                    .attributes
+                    .iter()
+                    .map(|(name, o)| (name.as_str(), *o)),
+            );
+        for (name, offset) in fields {
+            if offset >= stride {
+                polars_bail!(OutOfBounds: "offset {} of '{}' is outside the {}-value record", offset, name, stride);
+            }
+        }
+        let field = |offset: usize| -> Vec<f64> {
+            data.chunks_exact(stride)
+                .map(|record| record[offset])
+                .collect()
+        };
+        let mut cloud = Self::from_xyz(field(offsets.x), field(offsets.y), field(offsets.z))?;
+        for (name, offset) in &offsets.attributes {
+            cloud.set_attribute(Series::new(name.as_str().into(), field(*offset)))?;
+        }
+        Ok(cloud)
+    }
+
+    /// Read-only access to the underlying frame.
+    pub fn data(&self) -> &DataFrame {
+        &self.data
+    }
+
+    /// Number of points in the cloud.
+    pub fn len(&self) -> usize {
+        self.data.height()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns `true` if the cloud has a column with the given name.
+    pub fn has_column(&self, name: &str) -> bool {
+        self.data.get_column_index(name).is_some()
+    }
+
+    /// Names of all non-coordinate columns, in frame order.
+    pub fn attribute_names(&self) -> Vec<String> {
+        self.data
+            .get_column_names()
+            .into_iter()
+            .filter(|name| !COORDINATE_COLUMNS.contains(&name.as_str()))
+            .map(|name| name.to_string())
+            .collect()
+    }
+
+    /// The `x` coordinates; empty for an empty cloud, like `y()` and `z()`.
+    pub fn x(&self) -> Result<Vec<f64>, PolarsError> {
+        self.column_f64("x")
+    }
+
+    pub fn y(&self) -> Result<Vec<f64>, PolarsError> {
+        self.column_f64("y")
+    }
+
+    pub fn z(&self) -> Result<Vec<f64>, PolarsError> {
+        self.column_f64("z")
+    }
+
+    /// Values of any numeric column as `f64`, like `x()`; integer columns
+    /// are cast and nulls read as NaN. Errors on a missing or non-numeric
+    /// column.
+    pub fn attribute(&self, name: &str) -> Result<Vec<f64>, PolarsError> {
+        let dtype = self.data.column(name)?.dtype();
+        if !dtype.is_primitive_numeric() {
+            polars_bail!(SchemaMismatch: "column '{}' is {}, not numeric", name, dtype);
+        }
+        self.column_f64(name)
+    }
+
+    /// Coordinates as `[x, y, z]` triples.
+    pub fn xyz(&self) -> Result<Vec<[f64; 3]>, PolarsError> {
+        let (x, y, z) = (self.x()?, self.y()?, self.z()?);
+        Ok((0..x.len()).map(|i| [x[i], y[i], z[i]]).collect())
+    }
+
+    /// Returns `true` if the cloud carries `nx`, `ny` and `nz` columns.
+    pub fn has_normals(&self) -> bool {
+        NORMAL_COLUMNS.iter().all(|name| self.has_column(name))
+    }
+
+    /// Normals as `[nx, ny, nz]` triples; errors if the cloud has none.
+    pub fn normals(&self) -> Result<Vec<[f64; 3]>, PolarsError> {
+        if !self.has_normals() {
+            polars_bail!(ColumnNotFound: "cloud has no normals (expected nx, ny, nz columns)");
+        }
+        let (nx, ny, nz) = (
+            self.column_f64("nx")?,
+            self.column_f64("ny")?,
+            self.column_f64("nz")?,
+        );
+        Ok((0..nx.len()).map(|i| [nx[i], ny[i], nz[i]]).collect())
+    }
+
+    /// Declares three columns as one vector attribute so `transform`
+    /// rotates them instead of copying them.
+    ///
+    /// `nx`/`ny`/`nz` are always treated as normals and need no
+    /// registration. Registering the same columns again updates the kind.
+    pub fn register_vector_attribute(
+        &mut self,
+        columns: [&str; 3],
+        kind: VectorKind,
+    ) -> Result<(), PolarsError> {
+        for name in columns {
+            if COORDINATE_COLUMNS.contains(&name) {
+                polars_bail!(InvalidOperation: "'{}' is a coordinate column, not an attribute", name);
+            }
+            self.data.column(name)?;
+        }
+        let columns = columns.map(str::to_string);
+        self.vectors.retain(|v| v.columns != columns);
+        self.vectors.push(VectorAttribute { columns, kind });
+        Ok(())
+    }
+
+    /// Vector attributes `transform` will rotate: the registered ones whose
+    /// columns still exist, plus the normals if present and unregistered.
+    pub fn vector_attributes(&self) -> Vec<VectorAttribute> {
+        let mut vectors: Vec<VectorAttribute> = self
+            .vectors
+            .iter()
+            .filter(|v| v.columns.iter().all(|c| self.has_column(c)))
+            .cloned()
+            .collect();
+        let normals = NORMAL_COLUMNS.map(str::to_string);
+        if self.has_normals() && !vectors.iter().any(|v| v.columns == normals) {
+            vectors.push(VectorAttribute {
+                columns: normals,
+                kind: VectorKind::Normal,
+            });
+        }
+        vectors
+    }
+
+    /// Global position of the local origin. Stored coordinates (`x()`,
+    /// `xyz()`, the frame) are relative to it, and geometric operations
+    /// such as `transform` act on them; `global_xyz()` adds it back.
+    /// `[0, 0, 0]` unless set.
+    pub fn origin(&self) -> [f64; 3] {
+        self.origin
+    }
+
+    /// Declares that the stored coordinates are relative to `origin`,
+    /// without changing them.
+    pub fn set_origin(&mut self, origin: [f64; 3]) {
+        self.origin = origin;
+    }
+
+    /// Re-expresses the coordinates relative to a new local `origin`,
+    /// keeping every point's global position.
+    ///
+    /// Moving the origin into the cloud (e.g. to its global centroid)
+    /// keeps georeferenced coordinates such as UTM small and precise;
+    /// relocating back to `[0, 0, 0]` restores plain global coordinates.
+    pub fn relocate_origin(&self, origin: [f64; 3]) -> Result<Self, PolarsError> {
+        let shift = |values: Vec<f64>, axis: usize| -> Vec<f64> {
+            let delta = origin[axis] - self.origin[axis];
+            values.into_iter().map(|v| v - delta).collect()
+        };
+        let mut out = self.with_coordinates(
+            shift(self.x()?, 0),
+            shift(self.y()?, 1),
+            shift(self.z()?, 2),
+        )?;
+        out.origin = origin;
+        Ok(out)
+    }
+
+    /// Coordinates with the local origin added back.
+    pub fn global_xyz(&self) -> Result<Vec<[f64; 3]>, PolarsError> {
+        Ok(self
+            .xyz()?
+            .into_iter()
+            .map(|p| [0, 1, 2].map(|axis| p[axis] + self.origin[axis]))
+            .collect())
+    }
+
+    /// `(width, height)` of an organized cloud, whose rows form a
+    /// row-major sensor image (as from a depth camera or a spinning lidar);
+    /// `None` for an unorganized one.
+    ///
+    /// Operations that keep every row in place (such as `transform`)
+    /// preserve it, while `take`, `filter`, `slice` and `concat` drop it.
+    pub fn organized_dims(&self) -> Option<(usize, usize)> {
+        self.organized
+    }
+
+    /// Marks the cloud as organized into a `width` x `height` image.
+    /// `width * height` must equal the number of points.
+    pub fn set_organized(&mut self, width: usize, height: usize) -> Result<(), PolarsError> {
+        if width.checked_mul(height) != Some(self.len()) {
+            polars_bail!(ShapeMismatch: "a {}x{} image does not match the cloud's {} points", width, height, self.len());
+        }
+        self.organized = Some((width, height));
+        Ok(())
+    }
+
+    /// Matrices applied through `transform` and its helpers since the cloud
+    /// was built, oldest first.
+    pub fn transform_history(&self) -> &[Matrix4<f64>] {
+        &self.history
+    }
+
+    /// The composition of [`TablePointCloud::transform_history`], mapping
+    /// original coordinates to the current ones; identity when untouched.
+    pub fn accumulated_transform(&self) -> Matrix4<f64> {
+        self.history
+            .iter()
+            .fold(Matrix4::identity(), |acc, matrix| matrix * acc)
+    }
+
+    pub(crate) fn record_transform(&mut self, matrix: Matrix4<f64>) {
+        self.history.push(matrix);
+    }
+
+    pub(crate) fn clear_history(&mut self) {
+        self.history.clear();
+    }
+
+    /// Adds or replaces an attribute column.
+    ///
+    /// The series must have one value per point and may not shadow a
+    /// coordinate column.
+    pub fn set_attribute(&mut self, series: Series) -> Result<(), PolarsError> {
+        if COORDINATE_COLUMNS.contains(&series.name().as_str()) {
+            polars_bail!(InvalidOperation: "'{}' is a coordinate column, not an attribute", series.name());
+        }
+        if series.len() != self.len() {
+            polars_bail!(ShapeMismatch: "attribute '{}' has {} values but the cloud has {} points", series.name(), series.len(), self.len());
+        }
+        self.data.with_column(series.into_column())?;
+        Ok(())
+    }
+
+    /// Stacks clouds into one, taking the union of their attribute columns.
+    ///
+    /// Rows from a cloud lacking a column are null in it. A column whose
+    /// type differs between clouds is cast to `f64`. Registered vector
+    /// attributes of every input are kept; transform histories are not.
+    /// The result uses the first cloud's origin, with the others relocated
+    /// to it. No clouds yield an empty cloud.
+    pub fn concat(clouds: &[&Self]) -> Result<Self, PolarsError> {
+        let mut schema: Vec<(PlSmallStr, DataType)> = COORDINATE_COLUMNS
+            .iter()
+            .map(|&name| (name.into(), DataType::Float64))
+            .collect();
+        for cloud in clouds {
+            for column in cloud.data.columns() {
+                match schema.iter_mut().find(|(name, _)| name == column.name()) {
+                    Some((_, dtype)) if dtype != column.dtype() => *dtype = DataType::Float64,
+                    Some(_) => {}
+                    None => schema.push((column.name().clone(), column.dtype().clone())),
+                }
+            }
+        }
+        let mut out = Self::from_xyz(Vec::new(), Vec::new(), Vec::new())?;
+        for (name, dtype) in &schema[COORDINATE_COLUMNS.len()..] {
+            out.data
+                .with_column(Column::full_null(name.clone(), 0, dtype))?;
+        }
+        out.origin = clouds.first().map_or([0.0; 3], |cloud| cloud.origin);
+        for &cloud in clouds {
+            let relocated;
+            let cloud = if cloud.origin == out.origin {
+                cloud
+            } else {
+                relocated = cloud.relocate_origin(out.origin)?;
+                &relocated
+            };
+            let columns = schema
+                .iter()
+                .map(|(name, dtype)| match cloud.data.column(name) {
+                    Ok(column) => column.cast(dtype),
+                    Err(_) => Ok(Column::full_null(name.clone(), cloud.len(), dtype)),
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            out.data
+                .vstack_mut_owned(DataFrame::new(cloud.len(), columns)?)?;
+            for vector in &cloud.vectors {
+                if !out.vectors.contains(vector) {
+                    out.vectors.push(vector.clone());
+                }
+            }
+        }
+        out.data.rechunk_mut();
+        Ok(out)
+    }
+
+    /// A new cloud holding the rows at `indices`, in that order.
+    pub fn take(&self, indices: &[usize]) -> Result<Self, PolarsError> {
+        let indices: Vec<IdxSize> = indices.iter().map(|&i| i as IdxSize).collect();
+        let data = self.data.take(&IdxCa::from_vec("".into(), indices))?;
+        Ok(Self {
+            data,
+            vectors: self.vectors.clone(),
+            history: self.history.clone(),
+            origin: self.origin,
+            organized: None,
+        })
+    }
+
+    /// A new cloud holding the rows where `mask` is true.
+    pub fn filter(&self, mask: &BooleanChunked) -> Result<Self, PolarsError> {
+        Ok(Self {
+            data: self.data.filter(mask)?,
+            vectors: self.vectors.clone(),
+            history: self.history.clone(),
+            origin: self.origin,
+            organized: None,
+        })
+    }
+
+    /// A zero-copy view of `length` rows starting at `offset`, clamped to
+    /// the cloud's bounds.
+    pub fn slice(&self, offset: usize, length: usize) -> Self {
+        Self {
+            data: self.data.slice(offset as i64, length),
+            vectors: self.vectors.clone(),
+            history: self.history.clone(),
+            origin: self.origin,
+            organized: None,
+        }
+    }
+
+    /// Runs `f` over a lazy view of the frame and wraps the collected result
+    /// as a new cloud, for filters and derived columns not covered by a
+    /// dedicated method.
+    ///
+    /// The result must still hold `x`, `y` and `z` as `f64`. Origin and
+    /// transform history carry over, as do vector attributes whose columns
+    /// survive; organized dimensions do not.
+    pub fn with_lazy(&self, f: impl FnOnce(LazyFrame) -> LazyFrame) -> Result<Self, PolarsError> {
+        let data = f(self.data.clone().lazy()).collect()?;
+        for name in COORDINATE_COLUMNS {
+            let Some(column) = data.column(name).ok() else {
+                polars_bail!(ColumnNotFound: "lazy query dropped the '{}' coordinate column", name);
+            };
+            if column.dtype() != &DataType::Float64 {
+                polars_bail!(SchemaMismatch: "lazy query left coordinate column '{}' as {}, expected f64", name, column.dtype());
+            }
+        }
+        let vectors = self
+            .vectors
+            .iter()
+            .filter(|vector| {
+                vector
+                    .columns
+                    .iter()
+                    .all(|name| data.get_column_index(name).is_some())
+            })
+            .cloned()
+            .collect();
+        Ok(Self {
+            data,
+            vectors,
+            history: self.history.clone(),
+            origin: self.origin,
+            organized: None,
+        })
+    }
+
+    /// Returns a copy of the cloud with its coordinates replaced, keeping
+    /// every attribute column in place.
+    pub(crate) fn with_coordinates(
+        &self,
+        x: Vec<f64>,
+        y: Vec<f64>,
+        z: Vec<f64>,
+    ) -> Result<Self, PolarsError> {
+        let mut data = self.data.clone();
+        for (name, values) in COORDINATE_COLUMNS.into_iter().zip([x, y, z]) {
+            if values.len() != data.height() {
+                polars_bail!(ShapeMismatch: "new '{}' coordinates have {} values but the cloud has {} points", name, values.len(), data.height());
+            }
+            data.with_column(Column::new(name.into(), values))?;
+        }
+        Ok(Self {
+            data,
+            vectors: self.vectors.clone(),
+            history: self.history.clone(),
+            origin: self.origin,
+            organized: self.organized,
+        })
+    }
+
+    /// Fails with a uniform error when the cloud has no points, for
+    /// operations such as `centroid` that are undefined on an empty cloud.
+    pub(crate) fn ensure_non_empty(&self, operation: &str) -> Result<(), PolarsError> {
+        if self.is_empty() {
+            polars_bail!(ComputeError: "{} is undefined for an empty cloud", operation);
+        }
+        Ok(())
+    }
+
+    /// Reads any numeric column as `f64`, mapping nulls to NaN.
+    pub(crate) fn column_f64(&self, name: &str) -> Result<Vec<f64>, PolarsError> {
+        let column = self.data.column(name)?.cast(&DataType::Float64)?;
+        Ok(column
+            .f64()?
+            .iter()
+            .map(|v| v.unwrap_or(f64::NAN))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interleaved_records_with_intensity() {
+        let data = [
+            0.0, 1.0, 2.0, 10.0, //
+            3.0, 4.0, 5.0, 20.0, //
+            6.0, 7.0, 8.0, 30.0,
+        ];
+        let offsets = XyzOffsets::new(0, 1, 2).with_attribute("intensity", 3);
+        let cloud = TablePointCloud::from_interleaved(&data, 4, &offsets).unwrap();
+        assert_eq!(cloud.len(), 3);
+        assert_eq!(cloud.x().unwrap(), [0.0, 3.0, 6.0]);
+        assert_eq!(cloud.y().unwrap(), [1.0, 4.0, 7.0]);
+        assert_eq!(cloud.z().unwrap(), [2.0, 5.0, 8.0]);
+        assert_eq!(cloud.attribute("intensity").unwrap(), [10.0, 20.0, 30.0]);
+
+        assert!(TablePointCloud::from_interleaved(&data[..11], 4, &offsets).is_err());
+        let outside = XyzOffsets::new(0, 1, 2).with_attribute("intensity", 4);
+        assert!(TablePointCloud::from_interleaved(&data, 4, &outside).is_err());
+    }
+
+    #[test]
+    fn wraps_dataframe_with_and_without_z() {
+        let df = df!(
+            "x" => [1.0f32, 2.0],
+            "y" => [3i32, 4],
+            "z" => [5.0, 6.0],
+            "intensity" => [7u16, 8],
+        )
+        .unwrap();
+        let cloud = TablePointCloud::from_dataframe(df).unwrap();
+        assert_eq!(cloud.xyz().unwrap(), [[1.0, 3.0, 5.0], [2.0, 4.0, 6.0]]);
+        assert_eq!(
+            cloud.data().column("y").unwrap().dtype(),
+            &DataType::Float64
+        );
+        assert_eq!(cloud.attribute_names(), ["intensity"]);
+
+        let flat = df!("x" => [1.0], "y" => [2.0]).unwrap();
+        let err = TablePointCloud::from_dataframe(flat).unwrap_err();
+        assert!(err.to_string().contains("'z'"), "{err}");
+    }
+
+    #[test]
+    fn empty_cloud_transform_short_circuits() {
+        let mut cloud = TablePointCloud::from_xyz(Vec::new(), Vec::new(), Vec::new()).unwrap();
+        cloud
+            .set_attribute(Series::new("intensity".into(), Vec::<f32>::new()))
+            .unwrap();
+        assert!(cloud.ensure_non_empty("centroid").is_err());
+
+        let moved = cloud
+            .transform(&Matrix4::new_translation(&[1.0, 2.0, 3.0].into()))
+            .unwrap();
+        assert!(moved.is_empty());
+        assert_eq!(moved.data().schema(), cloud.data().schema());
+        assert_eq!(moved.transform_history().len(), 1);
+
+        let one = TablePointCloud::from_xyz(vec![0.0], vec![0.0], vec![0.0]).unwrap();
+        assert!(one.ensure_non_empty("centroid").is_ok());
+    }
+
+    #[test]
+    fn lazy_passthrough_adds_a_column() {
+        let mut cloud = TablePointCloud::from_xyz(
+            vec![3.0, 0.0, 1.0],
+            vec![4.0, 0.0, 2.0],
+            vec![0.0, 0.0, 2.0],
+        )
+        .unwrap();
+        cloud.set_origin([1.0, 2.0, 3.0]);
+
+        let with_distance = cloud
+            .with_lazy(|frame| {
+                frame.with_column(
+                    (col("x") * col("x") + col("y") * col("y") + col("z") * col("z"))
+                        .sqrt()
+                        .alias("dist_from_origin"),
+                )
+            })
+            .unwrap();
+        assert_eq!(
+            with_distance.attribute("dist_from_origin").unwrap(),
+            [5.0, 0.0, 3.0]
+        );
+        assert_eq!(with_distance.origin(), [1.0, 2.0, 3.0]);
+
+        let near = cloud
+            .with_lazy(|frame| frame.filter(col("x").lt(lit(2.0))))
+            .unwrap();
+        assert_eq!(near.len(), 2);
+
+        assert!(cloud
+            .with_lazy(|frame| frame.select([col("x"), col("y")]))
+            .is_err());
+        assert!(cloud
+            .with_lazy(|frame| frame.with_column(col("y").cast(DataType::Float32)))
+            .is_err());
+    }
+
+    #[test]
+    fn attribute_reads_a_column_back() {
+        let mut cloud =
+            TablePointCloud::from_xyz(vec![0.0; 3], vec![1.0; 3], vec![2.0; 3]).unwrap();
+        cloud
+            .set_attribute(Series::new(
+                "intensity".into(),
+                vec![Some(7u16), None, Some(300)],
+            ))
+            .unwrap();
+        cloud
+            .set_attribute(Series::new("tag".into(), vec!["a", "b", "c"]))
+            .unwrap();
+        let intensity = cloud.attribute("intensity").unwrap();
+        assert_eq!(intensity[0], 7.0);
+        assert!(intensity[1].is_nan());
+        assert_eq!(intensity[2], 300.0);
+        assert_eq!(cloud.attribute("y").unwrap(), cloud.y().unwrap());
+        assert!(cloud.attribute("missing").is_err());
+        assert!(cloud.attribute("tag").is_err());
+    }
+}
@@ -0,0 +1,95 @@
+use polars::prelude::*;
+
+use crate::kdtree::KdTree;
+use crate::TablePointCloud;
+
+/// Label given to noise points by [`TablePointCloud::dbscan`].
+const NOISE: i64 = -1;
+
+impl TablePointCloud {
+    /// DBSCAN clustering, returning one label per point: clusters are
+    /// numbered from 0 in order of discovery and noise is `-1`.
+    ///
+    /// A point is a core point when at least `min_points` points,
+    /// itself included, lie within `eps`. Clusters grow from core points
+    /// through their neighborhoods; non-core points reached this way join
+    /// the first cluster that reaches them, and the rest are noise. Unlike
+    /// connected components, sparse outliers never bridge two clusters.
+    pub fn dbscan(&self, eps: f64, min_points: usize) -> Result<Vec<i64>, PolarsError> {
+        if !(eps.is_finite() && eps >= 0.0) {
+            polars_bail!(InvalidOperation: "DBSCAN eps must be non-negative and finite, got {}", eps);
+        }
+        if min_points == 0 {
+            polars_bail!(InvalidOperation: "DBSCAN needs min_points > 0");
+        }
+        let points = self.xyz()?;
+        let tree = KdTree::new(&points);
+        let neighbors = |i: usize| -> Vec<usize> {
+            tree.radius_search(points[i], eps)
+                .into_iter()
+                .map(|(j, _)| j)
+                .collect()
+        };
+
+        let mut labels: Vec<Option<i64>> = vec![None; points.len()];
+        let mut next = 0;
+        for seed in 0..points.len() {
+            if labels[seed].is_some() {
+                continue;
+            }
+            let seed_neighbors = neighbors(seed);
+            if seed_neighbors.len() < min_points {
+                // May still be claimed as a border point later.
+                labels[seed] = Some(NOISE);
+                continue;
+            }
+            let cluster = next;
+            next += 1;
+            labels[seed] = Some(cluster);
+            let mut queue = seed_neighbors;
+            while let Some(i) = queue.pop() {
+                match labels[i] {
+                    Some(NOISE) => labels[i] = Some(cluster),
+                    None => {
+                        labels[i] = Some(cluster);
+                        let reached = neighbors(i);
+                        if reached.len() >= min_points {
+                            queue.extend(reached);
+                        }
+                    }
+                    Some(_) => {}
+                }
+            }
+        }
+        Ok(labels.into_iter().map(|l| l.unwrap_or(NOISE)).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn two_blobs_and_scattered_noise() {
+        let blob_a = TablePointCloud::sample_sphere([0.0; 3], 1.0, 100, 1).unwrap();
+        let blob_b = TablePointCloud::sample_sphere([10.0, 0.0, 0.0], 1.0, 100, 2).unwrap();
+        let noise = TablePointCloud::from_xyz(
+            vec![5.0, -6.0, 3.0, 12.0],
+            vec![5.0, 2.0, -7.0, 6.0],
+            vec![0.0, 4.0, 1.0, -5.0],
+        )
+        .unwrap();
+        let cloud = TablePointCloud::concat(&[&blob_a, &noise, &blob_b]).unwrap();
+
+        let labels = cloud.dbscan(0.6, 5).unwrap();
+        assert_eq!(labels.len(), 204);
+        assert!(labels[..100].iter().all(|&l| l == 0));
+        assert_eq!(labels[100..104], [NOISE; 4]);
+        assert!(labels[104..].iter().all(|&l| l == 1));
+
+        // With a huge eps everything is one cluster.
+        assert!(cloud.dbscan(100.0, 5).unwrap().iter().all(|&l| l == 0));
+        assert!(cloud.dbscan(-1.0, 5).is_err());
+        assert!(cloud.dbscan(0.5, 0).is_err());
+    }
+}
@@ -0,0 +1,259 @@
+use std::collections::HashMap;
+
+use polars::lazy::dsl::{max_horizontal, min_horizontal};
+use polars::prelude::*;
+
+use crate::cloud::COLOR_COLUMNS;
+use crate::TablePointCloud;
+
+/// Color given to labels missing from a palette.
+const UNMAPPED_COLOR: [u8; 3] = [128, 128, 128];
+
+/// Maps a scalar in `[0, 1]` to a color, for false-color rendering.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Colormap {
+    /// Black to white.
+    Grayscale,
+    /// Blue through cyan, yellow and red.
+    Jet,
+    /// Perceptually uniform dark purple through teal to yellow.
+    Viridis,
+}
+
+impl Colormap {
+    /// Color for `t`, clamped to `[0, 1]`.
+    pub fn color(self, t: f64) -> [u8; 3] {
+        let t = t.clamp(0.0, 1.0);
+        let channel = |v: f64| (v.clamp(0.0, 1.0) * 255.0).round() as u8;
+        match self {
+            Colormap::Grayscale => [channel(t); 3],
+            Colormap::Jet => [3.0, 2.0, 1.0].map(|center| channel(1.5 - (4.0 * t - center).abs())),
+            Colormap::Viridis => {
+                const STOPS: [[f64; 3]; 5] = [
+                    [0.267, 0.005, 0.329],
+                    [0.229, 0.322, 0.546],
+                    [0.128, 0.567, 0.551],
+                    [0.369, 0.789, 0.383],
+                    [0.993, 0.906, 0.144],
+                ];
+                let scaled = t * (STOPS.len() - 1) as f64;
+                let i = (scaled as usize).min(STOPS.len() - 2);
+                let f = scaled - i as f64;
+                [0, 1, 2].map(|c| channel(STOPS[i][c] + (STOPS[i + 1][c] - STOPS[i][c]) * f))
+            }
+        }
+    }
+}
+
+impl TablePointCloud {
+    /// Writes `red`/`green`/`blue` `u8` columns by mapping the numeric
+    /// `channel` through `colormap`, stretched over the column's min/max.
+    ///
+    /// A constant column maps to the middle of the colormap; nulls and NaNs
+    /// are colored gray. Existing color columns are replaced.
+    pub fn colorize_by_scalar(
+        &mut self,
+        channel: &str,
+        colormap: Colormap,
+    ) -> Result<(), PolarsError> {
+        let values = self.column_f64(channel)?;
+        let finite = values.iter().filter(|v| !v.is_nan());
+        let lo = finite.clone().cloned().fold(f64::INFINITY, f64::min);
+        let hi = finite.cloned().fold(f64::NEG_INFINITY, f64::max);
+        let colors: Vec<[u8; 3]> = values
+            .iter()
+            .map(|&v| {
+                if v.is_nan() {
+                    UNMAPPED_COLOR
+                } else if hi > lo {
+                    colormap.color((v - lo) / (hi - lo))
+                } else {
+                    colormap.color(0.5)
+                }
+            })
+            .collect();
+        self.set_colors(&colors)
+    }
+
+    /// Writes `red`/`green`/`blue` `u8` columns from each point's integer
+    /// label in `label_column`.
+    ///
+    /// Labels absent from `palette`, and null labels, are colored gray.
+    /// Existing color columns are replaced.
+    pub fn colorize_by_labels(
+        &mut self,
+        label_column: &str,
+        palette: &HashMap<i64, [u8; 3]>,
+    ) -> Result<(), PolarsError> {
+        let labels = self.data().column(label_column)?.cast(&DataType::Int64)?;
+        let colors: Vec<[u8; 3]> = labels
+            .i64()?
+            .iter()
+            .map(|label| {
+                label
+                    .and_then(|label| palette.get(&label).copied())
+                    .unwrap_or(UNMAPPED_COLOR)
+            })
+            .collect();
+        self.set_colors(&colors)
+    }
+
+    /// Adds `hue` (degrees in `[0, 360)`), `saturation` and `value` (both
+    /// in `[0, 1]`) columns computed from `red`/`green`/`blue` on the 0-255
+    /// scale. Gray points get hue 0 and nulls propagate. Errors without
+    /// color columns.
+    pub fn rgb_to_hsv(&self) -> Result<Self, PolarsError> {
+        let [r, g, b] = COLOR_COLUMNS.map(|name| col(name).cast(DataType::Float64) / lit(255.0));
+        let max = max_horizontal([r.clone(), g.clone(), b.clone()])?;
+        let delta = max.clone() - min_horizontal([r.clone(), g.clone(), b.clone()])?;
+        let red_sector = (g.clone() - b.clone()) / delta.clone();
+        let hue = when(delta.clone().eq(lit(0.0)))
+            .then(lit(0.0))
+            .when(max.clone().eq(r.clone()))
+            .then(
+                when(red_sector.clone().lt(lit(0.0)))
+                    .then(red_sector.clone() + lit(6.0))
+                    .otherwise(red_sector),
+            )
+            .when(max.clone().eq(g.clone()))
+            .then((b - r.clone()) / delta.clone() + lit(2.0))
+            .otherwise((r - g) / delta.clone() + lit(4.0))
+            * lit(60.0);
+        let saturation = when(max.clone().eq(lit(0.0)))
+            .then(lit(0.0))
+            .otherwise(delta / max.clone());
+        self.with_derived_columns([
+            hue.alias("hue"),
+            saturation.alias("saturation"),
+            max.alias("value"),
+        ])
+    }
+
+    /// Inverse of [`TablePointCloud::rgb_to_hsv`]: writes `u8`
+    /// `red`/`green`/`blue` columns from `hue`/`saturation`/`value`.
+    pub fn hsv_to_rgb(&self) -> Result<Self, PolarsError> {
+        let [h, s, v] =
+            ["hue", "saturation", "value"].map(|name| col(name).cast(DataType::Float64));
+        // Hue in sixths of a turn, wrapped into [0, 6).
+        let h = (h.clone() - (h / lit(360.0)).floor() * lit(360.0)) / lit(60.0);
+        let sector = h.clone().floor();
+        let c = v.clone() * s;
+        let x = c.clone()
+            * (lit(1.0) - (h.clone() - (h / lit(2.0)).floor() * lit(2.0) - lit(1.0)).abs());
+        let m = v - c.clone();
+        // Each channel takes `c` in two sectors, `x` in two others and 0 in
+        // the remaining two.
+        let channel = |name: &str, c_sectors: [f64; 2], x_sectors: [f64; 2]| {
+            let within = |[a, b]: [f64; 2]| sector.clone().eq(lit(a)).or(sector.clone().eq(lit(b)));
+            let base = when(within(c_sectors))
+                .then(c.clone())
+                .when(within(x_sectors))
+                .then(x.clone())
+                .otherwise(lit(0.0));
+            ((base + m.clone()).clip(lit(0.0), lit(1.0)) * lit(255.0))
+                .round(0, RoundMode::HalfAwayFromZero)
+                .cast(DataType::UInt8)
+                .alias(name)
+        };
+        self.with_derived_columns([
+            channel("red", [0.0, 5.0], [1.0, 4.0]),
+            channel("green", [1.0, 2.0], [0.0, 3.0]),
+            channel("blue", [3.0, 4.0], [2.0, 5.0]),
+        ])
+    }
+
+    /// Evaluates `exprs` over the frame and stores each result as the
+    /// attribute named by its alias.
+    fn with_derived_columns<const N: usize>(&self, exprs: [Expr; N]) -> Result<Self, PolarsError> {
+        let values = self.data().clone().lazy().select(exprs).collect()?;
+        let mut out = self.clone();
+        for column in values.columns() {
+            out.set_attribute(column.as_materialized_series().clone())?;
+        }
+        Ok(out)
+    }
+
+    fn set_colors(&mut self, colors: &[[u8; 3]]) -> Result<(), PolarsError> {
+        for (channel, name) in COLOR_COLUMNS.into_iter().enumerate() {
+            let values: Vec<u8> = colors.iter().map(|c| c[channel]).collect();
+            self.set_attribute(Series::new(name.into(), values))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn colors(cloud: &TablePointCloud) -> Vec<[u8; 3]> {
+        let [r, g, b] = COLOR_COLUMNS.map(|name| cloud.column_f64(name).unwrap());
+        (0..cloud.len())
+            .map(|i| [r[i], g[i], b[i]].map(|c| c as u8))
+            .collect()
+    }
+
+    #[test]
+    fn labels_map_to_palette_colors() {
+        let mut cloud =
+            TablePointCloud::from_xyz(vec![0.0; 4], vec![0.0; 4], vec![0.0; 4]).unwrap();
+        cloud
+            .set_attribute(Series::new("label".into(), vec![2i32, 6, 2, 9]))
+            .unwrap();
+        let palette = HashMap::from([(2, [139, 69, 19]), (6, [255, 0, 0])]);
+        cloud.colorize_by_labels("label", &palette).unwrap();
+
+        for name in COLOR_COLUMNS {
+            assert_eq!(cloud.data().column(name).unwrap().dtype(), &DataType::UInt8);
+        }
+        assert_eq!(
+            colors(&cloud),
+            [[139, 69, 19], [255, 0, 0], [139, 69, 19], UNMAPPED_COLOR]
+        );
+        assert!(cloud.colorize_by_labels("class", &palette).is_err());
+    }
+
+    fn colored(colors: &[[u8; 3]]) -> TablePointCloud {
+        let n = colors.len();
+        let mut cloud =
+            TablePointCloud::from_xyz(vec![0.0; n], vec![0.0; n], vec![0.0; n]).unwrap();
+        cloud.set_colors(colors).unwrap();
+        cloud
+    }
+
+    #[test]
+    fn hsv_of_primaries_and_round_trip() {
+        let palette = [
+            [255, 0, 0],
+            [0, 255, 0],
+            [0, 0, 255],
+            [128, 128, 128],
+            [255, 0, 128],
+            [12, 200, 77],
+            [250, 240, 3],
+            [0, 0, 0],
+        ];
+        let hsv = colored(&palette).rgb_to_hsv().unwrap();
+        let hue = hsv.column_f64("hue").unwrap();
+        assert_eq!(hue[..4], [0.0, 120.0, 240.0, 0.0]);
+        assert!((hue[4] - 329.882_352_941).abs() < 1e-6, "{}", hue[4]);
+        assert_eq!(
+            hsv.column_f64("saturation").unwrap()[..4],
+            [1.0, 1.0, 1.0, 0.0]
+        );
+        assert_eq!(hsv.column_f64("value").unwrap()[..3], [1.0, 1.0, 1.0]);
+
+        let n = palette.len();
+        let mut back = TablePointCloud::from_xyz(vec![0.0; n], vec![0.0; n], vec![0.0; n]).unwrap();
+        for name in ["hue", "saturation", "value"] {
+            let column = hsv.data().column(name).unwrap();
+            back.set_attribute(column.as_materialized_series().clone())
+                .unwrap();
+        }
+        assert_eq!(colors(&back.hsv_to_rgb().unwrap()), palette);
+
+        let plain = TablePointCloud::from_xyz(vec![0.0], vec![0.0], vec![0.0]).unwrap();
+        assert!(plain.rgb_to_hsv().is_err());
+        assert!(plain.hsv_to_rgb().is_err());
+    }
+}
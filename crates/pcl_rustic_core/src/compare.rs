@@ -0,0 +1,446 @@
+use std::collections::HashMap;
+
+use ndarray::Array2;
+use polars::prelude::*;
+
+use crate::cloud::COORDINATE_COLUMNS;
+use crate::kdtree::KdTree;
+use crate::TablePointCloud;
+
+/// Point budget used by [`TablePointCloud::distance_matrix`]: 4096 points,
+/// a 128 MiB matrix.
+pub const DEFAULT_MAX_DISTANCE_MATRIX_POINTS: usize = 1 << 12;
+
+impl TablePointCloud {
+    /// Symmetric Hausdorff distance between two clouds.
+    ///
+    /// This is the larger of the two directed distances, each being the
+    /// worst nearest-neighbor distance from one cloud to the other, so a
+    /// single far outlier in either cloud dominates the result. The clouds
+    /// are compared in global coordinates, so their origins may differ.
+    pub fn hausdorff_distance(&self, other: &TablePointCloud) -> Result<f64, PolarsError> {
+        self.ensure_non_empty("Hausdorff distance")?;
+        other.ensure_non_empty("Hausdorff distance")?;
+        let (a, b) = (self.xyz()?, xyz_relative_to(other, self.origin())?);
+        let (tree_a, tree_b) = (KdTree::new(&a), KdTree::new(&b));
+        Ok(directed_hausdorff(&a, &tree_b).max(directed_hausdorff(&b, &tree_a)))
+    }
+
+    /// Fraction of points in `self` with at least one point of `other`
+    /// within `radius`.
+    ///
+    /// 1.0 means `self` is fully covered by `other`; an empty `other` gives
+    /// 0.0. Useful as a sanity check on registration results. `other` is
+    /// relocated to this cloud's origin first.
+    pub fn overlap_ratio(&self, other: &TablePointCloud, radius: f64) -> Result<f64, PolarsError> {
+        if !(radius.is_finite() && radius >= 0.0) {
+            polars_bail!(InvalidOperation: "overlap radius must be non-negative and finite, got {}", radius);
+        }
+        self.ensure_non_empty("overlap ratio")?;
+        let tree = KdTree::new(&xyz_relative_to(other, self.origin())?);
+        let covered = self
+            .xyz()?
+            .into_iter()
+            .filter(|&p| tree.nearest(p).is_some_and(|(_, d)| d <= radius))
+            .count();
+        Ok(covered as f64 / self.len() as f64)
+    }
+
+    /// Points of `self` farther than `threshold` from every point of
+    /// `reference`, i.e. geometry that is new or has moved since the
+    /// reference was captured.
+    ///
+    /// The complement of [`TablePointCloud::overlap_ratio`]'s coverage test;
+    /// against an empty reference every point counts as changed. The
+    /// reference is relocated to this cloud's origin first.
+    pub fn changed_points(
+        &self,
+        reference: &TablePointCloud,
+        threshold: f64,
+    ) -> Result<Self, PolarsError> {
+        if !(threshold.is_finite() && threshold >= 0.0) {
+            polars_bail!(InvalidOperation: "change threshold must be non-negative and finite, got {}", threshold);
+        }
+        let tree = KdTree::new(&xyz_relative_to(reference, self.origin())?);
+        let mask: BooleanChunked = self
+            .xyz()?
+            .into_iter()
+            .map(|p| tree.nearest(p).is_none_or(|(_, d)| d > threshold))
+            .collect();
+        self.filter(&mask)
+    }
+
+    /// Pairwise Euclidean distances as a symmetric `(N, N)` array with a
+    /// zero diagonal, for prototyping clustering on small clouds.
+    ///
+    /// Errors when the cloud has more than
+    /// [`DEFAULT_MAX_DISTANCE_MATRIX_POINTS`] points, since the matrix grows
+    /// quadratically.
+    pub fn distance_matrix(&self) -> Result<Array2<f64>, PolarsError> {
+        self.distance_matrix_with_limit(DEFAULT_MAX_DISTANCE_MATRIX_POINTS)
+    }
+
+    /// [`TablePointCloud::distance_matrix`] with an explicit cap on the
+    /// number of points.
+    pub fn distance_matrix_with_limit(
+        &self,
+        max_points: usize,
+    ) -> Result<Array2<f64>, PolarsError> {
+        if self.len() > max_points {
+            polars_bail!(ComputeError: "distance matrix of {} points exceeds the limit of {} points", self.len(), max_points);
+        }
+        let points = self.xyz()?;
+        let n = points.len();
+        let mut matrix = Array2::zeros((n, n));
+        for i in 0..n {
+            for j in i + 1..n {
+                let d = (0..3)
+                    .map(|axis| (points[i][axis] - points[j][axis]).powi(2))
+                    .sum::<f64>()
+                    .sqrt();
+                matrix[(i, j)] = d;
+                matrix[(j, i)] = d;
+            }
+        }
+        Ok(matrix)
+    }
+
+    /// Returns `true` if both clouds have the same origin, the same columns
+    /// (names and dtypes), the same number of points, and every numeric
+    /// value differs by at most `tol`.
+    ///
+    /// Rows are compared in order. NaNs only match NaNs and nulls only match
+    /// nulls; non-numeric columns must be exactly equal.
+    pub fn approx_eq(&self, other: &TablePointCloud, tol: f64) -> bool {
+        if self.origin() != other.origin()
+            || self.len() != other.len()
+            || self.data().width() != other.data().width()
+        {
+            return false;
+        }
+        self.data().columns().iter().all(|column| {
+            let Ok(theirs) = other.data().column(column.name()) else {
+                return false;
+            };
+            if column.dtype() != theirs.dtype() {
+                return false;
+            }
+            if !column.dtype().is_primitive_numeric() {
+                return column.equals_missing(theirs);
+            }
+            let (Ok(ours), Ok(theirs)) = (
+                column.cast(&DataType::Float64),
+                theirs.cast(&DataType::Float64),
+            ) else {
+                return false;
+            };
+            let (Ok(a), Ok(b)) = (ours.f64(), theirs.f64()) else {
+                return false;
+            };
+            let close = a.iter().zip(b.iter()).all(|pair| match pair {
+                (Some(a), Some(b)) => (a.is_nan() && b.is_nan()) || (a - b).abs() <= tol,
+                (None, None) => true,
+                _ => false,
+            });
+            close
+        })
+    }
+
+    /// Drops points lying within `tol` of an earlier point, keeping the
+    /// first occurrence.
+    ///
+    /// Kept points are hashed into voxels of side `tol`, so each point only
+    /// needs to be checked against the kept points in its own and the 26
+    /// adjacent voxels. A `tol` of zero removes exact duplicates only.
+    pub fn remove_duplicates(&self, tol: f64) -> Result<Self, PolarsError> {
+        if !(tol.is_finite() && tol >= 0.0) {
+            polars_bail!(InvalidOperation: "duplicate tolerance must be non-negative and finite, got {}", tol);
+        }
+        let points = self.xyz()?;
+        let cell = |p: &[f64; 3]| -> [i64; 3] {
+            if tol == 0.0 {
+                // `-0.0` and `0.0` are equal, so they must share a cell.
+                p.map(|v| if v == 0.0 { 0.0f64 } else { v }.to_bits() as i64)
+            } else {
+                p.map(|v| (v / tol).floor() as i64)
+            }
+        };
+        let reach: i64 = if tol == 0.0 { 0 } else { 1 };
+        let mut kept_by_cell: HashMap<[i64; 3], Vec<usize>> = HashMap::new();
+        let mut kept = Vec::new();
+        for (i, p) in points.iter().enumerate() {
+            let [cx, cy, cz] = cell(p);
+            let mut duplicate = false;
+            'search: for dx in -reach..=reach {
+                for dy in -reach..=reach {
+                    for dz in -reach..=reach {
+                        let Some(others) = kept_by_cell.get(&[cx + dx, cy + dy, cz + dz]) else {
+                            continue;
+                        };
+                        if others.iter().any(|&j| within(p, &points[j], tol)) {
+                            duplicate = true;
+                            break 'search;
+                        }
+                    }
+                }
+            }
+            if !duplicate {
+                kept_by_cell.entry([cx, cy, cz]).or_default().push(i);
+                kept.push(i);
+            }
+        }
+        self.take(&kept)
+    }
+
+    /// Copies attribute `columns` from `source` onto each point of `self`,
+    /// taking the values of its nearest neighbor in `source`.
+    ///
+    /// This propagates per-point labels computed on one cloud (for example a
+    /// downsampled copy) back onto a spatially aligned cloud. Existing
+    /// columns of the same name are replaced and the source dtypes are kept.
+    /// `source` is relocated to this cloud's origin before matching.
+    pub fn transfer_attributes(
+        &self,
+        source: &TablePointCloud,
+        columns: &[&str],
+    ) -> Result<Self, PolarsError> {
+        for name in columns {
+            if COORDINATE_COLUMNS.contains(name) {
+                polars_bail!(InvalidOperation: "cannot transfer coordinate column '{}'", name);
+            }
+            source.data().column(name)?;
+        }
+        if source.is_empty() && !self.is_empty() {
+            polars_bail!(ComputeError: "cannot transfer attributes from an empty cloud");
+        }
+        let tree = KdTree::new(&xyz_relative_to(source, self.origin())?);
+        let nearest: Vec<IdxSize> = self
+            .xyz()?
+            .into_iter()
+            .filter_map(|p| tree.nearest(p))
+            .map(|(index, _)| index as IdxSize)
+            .collect();
+
+        let mut out = self.clone();
+        for name in columns {
+            let values = source.data().column(name)?.take_slice(&nearest)?;
+            out.set_attribute(values.take_materialized_series())?;
+        }
+        Ok(out)
+    }
+}
+
+/// `cloud`'s coordinates relative to `origin`, as [`TablePointCloud::concat`]
+/// relocates its inputs.
+fn xyz_relative_to(
+    cloud: &TablePointCloud,
+    origin: [f64; 3],
+) -> Result<Vec<[f64; 3]>, PolarsError> {
+    if cloud.origin() == origin {
+        cloud.xyz()
+    } else {
+        cloud.relocate_origin(origin)?.xyz()
+    }
+}
+
+fn within(a: &[f64; 3], b: &[f64; 3], tol: f64) -> bool {
+    let d2 = (a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2) + (a[2] - b[2]).powi(2);
+    d2 <= tol * tol
+}
+
+fn directed_hausdorff(points: &[[f64; 3]], tree: &KdTree) -> f64 {
+    points
+        .iter()
+        .filter_map(|&p| tree.nearest(p))
+        .map(|(_, distance)| distance)
+        .fold(0.0, f64::max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cloud(points: &[[f64; 3]]) -> TablePointCloud {
+        TablePointCloud::from_point_array(points).unwrap()
+    }
+
+    fn unit_square() -> Vec<[f64; 3]> {
+        vec![
+            [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [1.0, 1.0, 0.0],
+        ]
+    }
+
+    #[test]
+    fn hausdorff_is_dominated_by_outlier() {
+        let reference = cloud(&unit_square());
+        let mut points = unit_square();
+        points.push([1.0, 1.0, 10.0]);
+        let with_outlier = cloud(&points);
+
+        assert_eq!(reference.hausdorff_distance(&reference).unwrap(), 0.0);
+        assert_eq!(reference.hausdorff_distance(&with_outlier).unwrap(), 10.0);
+        assert_eq!(with_outlier.hausdorff_distance(&reference).unwrap(), 10.0);
+        assert!(reference.hausdorff_distance(&cloud(&[])).is_err());
+    }
+
+    #[test]
+    fn transfer_copies_nearest_label() {
+        let mut sparse = cloud(&[[0.0, 0.0, 0.0], [10.0, 0.0, 0.0]]);
+        sparse
+            .set_attribute(Series::new("label".into(), vec![1i64, 2]))
+            .unwrap();
+        let dense = cloud(&[
+            [-0.5, 0.0, 0.0],
+            [1.0, 0.5, 0.0],
+            [4.9, 0.0, 0.0],
+            [5.1, 0.0, 0.0],
+            [11.0, 0.0, 0.0],
+        ]);
+        let labeled = dense.transfer_attributes(&sparse, &["label"]).unwrap();
+        let labels: Vec<Option<i64>> = labeled
+            .data()
+            .column("label")
+            .unwrap()
+            .i64()
+            .unwrap()
+            .iter()
+            .collect();
+        assert_eq!(labels, [1, 1, 1, 2, 2].map(Some));
+        assert!(dense.transfer_attributes(&sparse, &["missing"]).is_err());
+    }
+
+    #[test]
+    fn approx_eq_within_tolerance() {
+        let mut a = cloud(&unit_square());
+        a.set_attribute(Series::new(
+            "intensity".into(),
+            vec![1.0, f64::NAN, 3.0, 4.0],
+        ))
+        .unwrap();
+        let mut b = a.translate([1e-6, 0.0, 0.0]).unwrap();
+        assert!(a.approx_eq(&a, 0.0));
+        assert!(a.approx_eq(&b, 1e-5));
+        assert!(!a.approx_eq(&b, 1e-7));
+
+        b.set_attribute(Series::new("intensity".into(), vec![1.0, 2.0, 3.0, 4.0]))
+            .unwrap();
+        assert!(!a.approx_eq(&b, 1e-5), "NaN only matches NaN");
+        assert!(!a.approx_eq(&cloud(&unit_square()), 1e-5), "schemas differ");
+
+        let mut moved = a.clone();
+        moved.set_origin([1.0, 0.0, 0.0]);
+        assert!(!a.approx_eq(&moved, 1e-5), "origins differ");
+    }
+
+    #[test]
+    fn remove_duplicates_exact_and_near() {
+        let points = [
+            [0.0, 0.0, 0.0],
+            [-0.0, 0.0, -0.0],
+            [1.0, 1.0, 1.0],
+            [1.0, 1.0, 1.0],
+            [1.0, 1.0, 1.004],
+            [2.0, 0.0, 0.0],
+        ];
+        let exact = cloud(&points).remove_duplicates(0.0).unwrap();
+        assert_eq!(
+            exact.xyz().unwrap(),
+            [
+                [0.0, 0.0, 0.0],
+                [1.0, 1.0, 1.0],
+                [1.0, 1.0, 1.004],
+                [2.0, 0.0, 0.0]
+            ]
+        );
+
+        let near = cloud(&points).remove_duplicates(0.01).unwrap();
+        assert_eq!(
+            near.xyz().unwrap(),
+            [[0.0, 0.0, 0.0], [1.0, 1.0, 1.0], [2.0, 0.0, 0.0]]
+        );
+    }
+
+    #[test]
+    fn overlap_of_identical_and_disjoint_clouds() {
+        let a = cloud(&unit_square());
+        let far = a.translate([100.0, 0.0, 0.0]).unwrap();
+        assert_eq!(a.overlap_ratio(&a, 0.01).unwrap(), 1.0);
+        assert_eq!(a.overlap_ratio(&far, 0.01).unwrap(), 0.0);
+        assert_eq!(
+            a.overlap_ratio(&cloud(&[[0.0, 0.0, 0.0]]), 0.01).unwrap(),
+            0.25
+        );
+        assert_eq!(a.overlap_ratio(&cloud(&[]), 0.01).unwrap(), 0.0);
+    }
+
+    #[test]
+    fn detects_an_added_object() {
+        // A 10 x 10 ground grid, rescanned with small noise and a box
+        // standing on it.
+        let ground: Vec<[f64; 3]> = (0..100)
+            .map(|i| [(i % 10) as f64, (i / 10) as f64, 0.0])
+            .collect();
+        let mut scan: Vec<[f64; 3]> = ground
+            .iter()
+            .enumerate()
+            .map(|(i, p)| [p[0], p[1], if i % 2 == 0 { 0.02 } else { -0.02 }])
+            .collect();
+        let object = [[4.5, 4.5, 1.0], [4.5, 5.0, 1.5], [5.0, 4.5, 2.0]];
+        scan.extend(object);
+
+        let changed = cloud(&scan).changed_points(&cloud(&ground), 0.1).unwrap();
+        assert_eq!(changed.xyz().unwrap(), object);
+        assert!(cloud(&ground)
+            .changed_points(&cloud(&ground), 0.0)
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            cloud(&ground)
+                .changed_points(&cloud(&[]), 0.1)
+                .unwrap()
+                .len(),
+            100
+        );
+        assert!(cloud(&ground)
+            .changed_points(&cloud(&ground), -1.0)
+            .is_err());
+    }
+
+    #[test]
+    fn comparisons_use_global_coordinates() {
+        let square = cloud(&unit_square());
+        let relocated = square.relocate_origin([500.0, -20.0, 3.0]).unwrap();
+        assert_eq!(square.hausdorff_distance(&relocated).unwrap(), 0.0);
+        assert_eq!(relocated.overlap_ratio(&square, 1e-9).unwrap(), 1.0);
+        assert!(relocated.changed_points(&square, 1e-9).unwrap().is_empty());
+
+        let mut labeled = square.clone();
+        labeled
+            .set_attribute(Series::new("label".into(), vec![1i64, 2, 3, 4]))
+            .unwrap();
+        let transferred = relocated.transfer_attributes(&labeled, &["label"]).unwrap();
+        assert_eq!(
+            transferred.attribute("label").unwrap(),
+            [1.0, 2.0, 3.0, 4.0]
+        );
+    }
+
+    #[test]
+    fn distance_matrix_of_three_points() {
+        let points = cloud(&[[0.0, 0.0, 0.0], [3.0, 4.0, 0.0], [0.0, 0.0, 2.0]]);
+        let matrix = points.distance_matrix().unwrap();
+        assert_eq!(matrix.dim(), (3, 3));
+        assert_eq!(matrix, matrix.t());
+        assert!((0..3).all(|i| matrix[(i, i)] == 0.0));
+        assert_eq!(matrix[(0, 1)], 5.0);
+        assert_eq!(matrix[(0, 2)], 2.0);
+        assert_eq!(matrix[(1, 2)], 29.0f64.sqrt());
+
+        assert!(points.distance_matrix_with_limit(2).is_err());
+        assert_eq!(points.distance_matrix_with_limit(3).unwrap(), matrix);
+        assert_eq!(cloud(&[]).distance_matrix().unwrap().dim(), (0, 0));
+    }
+}
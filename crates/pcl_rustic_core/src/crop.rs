@@ -0,0 +1,281 @@
+use nalgebra::Matrix4;
+use polars::prelude::*;
+
+use crate::TablePointCloud;
+
+impl TablePointCloud {
+    /// Points inside the axis-aligned box `[min, max]`, boundary included.
+    pub fn crop_box(&self, min: [f64; 3], max: [f64; 3]) -> Result<Self, PolarsError> {
+        if (0..3).any(|axis| min[axis].is_nan() || max[axis].is_nan() || min[axis] > max[axis]) {
+            polars_bail!(InvalidOperation: "box must satisfy min <= max, got {:?} and {:?}", min, max);
+        }
+        let inside = ["x", "y", "z"]
+            .into_iter()
+            .zip(min.into_iter().zip(max))
+            .map(|(name, (lo, hi))| col(name).gt_eq(lit(lo)).and(col(name).lt_eq(lit(hi))))
+            .reduce(Expr::and)
+            .expect("three axes");
+        self.crop(inside)
+    }
+
+    /// Points within `radius` of `center`, boundary included.
+    pub fn crop_sphere(&self, center: [f64; 3], radius: f64) -> Result<Self, PolarsError> {
+        check_radius(radius)?;
+        let [dx, dy, dz] = offsets(center);
+        let dist2 = dx.clone() * dx + dy.clone() * dy + dz.clone() * dz;
+        self.crop(dist2.lt_eq(lit(radius * radius)))
+    }
+
+    /// Points inside a finite cylinder, boundary included.
+    ///
+    /// The cylinder's axis starts at `axis_point` and runs `height` along
+    /// `axis_dir` (any non-zero length); points are kept when their distance
+    /// to the axis is at most `radius` and their projection onto it falls
+    /// within `[0, height]`.
+    pub fn crop_cylinder(
+        &self,
+        axis_point: [f64; 3],
+        axis_dir: [f64; 3],
+        radius: f64,
+        height: f64,
+    ) -> Result<Self, PolarsError> {
+        check_radius(radius)?;
+        if height.is_nan() || height < 0.0 {
+            polars_bail!(InvalidOperation: "cylinder height must be non-negative, got {}", height);
+        }
+        let norm = axis_dir.iter().map(|c| c * c).sum::<f64>().sqrt();
+        if !(norm.is_finite() && norm > 0.0) {
+            polars_bail!(InvalidOperation: "cylinder axis must be non-zero and finite, got {:?}", axis_dir);
+        }
+        let [ux, uy, uz] = axis_dir.map(|c| c / norm);
+        let [dx, dy, dz] = offsets(axis_point);
+        let along = dx.clone() * lit(ux) + dy.clone() * lit(uy) + dz.clone() * lit(uz);
+        let dist2 = dx.clone() * dx + dy.clone() * dy + dz.clone() * dz;
+        let radial2 = dist2 - along.clone() * along.clone();
+        self.crop(
+            radial2
+                .lt_eq(lit(radius * radius))
+                .and(along.clone().gt_eq(lit(0.0)))
+                .and(along.lt_eq(lit(height))),
+        )
+    }
+
+    /// Points with `z_min <= z <= z_max`. With `flatten`, their `z` is set
+    /// to the band's midpoint, giving a planar cloud (e.g. for floor
+    /// plans). Attributes are kept.
+    pub fn slice_z(&self, z_min: f64, z_max: f64, flatten: bool) -> Result<Self, PolarsError> {
+        if z_min.is_nan() || z_max.is_nan() || z_min > z_max {
+            polars_bail!(InvalidOperation: "z band must satisfy z_min <= z_max, got [{}, {}]", z_min, z_max);
+        }
+        let band = self.crop(col("z").gt_eq(lit(z_min)).and(col("z").lt_eq(lit(z_max))))?;
+        if !flatten {
+            return Ok(band);
+        }
+        let mid = z_min + (z_max - z_min) / 2.0;
+        band.with_coordinates(band.x()?, band.y()?, vec![mid; band.len()])
+    }
+
+    /// Points inside the view frustum of a combined view-projection matrix:
+    /// those whose clip coordinates `view_proj * (x, y, z, 1)` satisfy
+    /// `-w <= x, y, z <= w`, boundary included. This is the OpenGL clip
+    /// volume; points behind the camera (`w < 0`) are dropped.
+    pub fn crop_frustum(&self, view_proj: &Matrix4<f64>) -> Result<Self, PolarsError> {
+        if view_proj.iter().any(|v| !v.is_finite()) {
+            polars_bail!(InvalidOperation: "view-projection matrix has non-finite entries");
+        }
+        let clip = |row: usize| {
+            col("x") * lit(view_proj[(row, 0)])
+                + col("y") * lit(view_proj[(row, 1)])
+                + col("z") * lit(view_proj[(row, 2)])
+                + lit(view_proj[(row, 3)])
+        };
+        let w = clip(3);
+        let inside = (0..3)
+            .map(|row| {
+                let c = clip(row);
+                c.clone().gt_eq(-w.clone()).and(c.lt_eq(w.clone()))
+            })
+            .reduce(Expr::and)
+            .expect("three clip axes");
+        self.crop(inside)
+    }
+
+    /// Drops likely noise returns: points with `intensity` below
+    /// `min_intensity` that are also farther than `max_range` from the
+    /// local origin (the sensor). Near returns are kept whatever their
+    /// intensity, as are points with a null intensity. Errors without a
+    /// numeric `intensity` column.
+    pub fn filter_by_snr(&self, min_intensity: f64, max_range: f64) -> Result<Self, PolarsError> {
+        if min_intensity.is_nan() {
+            polars_bail!(InvalidOperation: "minimum intensity must not be NaN");
+        }
+        if max_range.is_nan() || max_range < 0.0 {
+            polars_bail!(InvalidOperation: "maximum range must be non-negative, got {}", max_range);
+        }
+        let intensity = self.data().column("intensity")?;
+        if !intensity.dtype().is_primitive_numeric() {
+            polars_bail!(SchemaMismatch: "column 'intensity' is {}, not numeric", intensity.dtype());
+        }
+        let [dx, dy, dz] = offsets([0.0; 3]);
+        let range2 = dx.clone() * dx + dy.clone() * dy + dz.clone() * dz;
+        let noise = col("intensity")
+            .cast(DataType::Float64)
+            .lt(lit(min_intensity))
+            .and(range2.gt(lit(max_range * max_range)));
+        self.crop(noise.not().fill_null(lit(true)))
+    }
+
+    /// Keeps the rows where the boolean expression `inside` holds.
+    fn crop(&self, inside: Expr) -> Result<Self, PolarsError> {
+        let mask = self
+            .data()
+            .clone()
+            .lazy()
+            .select([inside.alias("inside")])
+            .collect()?;
+        self.filter(mask.column("inside")?.bool()?)
+    }
+}
+
+fn check_radius(radius: f64) -> Result<(), PolarsError> {
+    if radius.is_nan() || radius < 0.0 {
+        polars_bail!(InvalidOperation: "crop radius must be non-negative, got {}", radius);
+    }
+    Ok(())
+}
+
+/// Per-axis expressions for the offset of each point from `origin`.
+fn offsets(origin: [f64; 3]) -> [Expr; 3] {
+    [
+        col("x") - lit(origin[0]),
+        col("y") - lit(origin[1]),
+        col("z") - lit(origin[2]),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cloud(points: &[[f64; 3]]) -> TablePointCloud {
+        TablePointCloud::from_point_array(points).unwrap()
+    }
+
+    #[test]
+    fn sphere_and_cylinder_crops() {
+        let cloud = cloud(&[
+            [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [0.0, 0.0, 1.5],
+            [0.5, 0.5, 3.0],
+            [2.0, 0.0, 1.0],
+            [0.0, 0.0, -1.0],
+        ]);
+
+        let sphere = cloud.crop_sphere([0.0; 3], 1.0).unwrap();
+        assert_eq!(
+            sphere.xyz().unwrap(),
+            [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 0.0, -1.0]]
+        );
+
+        // A vertical pole of radius 1 from z = 0 to z = 2: the point at
+        // x = 2 is beyond the radius and the others above or below it are
+        // outside the height.
+        let pole = cloud
+            .crop_cylinder([0.0; 3], [0.0, 0.0, 4.0], 1.0, 2.0)
+            .unwrap();
+        assert_eq!(
+            pole.xyz().unwrap(),
+            [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 0.0, 1.5]]
+        );
+
+        assert!(cloud.crop_sphere([0.0; 3], -1.0).is_err());
+        assert!(cloud
+            .crop_cylinder([0.0; 3], [0.0, 0.0, 1.0], -1.0, 2.0)
+            .is_err());
+    }
+
+    #[test]
+    fn z_slice_keeps_and_flattens_the_band() {
+        let mut floors = cloud(&[
+            [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.1],
+            [0.0, 1.0, 1.2],
+            [1.0, 1.0, 1.4],
+            [2.0, 1.0, 1.6],
+            [0.0, 0.0, 3.0],
+        ]);
+        floors
+            .set_attribute(Series::new("label".into(), vec![1i32, 1, 2, 2, 2, 3]))
+            .unwrap();
+
+        let band = floors.slice_z(1.0, 2.0, false).unwrap();
+        assert_eq!(band.z().unwrap(), [1.2, 1.4, 1.6]);
+        let flat = floors.slice_z(1.0, 2.0, true).unwrap();
+        assert_eq!(flat.z().unwrap(), [1.5; 3]);
+        assert_eq!(flat.x().unwrap(), [0.0, 1.0, 2.0]);
+        assert_eq!(flat.attribute("label").unwrap(), [2.0; 3]);
+
+        assert!(floors.slice_z(2.0, 1.0, true).is_err());
+    }
+
+    #[test]
+    fn orthographic_frustum_keeps_the_view_box() {
+        // Looking down -z at x in [-2, 2], y in [-1, 1], depth 1 to 10.
+        let ortho = nalgebra::Orthographic3::new(-2.0, 2.0, -1.0, 1.0, 1.0, 10.0);
+        let cloud = cloud(&[
+            [0.0, 0.0, -5.0],
+            [2.0, 0.0, -5.0],
+            [2.5, 0.0, -5.0],
+            [0.0, -1.2, -5.0],
+            [0.0, 0.0, -0.5],
+            [0.0, 0.0, -11.0],
+            [-2.0, 1.0, -10.0],
+            [0.0, 0.0, 5.0],
+        ]);
+        let kept = cloud.crop_frustum(ortho.as_matrix()).unwrap();
+        assert_eq!(
+            kept.xyz().unwrap(),
+            [[0.0, 0.0, -5.0], [2.0, 0.0, -5.0], [-2.0, 1.0, -10.0]]
+        );
+        let mut bad = *ortho.as_matrix();
+        bad[(0, 0)] = f64::NAN;
+        assert!(cloud.crop_frustum(&bad).is_err());
+    }
+
+    #[test]
+    fn snr_filter_drops_far_weak_returns() {
+        let mut cloud = cloud(&[
+            [1.0, 0.0, 0.0],
+            [2.0, 0.0, 0.0],
+            [30.0, 40.0, 0.0],
+            [0.0, 60.0, 0.0],
+            [0.0, 0.0, 80.0],
+        ]);
+        // Near strong, near weak, far strong, far weak, far unknown.
+        cloud
+            .set_attribute(Series::new(
+                "intensity".into(),
+                vec![Some(200u16), Some(3), Some(150), Some(4), None],
+            ))
+            .unwrap();
+        cloud.set_origin([500.0, 500.0, 0.0]);
+
+        let kept = cloud.filter_by_snr(10.0, 20.0).unwrap();
+        assert_eq!(
+            kept.xyz().unwrap(),
+            [
+                [1.0, 0.0, 0.0],
+                [2.0, 0.0, 0.0],
+                [30.0, 40.0, 0.0],
+                [0.0, 0.0, 80.0]
+            ]
+        );
+        // The weak return at range 60 is not beyond a 60 m limit.
+        assert_eq!(cloud.filter_by_snr(10.0, 60.0).unwrap().len(), 5);
+
+        let bare = TablePointCloud::from_xyz(vec![0.0], vec![0.0], vec![0.0]).unwrap();
+        assert!(bare.filter_by_snr(10.0, 20.0).is_err());
+        assert!(cloud.filter_by_snr(10.0, -1.0).is_err());
+    }
+}
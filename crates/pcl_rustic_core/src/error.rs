@@ -0,0 +1,18 @@
+use polars::prelude::PolarsError;
+use thiserror::Error;
+
+/// Errors raised by point cloud operations that are not plain polars failures.
+#[derive(Debug, Error)]
+pub enum PcdError {
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("polars error: {0}")]
+    Polars(#[from] PolarsError),
+    #[error("invalid input: {0}")]
+    InvalidInput(String),
+    #[error("malformed file: {0}")]
+    Format(String),
+    #[cfg(feature = "rerun")]
+    #[error("rerun error: {0}")]
+    Rerun(#[from] rerun::RecordingStreamError),
+}
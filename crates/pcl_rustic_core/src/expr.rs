@@ -0,0 +1,243 @@
+use std::ops::{Add, Div, Mul, Sub};
+
+use polars::prelude::*;
+
+use crate::cloud::COORDINATE_COLUMNS;
+use crate::TablePointCloud;
+
+/// A small arithmetic expression over columns and constants, evaluated by
+/// [`TablePointCloud::compute_column`].
+///
+/// Build it with [`ColumnExpr::col`] and [`ColumnExpr::lit`] and combine
+/// with `+`, `-`, `*`, `/` and [`ColumnExpr::pow`], e.g.
+/// `ColumnExpr::col("intensity") / ColumnExpr::col("range").pow(ColumnExpr::lit(2.0))`.
+/// Columns are read as `f64`, so integer channels mix freely with floats.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ColumnExpr {
+    Column(String),
+    Constant(f64),
+    Add(Box<ColumnExpr>, Box<ColumnExpr>),
+    Sub(Box<ColumnExpr>, Box<ColumnExpr>),
+    Mul(Box<ColumnExpr>, Box<ColumnExpr>),
+    Div(Box<ColumnExpr>, Box<ColumnExpr>),
+    Pow(Box<ColumnExpr>, Box<ColumnExpr>),
+}
+
+impl ColumnExpr {
+    pub fn col(name: &str) -> Self {
+        ColumnExpr::Column(name.to_string())
+    }
+
+    pub fn lit(value: f64) -> Self {
+        ColumnExpr::Constant(value)
+    }
+
+    /// `self` raised to the power `exponent`.
+    pub fn pow(self, exponent: ColumnExpr) -> Self {
+        ColumnExpr::Pow(Box::new(self), Box::new(exponent))
+    }
+
+    fn to_polars(&self) -> Expr {
+        match self {
+            ColumnExpr::Column(name) => col(name.as_str()).cast(DataType::Float64),
+            ColumnExpr::Constant(value) => lit(*value),
+            ColumnExpr::Add(a, b) => a.to_polars() + b.to_polars(),
+            ColumnExpr::Sub(a, b) => a.to_polars() - b.to_polars(),
+            ColumnExpr::Mul(a, b) => a.to_polars() * b.to_polars(),
+            ColumnExpr::Div(a, b) => a.to_polars() / b.to_polars(),
+            ColumnExpr::Pow(a, b) => a.to_polars().pow(b.to_polars()),
+        }
+    }
+}
+
+macro_rules! column_expr_op {
+    ($($trait:ident::$method:ident => $variant:ident),* $(,)?) => {
+        $(impl $trait for ColumnExpr {
+            type Output = ColumnExpr;
+
+            fn $method(self, rhs: ColumnExpr) -> ColumnExpr {
+                ColumnExpr::$variant(Box::new(self), Box::new(rhs))
+            }
+        })*
+    };
+}
+
+column_expr_op!(Add::add => Add, Sub::sub => Sub, Mul::mul => Mul, Div::div => Div);
+
+/// What [`TablePointCloud::threshold_column`] does with values past the
+/// threshold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThresholdMode {
+    /// Set values above the threshold to 0.
+    ZeroAbove,
+    /// Set values below the threshold to 0.
+    ZeroBelow,
+    /// Remove points whose value is above the threshold.
+    DropAbove,
+    /// Remove points whose value is below the threshold.
+    DropBelow,
+}
+
+impl TablePointCloud {
+    /// Evaluates `expr` per point and stores the result as the `f64`
+    /// attribute `name`, replacing any existing one.
+    ///
+    /// Arithmetic follows IEEE rules (division by zero gives an infinity or
+    /// NaN) and nulls propagate. Errors when `expr` names a missing column
+    /// or when `name` is a coordinate column.
+    pub fn compute_column(&self, name: &str, expr: ColumnExpr) -> Result<Self, PolarsError> {
+        let values = self
+            .data()
+            .clone()
+            .lazy()
+            .with_column(expr.to_polars().alias(name))
+            .collect()?;
+        let mut out = self.clone();
+        out.set_attribute(values.column(name)?.as_materialized_series().clone())?;
+        Ok(out)
+    }
+
+    /// Clamps attribute `name` to `[min, max]` in place, keeping its dtype.
+    /// Nulls and NaNs are left as they are. Errors on a missing or
+    /// coordinate column.
+    pub fn clamp_column(&mut self, name: &str, min: f64, max: f64) -> Result<(), PolarsError> {
+        if min.is_nan() || max.is_nan() || min > max {
+            polars_bail!(InvalidOperation: "clamp bounds must satisfy min <= max, got [{}, {}]", min, max);
+        }
+        let value = col(name).cast(DataType::Float64);
+        let clamped = when(value.clone().lt(lit(min)))
+            .then(lit(min))
+            .when(value.clone().gt(lit(max)))
+            .then(lit(max))
+            .otherwise(value);
+        self.replace_column(name, clamped)
+    }
+
+    /// Applies a threshold to attribute `name`: values strictly above (or
+    /// below) `value` are zeroed in place or their points removed, per
+    /// `mode`. Nulls and NaNs never count as past the threshold. Errors on
+    /// a missing or coordinate column.
+    pub fn threshold_column(
+        &mut self,
+        name: &str,
+        value: f64,
+        mode: ThresholdMode,
+    ) -> Result<(), PolarsError> {
+        if value.is_nan() {
+            polars_bail!(InvalidOperation: "threshold must not be NaN");
+        }
+        let current = col(name).cast(DataType::Float64);
+        let past = match mode {
+            ThresholdMode::ZeroAbove | ThresholdMode::DropAbove => current.clone().gt(lit(value)),
+            ThresholdMode::ZeroBelow | ThresholdMode::DropBelow => current.clone().lt(lit(value)),
+        };
+        match mode {
+            ThresholdMode::ZeroAbove | ThresholdMode::ZeroBelow => {
+                self.replace_column(name, when(past).then(lit(0.0)).otherwise(current))
+            }
+            ThresholdMode::DropAbove | ThresholdMode::DropBelow => {
+                if COORDINATE_COLUMNS.contains(&name) {
+                    polars_bail!(InvalidOperation: "'{}' is a coordinate column, not an attribute", name);
+                }
+                let keep = self
+                    .data()
+                    .clone()
+                    .lazy()
+                    .select([past.not().fill_null(lit(true)).alias("keep")])
+                    .collect()?;
+                *self = self.filter(keep.column("keep")?.bool()?)?;
+                Ok(())
+            }
+        }
+    }
+
+    /// Overwrites attribute `name` with `expr`, cast back to its dtype.
+    fn replace_column(&mut self, name: &str, expr: Expr) -> Result<(), PolarsError> {
+        let dtype = self.data().column(name)?.dtype().clone();
+        let values = self
+            .data()
+            .clone()
+            .lazy()
+            .select([expr.cast(dtype).alias(name)])
+            .collect()?;
+        self.set_attribute(values.column(name)?.as_materialized_series().clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Three points with a `u16` intensity and an `f64` range.
+    fn channels() -> TablePointCloud {
+        let mut cloud =
+            TablePointCloud::from_xyz(vec![0.0; 3], vec![0.0; 3], vec![0.0; 3]).unwrap();
+        cloud
+            .set_attribute(Series::new("intensity".into(), vec![100u16, 400, 900]))
+            .unwrap();
+        cloud
+            .set_attribute(Series::new("range".into(), vec![2.0, 4.0, 0.0]))
+            .unwrap();
+        cloud
+    }
+
+    #[test]
+    fn computes_a_reflectivity_ratio() {
+        let expr =
+            ColumnExpr::col("intensity") / ColumnExpr::col("range").pow(ColumnExpr::lit(2.0));
+        let cloud = channels().compute_column("reflectivity", expr).unwrap();
+        let reflectivity = cloud.attribute("reflectivity").unwrap();
+        assert_eq!(reflectivity[..2], [25.0, 25.0]);
+        assert_eq!(reflectivity[2], f64::INFINITY);
+        assert_eq!(
+            cloud.data().column("reflectivity").unwrap().dtype(),
+            &DataType::Float64
+        );
+
+        let shifted = (ColumnExpr::col("range") - ColumnExpr::lit(1.0)) * ColumnExpr::lit(3.0)
+            + ColumnExpr::col("intensity");
+        let cloud = cloud.compute_column("range", shifted).unwrap();
+        assert_eq!(cloud.attribute("range").unwrap(), [103.0, 409.0, 897.0]);
+
+        assert!(channels()
+            .compute_column("bad", ColumnExpr::col("missing"))
+            .is_err());
+        assert!(channels()
+            .compute_column("z", ColumnExpr::lit(1.0))
+            .is_err());
+    }
+
+    #[test]
+    fn clamping_intensity_respects_the_bounds() {
+        let mut cloud = channels();
+        cloud.clamp_column("intensity", 200.0, 500.0).unwrap();
+        assert_eq!(cloud.attribute("intensity").unwrap(), [200.0, 400.0, 500.0]);
+        assert_eq!(
+            cloud.data().column("intensity").unwrap().dtype(),
+            &DataType::UInt16
+        );
+        assert!(cloud.clamp_column("intensity", 5.0, 1.0).is_err());
+        assert!(cloud.clamp_column("missing", 0.0, 1.0).is_err());
+    }
+
+    #[test]
+    fn thresholds_zero_or_drop() {
+        let mut zeroed = channels();
+        zeroed
+            .threshold_column("intensity", 400.0, ThresholdMode::ZeroAbove)
+            .unwrap();
+        assert_eq!(zeroed.attribute("intensity").unwrap(), [100.0, 400.0, 0.0]);
+
+        let mut dropped = channels();
+        dropped
+            .threshold_column("range", 1.0, ThresholdMode::DropBelow)
+            .unwrap();
+        assert_eq!(dropped.attribute("range").unwrap(), [2.0, 4.0]);
+        assert!(dropped
+            .threshold_column("missing", 1.0, ThresholdMode::ZeroBelow)
+            .is_err());
+        assert!(dropped
+            .threshold_column("x", 1.0, ThresholdMode::DropAbove)
+            .is_err());
+    }
+}
@@ -0,0 +1,514 @@
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+use std::f64::consts::TAU;
+
+use nalgebra::Vector3;
+use polars::prelude::*;
+
+use crate::cloud::NORMAL_COLUMNS;
+use crate::kdtree::KdTree;
+use crate::TablePointCloud;
+
+/// Aggregate used by [`TablePointCloud::neighborhood_reduce`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reduce {
+    Mean,
+    Max,
+    Min,
+    /// Population standard deviation.
+    Std,
+}
+
+impl Reduce {
+    /// Lowercase name used as the output column suffix.
+    pub fn name(self) -> &'static str {
+        match self {
+            Reduce::Mean => "mean",
+            Reduce::Max => "max",
+            Reduce::Min => "min",
+            Reduce::Std => "std",
+        }
+    }
+
+    fn apply(self, values: &[f64]) -> f64 {
+        if values.is_empty() {
+            return f64::NAN;
+        }
+        let n = values.len() as f64;
+        let mean = || values.iter().sum::<f64>() / n;
+        match self {
+            Reduce::Mean => mean(),
+            Reduce::Max => values.iter().cloned().fold(f64::NEG_INFINITY, f64::max),
+            Reduce::Min => values.iter().cloned().fold(f64::INFINITY, f64::min),
+            Reduce::Std => {
+                let mean = mean();
+                (values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n).sqrt()
+            }
+        }
+    }
+}
+
+impl TablePointCloud {
+    /// Aggregates `column` over every point's neighbors within `radius`,
+    /// the point itself included, into a new `f64` column named
+    /// `{column}_{reduce}` (e.g. `intensity_mean`).
+    ///
+    /// Nulls and NaNs are skipped; a point with no valid value in its
+    /// neighborhood gets NaN.
+    pub fn neighborhood_reduce(
+        &self,
+        radius: f64,
+        column: &str,
+        reduce: Reduce,
+    ) -> Result<Self, PolarsError> {
+        if radius.is_nan() || radius < 0.0 {
+            polars_bail!(InvalidOperation: "neighborhood radius must be non-negative, got {}", radius);
+        }
+        let values = self.column_f64(column)?;
+        let points = self.xyz()?;
+        let tree = KdTree::new(&points);
+
+        let reduced: Vec<f64> = points
+            .iter()
+            .map(|&p| {
+                let neighbors: Vec<f64> = tree
+                    .radius_search(p, radius)
+                    .into_iter()
+                    .map(|(j, _)| values[j])
+                    .filter(|v| !v.is_nan())
+                    .collect();
+                reduce.apply(&neighbors)
+            })
+            .collect();
+
+        let mut out = self.clone();
+        let name = format!("{}_{}", column, reduce.name());
+        out.set_attribute(Series::new(name.into(), reduced))?;
+        Ok(out)
+    }
+
+    /// Inverse-distance-weighted interpolation of `column` at each query
+    /// position from its `k` nearest points.
+    ///
+    /// Weights are `1 / d^2`; a query coinciding with a point takes that
+    /// point's value. Nulls and NaNs are skipped, and a query whose
+    /// neighbors are all missing gets NaN. Errors on an empty cloud.
+    pub fn interpolate_at(
+        &self,
+        queries: &[[f64; 3]],
+        k: usize,
+        column: &str,
+    ) -> Result<Vec<f64>, PolarsError> {
+        if k == 0 {
+            polars_bail!(InvalidOperation: "interpolation needs k > 0");
+        }
+        self.ensure_non_empty("interpolation")?;
+        let values = self.column_f64(column)?;
+        let tree = KdTree::from_cloud(self)?;
+
+        Ok(queries
+            .iter()
+            .map(|&q| {
+                let neighbors: Vec<(f64, f64)> = tree
+                    .knn(q, k)
+                    .into_iter()
+                    .map(|(j, d)| (values[j], d))
+                    .filter(|(v, _)| !v.is_nan())
+                    .collect();
+                if let Some(&(v, _)) = neighbors.iter().find(|(_, d)| *d == 0.0) {
+                    return v;
+                }
+                let (sum, weights) =
+                    neighbors
+                        .iter()
+                        .fold((0.0, 0.0), |(sum, weights), &(v, d)| {
+                            let w = 1.0 / (d * d);
+                            (sum + w * v, weights + w)
+                        });
+                if weights == 0.0 {
+                    f64::NAN
+                } else {
+                    sum / weights
+                }
+            })
+            .collect())
+    }
+
+    /// Flags points on the boundary of a surface in a boolean `is_boundary`
+    /// column.
+    ///
+    /// Each point's `k` nearest neighbors are projected onto the tangent
+    /// plane given by its normal (`nx`, `ny`, `nz`). A point is a boundary
+    /// point when the largest angular gap between consecutive projected
+    /// neighbors exceeds `angle_threshold` (radians); interior points are
+    /// surrounded on all sides and only see small gaps. Points without any
+    /// usable neighbor are flagged as boundary.
+    pub fn detect_boundaries(&self, k: usize, angle_threshold: f64) -> Result<Self, PolarsError> {
+        if k == 0 {
+            polars_bail!(InvalidOperation: "boundary detection needs k > 0");
+        }
+        let normals = self.normals()?;
+        let points = self.xyz()?;
+        let tree = KdTree::new(&points);
+
+        let flags: Vec<bool> = points
+            .iter()
+            .zip(&normals)
+            .enumerate()
+            .map(|(i, (p, n))| {
+                let p = Vector3::from(*p);
+                let (u, w) = tangent_basis(&Vector3::from(*n));
+                let mut angles: Vec<f64> = tree
+                    .knn(points[i], k + 1)
+                    .into_iter()
+                    .filter(|&(j, _)| j != i)
+                    .filter_map(|(j, _)| {
+                        let v = Vector3::from(points[j]) - p;
+                        let (a, b) = (v.dot(&u), v.dot(&w));
+                        (a != 0.0 || b != 0.0).then(|| b.atan2(a))
+                    })
+                    .collect();
+                if angles.is_empty() {
+                    return true;
+                }
+                angles.sort_unstable_by(f64::total_cmp);
+                let wrap = angles[0] + TAU - angles[angles.len() - 1];
+                let max_gap = angles
+                    .windows(2)
+                    .map(|pair| pair[1] - pair[0])
+                    .fold(wrap, f64::max);
+                max_gap > angle_threshold
+            })
+            .collect();
+
+        let mut out = self.clone();
+        out.set_attribute(Series::new("is_boundary".into(), flags))?;
+        Ok(out)
+    }
+
+    /// Flips normals so neighboring ones agree in sign, after Hoppe et al.
+    ///
+    /// Points are linked to their `k` nearest neighbors with edge cost
+    /// `1 - |n_i . n_j|`, and orientation is propagated along the minimum
+    /// spanning tree of that graph, so it travels across flat regions
+    /// before crossing sharp creases. Each connected component is seeded
+    /// at its highest point, whose normal is made to point up (+z).
+    ///
+    /// Only signs change; the `nx`/`ny`/`nz` columns, which are required,
+    /// are rewritten as `f64`. Points with a NaN or zero normal are left
+    /// out of the graph and keep their normal.
+    pub fn orient_normals_consistent(&mut self, k: usize) -> Result<(), PolarsError> {
+        if k == 0 {
+            polars_bail!(InvalidOperation: "normal orientation needs k > 0");
+        }
+        let mut normals: Vec<Vector3<f64>> =
+            self.normals()?.into_iter().map(Vector3::from).collect();
+        let points = self.xyz()?;
+        let tree = KdTree::new(&points);
+        let valid: Vec<bool> = normals
+            .iter()
+            .map(|n| n.iter().all(|v| v.is_finite()) && n.norm_squared() > 0.0)
+            .collect();
+
+        // Symmetric k-nearest-neighbor graph over valid points.
+        let mut graph: Vec<Vec<usize>> = vec![Vec::new(); points.len()];
+        for (i, &p) in points.iter().enumerate() {
+            if !valid[i] {
+                continue;
+            }
+            for (j, _) in tree.knn(p, k + 1) {
+                if j != i && valid[j] {
+                    graph[i].push(j);
+                    graph[j].push(i);
+                }
+            }
+        }
+        let cost = |normals: &[Vector3<f64>], i: usize, j: usize| {
+            1.0 - (normals[i].dot(&normals[j]) / (normals[i].norm() * normals[j].norm())).abs()
+        };
+
+        // Seeds in order of decreasing height, so every component starts
+        // from its highest point.
+        let mut seeds: Vec<usize> = (0..points.len()).filter(|&i| valid[i]).collect();
+        seeds.sort_by(|&a, &b| points[b][2].total_cmp(&points[a][2]));
+        let mut visited = vec![false; points.len()];
+        let mut heap = BinaryHeap::new();
+        for seed in seeds {
+            if visited[seed] {
+                continue;
+            }
+            visited[seed] = true;
+            if normals[seed].z < 0.0 {
+                normals[seed] = -normals[seed];
+            }
+            heap.extend(graph[seed].iter().map(|&j| {
+                Reverse(MstEdge {
+                    cost: cost(&normals, seed, j),
+                    from: seed,
+                    to: j,
+                })
+            }));
+            // Prim's algorithm, orienting each point against its parent.
+            while let Some(Reverse(edge)) = heap.pop() {
+                if visited[edge.to] {
+                    continue;
+                }
+                visited[edge.to] = true;
+                if normals[edge.to].dot(&normals[edge.from]) < 0.0 {
+                    normals[edge.to] = -normals[edge.to];
+                }
+                for &j in &graph[edge.to] {
+                    if !visited[j] {
+                        heap.push(Reverse(MstEdge {
+                            cost: cost(&normals, edge.to, j),
+                            from: edge.to,
+                            to: j,
+                        }));
+                    }
+                }
+            }
+        }
+
+        for (axis, name) in NORMAL_COLUMNS.iter().enumerate() {
+            let values: Vec<f64> = normals.iter().map(|n| n[axis]).collect();
+            self.set_attribute(Series::new((*name).into(), values))?;
+        }
+        Ok(())
+    }
+
+    /// Writes a `verticality` column, `1 - |nz|` of the unit normal: 0 on
+    /// horizontal surfaces such as floors, 1 on vertical ones such as walls.
+    ///
+    /// Errors when the cloud has no `nx`/`ny`/`nz` normals; zero-length
+    /// normals give NaN.
+    pub fn verticality(&self) -> Result<Self, PolarsError> {
+        let values: Vec<f64> = self
+            .normals()?
+            .into_iter()
+            .map(|n| 1.0 - (n[2] / Vector3::from(n).norm()).abs())
+            .collect();
+        let mut out = self.clone();
+        out.set_attribute(Series::new("verticality".into(), values))?;
+        Ok(out)
+    }
+}
+
+/// Candidate edge of the orientation spanning tree, ordered by cost.
+#[derive(Debug, Clone, Copy)]
+struct MstEdge {
+    cost: f64,
+    from: usize,
+    to: usize,
+}
+
+impl PartialEq for MstEdge {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for MstEdge {}
+
+impl PartialOrd for MstEdge {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for MstEdge {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.cost
+            .total_cmp(&other.cost)
+            .then(self.to.cmp(&other.to))
+            .then(self.from.cmp(&other.from))
+    }
+}
+
+/// Two unit vectors spanning the plane orthogonal to `n`.
+fn tangent_basis(n: &Vector3<f64>) -> (Vector3<f64>, Vector3<f64>) {
+    let n = n.normalize();
+    let helper = if n.x.abs() < 0.9 {
+        Vector3::x()
+    } else {
+        Vector3::y()
+    };
+    let u = n.cross(&helper).normalize();
+    let w = n.cross(&u);
+    (u, w)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::FRAC_PI_2;
+
+    use super::*;
+
+    /// A `side` x `side` grid of unit spacing in the z = 0 plane with
+    /// upward normals.
+    fn flat_patch(side: usize) -> TablePointCloud {
+        let (mut x, mut y) = (Vec::new(), Vec::new());
+        for i in 0..side {
+            for j in 0..side {
+                x.push(i as f64);
+                y.push(j as f64);
+            }
+        }
+        let n = x.len();
+        let mut cloud = TablePointCloud::from_xyz(x, y, vec![0.0; n]).unwrap();
+        for (name, value) in NORMAL_COLUMNS.into_iter().zip([0.0, 0.0, 1.0]) {
+            cloud
+                .set_attribute(Series::new(name.into(), vec![value; n]))
+                .unwrap();
+        }
+        cloud
+    }
+
+    #[test]
+    fn boundary_flags_perimeter_only() {
+        let side = 5;
+        let flagged = flat_patch(side).detect_boundaries(8, FRAC_PI_2).unwrap();
+        let flags: Vec<bool> = flagged
+            .data()
+            .column("is_boundary")
+            .unwrap()
+            .bool()
+            .unwrap()
+            .iter()
+            .map(Option::unwrap)
+            .collect();
+        for i in 0..side {
+            for j in 0..side {
+                let on_edge = i == 0 || j == 0 || i == side - 1 || j == side - 1;
+                assert_eq!(flags[i * side + j], on_edge, "point ({i}, {j})");
+            }
+        }
+    }
+
+    #[test]
+    fn boundary_requires_normals() {
+        let cloud = TablePointCloud::from_xyz(vec![0.0], vec![0.0], vec![0.0]).unwrap();
+        assert!(cloud.detect_boundaries(4, FRAC_PI_2).is_err());
+    }
+
+    #[test]
+    fn neighborhood_mean_of_uniform_region() {
+        let mut cloud = flat_patch(7);
+        let mut intensity = vec![2.0; cloud.len()];
+        // A spike in the (0, 0) corner, far from the middle of the patch.
+        intensity[0] = 100.0;
+        cloud
+            .set_attribute(Series::new("intensity".into(), intensity))
+            .unwrap();
+
+        let centre = 3 * 7 + 3;
+        let mean = cloud
+            .neighborhood_reduce(1.5, "intensity", Reduce::Mean)
+            .unwrap()
+            .column_f64("intensity_mean")
+            .unwrap();
+        assert_eq!(mean[centre], 2.0);
+        assert!(mean[0] > 2.0);
+
+        let max = cloud
+            .neighborhood_reduce(1.5, "intensity", Reduce::Max)
+            .unwrap()
+            .column_f64("intensity_max")
+            .unwrap();
+        assert_eq!(max[centre], 2.0);
+        assert_eq!(max[7 + 1], 100.0);
+
+        let std = cloud
+            .neighborhood_reduce(1.5, "intensity", Reduce::Std)
+            .unwrap()
+            .column_f64("intensity_std")
+            .unwrap();
+        assert_eq!(std[centre], 0.0);
+    }
+
+    #[test]
+    fn interpolates_linear_field() {
+        let mut cloud = flat_patch(11);
+        let field = |x: f64, y: f64| 3.0 * x - 2.0 * y + 1.0;
+        let values: Vec<f64> = cloud
+            .x()
+            .unwrap()
+            .into_iter()
+            .zip(cloud.y().unwrap())
+            .map(|(x, y)| field(x, y))
+            .collect();
+        cloud
+            .set_attribute(Series::new("field".into(), values))
+            .unwrap();
+
+        // Queries at cell centres see four symmetric neighbours, so IDW
+        // reproduces the linear field; grid nodes take the node's value.
+        let queries = [[4.5, 5.5, 0.0], [2.5, 7.5, 0.0], [3.0, 6.0, 0.0]];
+        let interpolated = cloud.interpolate_at(&queries, 4, "field").unwrap();
+        for (q, value) in queries.iter().zip(interpolated) {
+            assert!((value - field(q[0], q[1])).abs() < 1e-9, "{q:?}: {value}");
+        }
+    }
+
+    #[test]
+    fn verticality_separates_floors_from_walls() {
+        let mut cloud =
+            TablePointCloud::from_xyz(vec![0.0; 4], vec![0.0; 4], vec![0.0; 4]).unwrap();
+        assert!(cloud.verticality().is_err());
+        // Floor, ceiling (flipped normal), wall, and an unnormalized wall.
+        let normals = [
+            [0.0, 0.0, 1.0],
+            [0.0, 0.0, -1.0],
+            [1.0, 0.0, 0.0],
+            [0.0, 3.0, 0.0],
+        ];
+        for (axis, name) in NORMAL_COLUMNS.into_iter().enumerate() {
+            let values: Vec<f64> = normals.iter().map(|n| n[axis]).collect();
+            cloud
+                .set_attribute(Series::new(name.into(), values))
+                .unwrap();
+        }
+        let verticality = cloud
+            .verticality()
+            .unwrap()
+            .attribute("verticality")
+            .unwrap();
+        for (actual, expected) in verticality.into_iter().zip([0.0, 0.0, 1.0, 1.0]) {
+            assert!((actual - expected).abs() < 1e-12, "{actual} != {expected}");
+        }
+    }
+
+    #[test]
+    fn hemisphere_normals_end_up_outward() {
+        // Upper unit hemisphere; every third normal is flipped inward.
+        let (mut points, mut normals) = (Vec::new(), Vec::new());
+        for ring in 0..8 {
+            let polar = 0.1 + 1.4 * ring as f64 / 8.0;
+            for step in 0..24 {
+                let azimuth = std::f64::consts::TAU * step as f64 / 24.0;
+                let p = [
+                    polar.sin() * azimuth.cos(),
+                    polar.sin() * azimuth.sin(),
+                    polar.cos(),
+                ];
+                let sign = if points.len() % 3 == 0 { -1.0 } else { 1.0 };
+                normals.push(p.map(|v| sign * v));
+                points.push(p);
+            }
+        }
+        let mut cloud = TablePointCloud::from_point_array(&points).unwrap();
+        assert!(cloud.orient_normals_consistent(6).is_err());
+        for (axis, name) in NORMAL_COLUMNS.into_iter().enumerate() {
+            let values: Vec<f64> = normals.iter().map(|n| n[axis]).collect();
+            cloud
+                .set_attribute(Series::new(name.into(), values))
+                .unwrap();
+        }
+
+        cloud.orient_normals_consistent(6).unwrap();
+        for (p, n) in points.iter().zip(cloud.normals().unwrap()) {
+            let outward = Vector3::from(*p).dot(&Vector3::from(n));
+            assert!((outward - 1.0).abs() < 1e-12, "{p:?} has normal {n:?}");
+        }
+        assert!(cloud.orient_normals_consistent(0).is_err());
+    }
+}
@@ -0,0 +1,746 @@
+use nalgebra::{Matrix4, Rotation3, Vector3, Vector4};
+use polars::prelude::*;
+
+use crate::cloud::VectorKind;
+use crate::transform::{CoordinateFrame, Transform};
+use crate::{PcdError, TablePointCloud};
+
+impl TablePointCloud {
+    /// Applies a homogeneous 4x4 transform to every point.
+    ///
+    /// Points are transformed as `(x, y, z, 1)` and divided by the resulting
+    /// `w`, so projective matrices are supported. Vector attributes (the
+    /// `nx`/`ny`/`nz` normals and any registered with
+    /// [`TablePointCloud::register_vector_attribute`]) are transformed by the
+    /// linear 3x3 block, normals using its inverse-transpose, and written
+    /// back as `f64`. Transformed normals are renormalized, except that
+    /// zero normals (the usual "no normal" placeholder) stay zero. All other
+    /// attributes are copied unchanged. An empty cloud yields an empty
+    /// cloud. The matrix is appended to the
+    /// [`TablePointCloud::transform_history`].
+    ///
+    /// Errors when the cloud has normals and the linear block is singular
+    /// (e.g. the flattening `diag(1, 1, 0, 1)`), since normals have no
+    /// well-defined image then; use
+    /// [`TablePointCloud::project_onto_plane`] to flatten such a cloud.
+    pub fn transform(&self, matrix: &Matrix4<f64>) -> Result<Self, PolarsError> {
+        if self.is_empty() {
+            let mut out = self.clone();
+            out.record_transform(*matrix);
+            return Ok(out);
+        }
+        let points = self.xyz()?;
+        let mut x = Vec::with_capacity(points.len());
+        let mut y = Vec::with_capacity(points.len());
+        let mut z = Vec::with_capacity(points.len());
+        for [px, py, pz] in points {
+            let p = matrix * Vector4::new(px, py, pz, 1.0);
+            x.push(p.x / p.w);
+            y.push(p.y / p.w);
+            z.push(p.z / p.w);
+        }
+        let mut out = self.with_coordinates(x, y, z)?;
+
+        let linear = matrix.fixed_view::<3, 3>(0, 0).into_owned();
+        for vector in self.vector_attributes() {
+            let map = match vector.kind {
+                VectorKind::Direction => linear,
+                VectorKind::Normal => linear
+                    .try_inverse()
+                    .ok_or_else(|| polars_err!(ComputeError: "cannot transform normals: the linear block is singular"))?
+                    .transpose(),
+            };
+            let [a, b, c] = &vector.columns;
+            let (va, vb, vc) = (
+                self.column_f64(a)?,
+                self.column_f64(b)?,
+                self.column_f64(c)?,
+            );
+            let mut out_a = Vec::with_capacity(va.len());
+            let mut out_b = Vec::with_capacity(va.len());
+            let mut out_c = Vec::with_capacity(va.len());
+            for i in 0..va.len() {
+                let mut v = map * Vector3::new(va[i], vb[i], vc[i]);
+                if vector.kind == VectorKind::Normal && v.norm() > 0.0 {
+                    v = v.normalize();
+                }
+                out_a.push(v.x);
+                out_b.push(v.y);
+                out_c.push(v.z);
+            }
+            out.set_attribute(Series::new(a.as_str().into(), out_a))?;
+            out.set_attribute(Series::new(b.as_str().into(), out_b))?;
+            out.set_attribute(Series::new(c.as_str().into(), out_c))?;
+        }
+        out.record_transform(*matrix);
+        Ok(out)
+    }
+
+    /// [`TablePointCloud::transform`] restricted to finite affine matrices.
+    ///
+    /// Rejects a matrix with any NaN or infinite entry, or whose bottom row
+    /// differs from `[0, 0, 0, 1]` by more than 1e-9, as a sign of a failed
+    /// solve upstream.
+    pub fn transform_checked(&self, matrix: &Matrix4<f64>) -> Result<Self, PcdError> {
+        ensure_finite_matrix(matrix)?;
+        let bottom = matrix.row(3);
+        let expected = [0.0, 0.0, 0.0, 1.0];
+        if (0..4).any(|i| (bottom[i] - expected[i]).abs() > 1e-9) {
+            return Err(PcdError::InvalidInput(format!(
+                "transform is not affine, bottom row is {bottom}"
+            )));
+        }
+        Ok(self.transform(matrix)?)
+    }
+
+    /// [`TablePointCloud::transform`] that refuses to produce non-finite
+    /// coordinates.
+    ///
+    /// Errors, naming the first offending point index, when a point has a
+    /// NaN or infinite coordinate or when the matrix maps it to one (for
+    /// instance a projective matrix giving it `w = 0`), instead of letting
+    /// NaN propagate downstream.
+    pub fn try_transform(&self, matrix: &Matrix4<f64>) -> Result<Self, PcdError> {
+        ensure_finite_matrix(matrix)?;
+        for (i, [px, py, pz]) in self.xyz()?.into_iter().enumerate() {
+            if ![px, py, pz].iter().all(|v| v.is_finite()) {
+                return Err(PcdError::InvalidInput(format!(
+                    "point {i} has non-finite coordinates ({px}, {py}, {pz})"
+                )));
+            }
+            let p = matrix * Vector4::new(px, py, pz, 1.0);
+            if ![p.x / p.w, p.y / p.w, p.z / p.w]
+                .iter()
+                .all(|v| v.is_finite())
+            {
+                return Err(PcdError::InvalidInput(format!(
+                    "transform sends point {i} ({px}, {py}, {pz}) to a non-finite position"
+                )));
+            }
+        }
+        Ok(self.transform(matrix)?)
+    }
+
+    /// [`TablePointCloud::transform`] taking the crate's [`Transform`].
+    ///
+    /// `transform` with a `Matrix4<f64>` is the canonical entry point, and
+    /// every other geometric helper goes through it; this is a thin bridge
+    /// so callers holding a `Transform<f64>` need not unwrap it.
+    pub fn apply_transform(&self, transform: &Transform<f64>) -> Result<Self, PolarsError> {
+        self.transform(transform.matrix())
+    }
+
+    /// Re-expresses the cloud, given in the `from` frame, in the `to`
+    /// frame by applying the axis permutation and sign flips between them
+    /// (see [`CoordinateFrame::conversion`]). Normals and registered vector
+    /// attributes are converted too.
+    pub fn convert_frame(
+        &self,
+        from: CoordinateFrame,
+        to: CoordinateFrame,
+    ) -> Result<Self, PolarsError> {
+        self.transform(&CoordinateFrame::conversion(from, to))
+    }
+
+    /// Shifts every point by `offset`.
+    pub fn translate(&self, offset: [f64; 3]) -> Result<Self, PolarsError> {
+        self.transform(&Matrix4::new_translation(&Vector3::from(offset)))
+    }
+
+    /// Translates the cloud so its centroid sits at the origin, returning
+    /// it with the subtracted centroid; `translate` by that offset undoes
+    /// it. Keeps large (e.g. UTM) coordinates within `f32` precision for
+    /// rendering. Errors on an empty cloud.
+    pub fn center(&self) -> Result<(Self, [f64; 3]), PolarsError> {
+        let centroid = self.centroid()?;
+        let centered = self.translate(centroid.map(|c| -c))?;
+        Ok((centered, centroid))
+    }
+
+    /// Subtracts the bounding box's min corner so every coordinate is
+    /// small and non-negative, returning the cloud with that offset.
+    ///
+    /// Unlike [`TablePointCloud::center`] this is a change of local origin
+    /// (see [`TablePointCloud::relocate_origin`]), not a transform: the
+    /// offset is added to [`TablePointCloud::origin`], `global_xyz()` is
+    /// unchanged and nothing is recorded in the transform history.
+    /// [`TablePointCloud::restore_global`] undoes it. Errors on an empty
+    /// cloud.
+    pub fn recenter_to_local(&self) -> Result<(Self, [f64; 3]), PolarsError> {
+        let (min, _) = self.aabb()?;
+        let origin = self.origin();
+        let recentered = self.relocate_origin([0, 1, 2].map(|axis| origin[axis] + min[axis]))?;
+        Ok((recentered, min))
+    }
+
+    /// Adds `offset` back to the coordinates and removes it from the
+    /// origin, undoing [`TablePointCloud::recenter_to_local`].
+    pub fn restore_global(&self, offset: [f64; 3]) -> Result<Self, PolarsError> {
+        let origin = self.origin();
+        self.relocate_origin([0, 1, 2].map(|axis| origin[axis] - offset[axis]))
+    }
+
+    /// Rotates about the x axis through the origin by `angle` radians.
+    pub fn rotate_x(&self, angle: f64) -> Result<Self, PolarsError> {
+        self.transform(&Rotation3::from_axis_angle(&Vector3::x_axis(), angle).to_homogeneous())
+    }
+
+    /// Rotates about the y axis through the origin by `angle` radians.
+    pub fn rotate_y(&self, angle: f64) -> Result<Self, PolarsError> {
+        self.transform(&Rotation3::from_axis_angle(&Vector3::y_axis(), angle).to_homogeneous())
+    }
+
+    /// Rotates about the z axis through the origin by `angle` radians.
+    pub fn rotate_z(&self, angle: f64) -> Result<Self, PolarsError> {
+        self.transform(&Rotation3::from_axis_angle(&Vector3::z_axis(), angle).to_homogeneous())
+    }
+
+    /// Undoes the recorded transform history by applying the inverse of
+    /// [`TablePointCloud::accumulated_transform`], returning a cloud with
+    /// an empty history.
+    ///
+    /// Coordinates are recovered up to floating-point round-off. Errors
+    /// when the accumulated matrix is singular.
+    pub fn reset_to_original(&self) -> Result<Self, PolarsError> {
+        if self.transform_history().is_empty() {
+            return Ok(self.clone());
+        }
+        let inverse = self.accumulated_transform().try_inverse().ok_or_else(
+            || polars_err!(ComputeError: "accumulated transform is singular and cannot be undone"),
+        )?;
+        let mut out = self.transform(&inverse)?;
+        out.clear_history();
+        Ok(out)
+    }
+
+    /// Projects every point orthogonally onto the plane `n . p + d = 0`,
+    /// keeping all attributes.
+    ///
+    /// `normal` need not be unit length (`d` is scaled along with it when
+    /// normalizing) but must be non-zero and finite.
+    pub fn project_onto_plane(&self, normal: [f64; 3], d: f64) -> Result<Self, PolarsError> {
+        let n = Vector3::from(normal);
+        let norm = n.norm();
+        if !(norm.is_finite() && norm > 0.0) {
+            polars_bail!(InvalidOperation: "plane normal must be non-zero and finite, got {:?}", normal);
+        }
+        let (n, d) = (n / norm, d / norm);
+        let mut x = Vec::with_capacity(self.len());
+        let mut y = Vec::with_capacity(self.len());
+        let mut z = Vec::with_capacity(self.len());
+        for p in self.xyz()? {
+            let p = Vector3::from(p);
+            let q = p - n * (n.dot(&p) + d);
+            x.push(q.x);
+            y.push(q.y);
+            z.push(q.z);
+        }
+        self.with_coordinates(x, y, z)
+    }
+
+    /// [`TablePointCloud::project_onto_plane`] for a plane given as
+    /// `[a, b, c, d]` coefficients of `ax + by + cz + d = 0`, the layout
+    /// plane fits are commonly exchanged in.
+    pub fn project_onto_plane_coefficients(&self, plane: [f64; 4]) -> Result<Self, PolarsError> {
+        let [a, b, c, d] = plane;
+        self.project_onto_plane([a, b, c], d)
+    }
+
+    /// Rounds each coordinate to the nearest multiple of its axis' `step`,
+    /// leaving axes with a zero step unchanged. Attributes are kept.
+    pub fn quantize(&self, step: [f64; 3]) -> Result<Self, PolarsError> {
+        if step.iter().any(|s| !(s.is_finite() && *s >= 0.0)) {
+            polars_bail!(InvalidOperation: "quantization steps must be non-negative and finite, got {:?}", step);
+        }
+        let snap = |values: Vec<f64>, step: f64| -> Vec<f64> {
+            if step == 0.0 {
+                return values;
+            }
+            values
+                .into_iter()
+                .map(|v| (v / step).round() * step)
+                .collect()
+        };
+        self.with_coordinates(
+            snap(self.x()?, step[0]),
+            snap(self.y()?, step[1]),
+            snap(self.z()?, step[2]),
+        )
+    }
+
+    /// Mean position of all points. Errors on an empty cloud.
+    pub fn centroid(&self) -> Result<[f64; 3], PolarsError> {
+        self.ensure_non_empty("centroid")?;
+        let n = self.len() as f64;
+        Ok([
+            self.x()?.iter().sum::<f64>() / n,
+            self.y()?.iter().sum::<f64>() / n,
+            self.z()?.iter().sum::<f64>() / n,
+        ])
+    }
+
+    /// Mean position weighted by `weight_column`. The weights must be
+    /// finite, non-negative and not all zero.
+    pub fn weighted_centroid(&self, weight_column: &str) -> Result<[f64; 3], PolarsError> {
+        let weights = self.column_f64(weight_column)?;
+        if weights.iter().any(|w| !w.is_finite() || *w < 0.0) {
+            polars_bail!(ComputeError: "weights in '{}' must be finite and non-negative", weight_column);
+        }
+        let total: f64 = weights.iter().sum();
+        if total <= 0.0 {
+            polars_bail!(ComputeError: "weights in '{}' are all zero", weight_column);
+        }
+        let mut sum = [0.0; 3];
+        for (p, w) in self.xyz()?.into_iter().zip(weights) {
+            for axis in 0..3 {
+                sum[axis] += w * p[axis];
+            }
+        }
+        Ok(sum.map(|s| s / total))
+    }
+
+    /// Axis-aligned bounding box as `(min, max)` corners. Errors on an empty
+    /// cloud.
+    pub fn aabb(&self) -> Result<([f64; 3], [f64; 3]), PolarsError> {
+        self.ensure_non_empty("bounding box")?;
+        let mut min = [f64::INFINITY; 3];
+        let mut max = [f64::NEG_INFINITY; 3];
+        for p in self.xyz()? {
+            for axis in 0..3 {
+                min[axis] = min[axis].min(p[axis]);
+                max[axis] = max[axis].max(p[axis]);
+            }
+        }
+        Ok((min, max))
+    }
+}
+
+/// Shared by the checked transforms: rejects a matrix with a NaN or
+/// infinite entry.
+fn ensure_finite_matrix(matrix: &Matrix4<f64>) -> Result<(), PcdError> {
+    if matrix.iter().any(|v| !v.is_finite()) {
+        return Err(PcdError::InvalidInput(format!(
+            "transform has non-finite entries: {matrix}"
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::FRAC_PI_2;
+
+    use super::*;
+
+    fn assert_close(actual: [f64; 3], expected: [f64; 3]) {
+        for axis in 0..3 {
+            assert!(
+                (actual[axis] - expected[axis]).abs() < 1e-9,
+                "{actual:?} != {expected:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn quarter_turn_rotates_normals() {
+        let mut cloud =
+            TablePointCloud::from_xyz(vec![1.0, 0.0], vec![0.0, 1.0], vec![0.0, 0.0]).unwrap();
+        cloud
+            .set_attribute(Series::new("nx".into(), vec![1.0, 0.0]))
+            .unwrap();
+        cloud
+            .set_attribute(Series::new("ny".into(), vec![0.0, 0.0]))
+            .unwrap();
+        cloud
+            .set_attribute(Series::new("nz".into(), vec![0.0, 2.0]))
+            .unwrap();
+
+        let rotation = Rotation3::from_axis_angle(&Vector3::z_axis(), FRAC_PI_2).to_homogeneous();
+        for rotated in [
+            cloud.transform(&rotation).unwrap(),
+            cloud
+                .apply_transform(&Transform::from_matrix(rotation))
+                .unwrap(),
+        ] {
+            let normals = rotated.normals().unwrap();
+            assert_close(normals[0], [0.0, 1.0, 0.0]);
+            assert_close(normals[1], [0.0, 0.0, 1.0]);
+            assert_close(rotated.xyz().unwrap()[0], [0.0, 1.0, 0.0]);
+        }
+    }
+
+    fn empty() -> TablePointCloud {
+        TablePointCloud::from_xyz(Vec::new(), Vec::new(), Vec::new()).unwrap()
+    }
+
+    #[test]
+    fn empty_cloud_geometry() {
+        let cloud = empty();
+        assert!(cloud.x().unwrap().is_empty());
+        assert!(cloud.y().unwrap().is_empty());
+        assert!(cloud.z().unwrap().is_empty());
+        assert!(cloud.xyz().unwrap().is_empty());
+        assert!(cloud.centroid().is_err());
+        assert!(cloud.aabb().is_err());
+        assert!(cloud.center().is_err());
+        assert!(cloud
+            .transform(&Matrix4::new_scaling(2.0))
+            .unwrap()
+            .is_empty());
+        assert!(cloud.translate([1.0, 2.0, 3.0]).unwrap().is_empty());
+        assert!(cloud.rotate_z(1.0).unwrap().is_empty());
+    }
+
+    #[test]
+    fn vector_attributes_follow_the_transform() {
+        let mut cloud = TablePointCloud::from_xyz(vec![0.0], vec![0.0], vec![0.0]).unwrap();
+        for (name, value) in [
+            ("nx", 1.0),
+            ("ny", 1.0),
+            ("nz", 0.0),
+            ("vx", 1.0),
+            ("vy", 0.0),
+            ("vz", 0.0),
+            ("intensity", 7.0),
+        ] {
+            cloud
+                .set_attribute(Series::new(name.into(), vec![value]))
+                .unwrap();
+        }
+        cloud
+            .register_vector_attribute(["vx", "vy", "vz"], VectorKind::Direction)
+            .unwrap();
+
+        let rotation = Rotation3::from_axis_angle(&Vector3::z_axis(), FRAC_PI_2).to_homogeneous();
+        let rotated = cloud.transform(&rotation).unwrap();
+        let half = std::f64::consts::FRAC_1_SQRT_2;
+        assert_close(rotated.normals().unwrap()[0], [-half, half, 0.0]);
+        let direction = ["vx", "vy", "vz"].map(|c| rotated.column_f64(c).unwrap()[0]);
+        assert_close(direction, [0.0, 1.0, 0.0]);
+        assert_eq!(rotated.column_f64("intensity").unwrap(), [7.0]);
+
+        // Stretching x tilts the plane's normal towards y, while a
+        // direction stretches with the points.
+        let stretch = Matrix4::new_nonuniform_scaling(&Vector3::new(2.0, 1.0, 1.0));
+        let stretched = cloud.transform(&stretch).unwrap();
+        let expected = Vector3::new(0.5, 1.0, 0.0).normalize();
+        assert_close(stretched.normals().unwrap()[0], expected.into());
+        let direction = ["vx", "vy", "vz"].map(|c| stretched.column_f64(c).unwrap()[0]);
+        assert_close(direction, [2.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn projection_flattens_onto_z_zero() {
+        let mut cloud = TablePointCloud::from_xyz(
+            vec![1.0, -2.0, 3.0],
+            vec![0.5, 4.0, -1.0],
+            vec![2.0, -3.5, 0.0],
+        )
+        .unwrap();
+        cloud
+            .set_attribute(Series::new("intensity".into(), vec![1.0, 2.0, 3.0]))
+            .unwrap();
+
+        // A non-unit normal is normalized internally.
+        let flat = cloud.project_onto_plane([0.0, 0.0, 5.0], 0.0).unwrap();
+        assert_eq!(flat.z().unwrap(), [0.0, 0.0, 0.0]);
+        assert_eq!(flat.x().unwrap(), cloud.x().unwrap());
+        assert_eq!(flat.y().unwrap(), cloud.y().unwrap());
+        assert_eq!(flat.attribute("intensity").unwrap(), [1.0, 2.0, 3.0]);
+
+        assert!(cloud.project_onto_plane([0.0; 3], 1.0).is_err());
+    }
+
+    fn scattered() -> TablePointCloud {
+        TablePointCloud::from_xyz(
+            vec![1.0, -2.0, 0.5],
+            vec![0.0, 3.0, -1.5],
+            vec![2.0, 1.0, 4.0],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn history_composes_applied_transforms() {
+        let cloud = scattered();
+        let first = Rotation3::from_axis_angle(&Vector3::x_axis(), 0.3).to_homogeneous();
+        let moved = cloud
+            .transform(&first)
+            .unwrap()
+            .translate([1.0, -2.0, 0.5])
+            .unwrap();
+        let second = Matrix4::new_translation(&Vector3::new(1.0, -2.0, 0.5));
+
+        assert_eq!(moved.transform_history(), [first, second]);
+        assert_eq!(moved.accumulated_transform(), second * first);
+        let original = moved.reset_to_original().unwrap();
+        assert!(original.transform_history().is_empty());
+        for (a, b) in original
+            .xyz()
+            .unwrap()
+            .into_iter()
+            .zip(cloud.xyz().unwrap())
+        {
+            assert_close(a, b);
+        }
+    }
+
+    #[test]
+    fn checked_transform_rejects_bad_matrices() {
+        let cloud = scattered();
+        let mut nan = Matrix4::identity();
+        nan[(1, 2)] = f64::NAN;
+        let mut infinite = Matrix4::identity();
+        infinite[(0, 3)] = f64::INFINITY;
+        let mut projective = Matrix4::identity();
+        projective[(3, 0)] = 0.5;
+        for matrix in [nan, infinite, projective] {
+            let err = cloud.transform_checked(&matrix).unwrap_err();
+            assert!(matches!(err, PcdError::InvalidInput(_)), "{err}");
+        }
+
+        let mut nearly_affine = Matrix4::new_translation(&Vector3::new(1.0, 2.0, 3.0));
+        nearly_affine[(3, 3)] += 1e-12;
+        let moved = cloud.transform_checked(&nearly_affine).unwrap();
+        assert_eq!(moved.len(), cloud.len());
+    }
+
+    #[test]
+    fn centering_moves_the_centroid_to_the_origin() {
+        let cloud = TablePointCloud::from_xyz(
+            vec![500_000.0, 500_002.0, 500_004.0],
+            vec![4_100_000.0, 4_100_003.0, 4_100_006.0],
+            vec![10.0, 11.0, 15.0],
+        )
+        .unwrap();
+        let (centered, offset) = cloud.center().unwrap();
+        assert_eq!(offset, [500_002.0, 4_100_003.0, 12.0]);
+        assert_close(centered.centroid().unwrap(), [0.0; 3]);
+        assert_eq!(centered.xyz().unwrap()[0], [-2.0, -3.0, -2.0]);
+
+        let restored = centered.translate(offset).unwrap();
+        assert_eq!(restored.xyz().unwrap(), cloud.xyz().unwrap());
+        assert!(empty().center().is_err());
+    }
+
+    #[test]
+    fn quantize_snaps_to_the_grid() {
+        let mut cloud =
+            TablePointCloud::from_xyz(vec![0.123, -0.349], vec![1.06, 2.0], vec![7.777, -1.234])
+                .unwrap();
+        cloud
+            .set_attribute(Series::new("intensity".into(), vec![1.0, 2.0]))
+            .unwrap();
+        let snapped = cloud.quantize([0.1, 0.1, 0.0]).unwrap();
+        assert_close(snapped.xyz().unwrap()[0], [0.1, 1.1, 7.777]);
+        assert_close(snapped.xyz().unwrap()[1], [-0.3, 2.0, -1.234]);
+        assert_eq!(snapped.attribute("intensity").unwrap(), [1.0, 2.0]);
+        assert!(cloud.quantize([0.1, -0.1, 0.1]).is_err());
+    }
+
+    #[test]
+    fn global_coordinates_survive_recentering() {
+        let cloud = TablePointCloud::from_xyz(
+            vec![500_001.25, 500_003.5],
+            vec![4_100_002.0, 4_100_000.75],
+            vec![51.0, 49.5],
+        )
+        .unwrap();
+        let global = cloud.global_xyz().unwrap();
+
+        let (local, offset) = cloud.recenter_to_local().unwrap();
+        assert_eq!(offset, [500_001.25, 4_100_000.75, 49.5]);
+        assert_eq!(local.origin(), offset);
+        assert_eq!(local.xyz().unwrap()[0], [0.0, 1.25, 1.5]);
+        assert_eq!(local.global_xyz().unwrap(), global);
+        assert!(local.transform_history().is_empty());
+
+        let restored = local.restore_global(offset).unwrap();
+        assert_eq!(restored.origin(), [0.0; 3]);
+        assert_eq!(restored.xyz().unwrap(), global);
+    }
+
+    #[test]
+    fn camera_to_enu_and_back() {
+        // One metre right, two down and three ahead of a level camera
+        // facing north is one east, three north and two below it.
+        let camera = TablePointCloud::from_xyz(vec![1.0], vec![2.0], vec![3.0]).unwrap();
+        let enu = camera
+            .convert_frame(CoordinateFrame::Camera, CoordinateFrame::Enu)
+            .unwrap();
+        assert_close(enu.xyz().unwrap()[0], [1.0, 3.0, -2.0]);
+        let ned = enu
+            .convert_frame(CoordinateFrame::Enu, CoordinateFrame::Ned)
+            .unwrap();
+        assert_close(ned.xyz().unwrap()[0], [3.0, 1.0, 2.0]);
+
+        let back = ned
+            .convert_frame(CoordinateFrame::Ned, CoordinateFrame::Camera)
+            .unwrap();
+        assert_close(back.xyz().unwrap()[0], [1.0, 2.0, 3.0]);
+    }
+
+    #[test]
+    fn projecting_onto_plane_coefficients_flattens_points() {
+        // 2x - y + 2z - 6 = 0, given unnormalized.
+        let plane = [2.0, -1.0, 2.0, -6.0];
+        let mut cloud = scattered();
+        cloud
+            .set_attribute(Series::new("intensity".into(), vec![1.0, 2.0, 3.0]))
+            .unwrap();
+        let projected = cloud.project_onto_plane_coefficients(plane).unwrap();
+        for p in projected.xyz().unwrap() {
+            let distance = (plane[0] * p[0] + plane[1] * p[1] + plane[2] * p[2] + plane[3]) / 3.0;
+            assert!(distance.abs() < 1e-12, "{p:?} is {distance} off the plane");
+        }
+        // Points only move along the normal.
+        for (p, q) in cloud
+            .xyz()
+            .unwrap()
+            .into_iter()
+            .zip(projected.xyz().unwrap())
+        {
+            let d = Vector3::from(p) - Vector3::from(q);
+            assert!(d.cross(&Vector3::new(2.0, -1.0, 2.0)).norm() < 1e-12);
+        }
+        assert_eq!(
+            projected.attribute("intensity").unwrap(),
+            vec![1.0, 2.0, 3.0]
+        );
+        assert!(cloud
+            .project_onto_plane_coefficients([0.0, 0.0, 0.0, 1.0])
+            .is_err());
+    }
+
+    #[test]
+    fn apply_transform_matches_the_matrix_path() {
+        let transform = Transform::from_rotation_translation(
+            Rotation3::from_euler_angles(0.3, -0.7, 1.1).matrix(),
+            [4.0, -2.5, 0.25],
+        );
+        let cloud = scattered();
+        let bridged = cloud.apply_transform(&transform).unwrap();
+        let direct = cloud.transform(transform.matrix()).unwrap();
+        assert!(bridged.approx_eq(&direct, 0.0));
+        assert_eq!(bridged.xyz().unwrap(), direct.xyz().unwrap());
+        assert_eq!(
+            bridged.accumulated_transform(),
+            direct.accumulated_transform()
+        );
+    }
+
+    #[test]
+    fn weighting_pulls_the_centroid_toward_heavy_points() {
+        let mut cloud = TablePointCloud::from_xyz(
+            vec![0.0, 2.0, 10.0, 12.0],
+            vec![0.0, 0.0, 4.0, 4.0],
+            vec![0.0; 4],
+        )
+        .unwrap();
+        cloud
+            .set_attribute(Series::new("weight".into(), vec![1.0; 4]))
+            .unwrap();
+        assert_close(
+            cloud.weighted_centroid("weight").unwrap(),
+            cloud.centroid().unwrap(),
+        );
+        cloud
+            .set_attribute(Series::new("weight".into(), vec![0.0, 0.0, 3.0, 1.0]))
+            .unwrap();
+        assert_close(cloud.weighted_centroid("weight").unwrap(), [10.5, 4.0, 0.0]);
+
+        for bad in [vec![0.0; 4], vec![1.0, -1.0, 1.0, 1.0]] {
+            cloud
+                .set_attribute(Series::new("weight".into(), bad))
+                .unwrap();
+            assert!(cloud.weighted_centroid("weight").is_err());
+        }
+        assert!(cloud.weighted_centroid("missing").is_err());
+    }
+
+    #[test]
+    fn try_transform_names_the_first_bad_point() {
+        let message = |err: PcdError| match err {
+            PcdError::InvalidInput(message) => message,
+            other => panic!("unexpected error {other}"),
+        };
+        let cloud = TablePointCloud::from_xyz(
+            vec![1.0, 2.0, f64::NAN],
+            vec![0.0, f64::NAN, 1.0],
+            vec![0.0; 3],
+        )
+        .unwrap();
+        let err = cloud.try_transform(&Matrix4::identity()).unwrap_err();
+        assert!(message(err).starts_with("point 1 "));
+
+        // w = x + 2 vanishes for the second scattered point (x = -2).
+        let mut projective = Matrix4::identity();
+        projective[(3, 0)] = 1.0;
+        projective[(3, 3)] = 2.0;
+        let err = scattered().try_transform(&projective).unwrap_err();
+        assert!(message(err).starts_with("transform sends point 1 "));
+
+        let mut nan = Matrix4::identity();
+        nan[(2, 1)] = f64::NAN;
+        assert!(scattered().try_transform(&nan).is_err());
+        let shift = Matrix4::new_translation(&Vector3::new(1.0, 0.0, 0.0));
+        assert!(scattered()
+            .try_transform(&shift)
+            .unwrap()
+            .approx_eq(&scattered().transform(&shift).unwrap(), 0.0));
+    }
+
+    #[test]
+    fn recentering_preserves_precision_in_f32() {
+        let x: Vec<f64> = (0..50).map(|i| 500_000.0 + 0.013 * i as f64).collect();
+        let y: Vec<f64> = (0..50).map(|i| 4_100_000.0 + 0.007 * i as f64).collect();
+        let cloud = TablePointCloud::from_xyz(x, y, vec![35.25; 50]).unwrap();
+        // Worst error of the point-to-point steps once stored as f32.
+        let f32_error = |cloud: &TablePointCloud| {
+            let p = cloud.xyz().unwrap();
+            p.windows(2)
+                .flat_map(|w| {
+                    (0..3).map(move |axis| {
+                        let exact = w[1][axis] - w[0][axis];
+                        let rounded = w[1][axis] as f32 - w[0][axis] as f32;
+                        (exact - rounded as f64).abs()
+                    })
+                })
+                .fold(0.0, f64::max)
+        };
+
+        let (local, offset) = cloud.recenter_to_local().unwrap();
+        assert_eq!(offset, [500_000.0, 4_100_000.0, 35.25]);
+        assert_eq!(local.aabb().unwrap().0, [0.0; 3]);
+        assert_eq!(local.origin(), offset);
+        assert!(f32_error(&cloud) > 1e-3);
+        assert!(f32_error(&local) < 1e-6);
+
+        let global = local.global_xyz().unwrap();
+        for (a, b) in global.into_iter().zip(cloud.xyz().unwrap()) {
+            assert_close(a, b);
+        }
+        let restored = local.restore_global(offset).unwrap();
+        assert!(restored.approx_eq(&cloud, 1e-9));
+        assert_eq!(restored.origin(), [0.0; 3]);
+    }
+
+    #[test]
+    fn zero_normals_survive_and_singular_maps_are_rejected() {
+        let mut cloud =
+            TablePointCloud::from_xyz(vec![0.0; 2], vec![0.0; 2], vec![0.0; 2]).unwrap();
+        for (name, values) in [("nx", [1.0, 0.0]), ("ny", [0.0, 0.0]), ("nz", [0.0, 0.0])] {
+            cloud
+                .set_attribute(Series::new(name.into(), values.to_vec()))
+                .unwrap();
+        }
+        let rotation = Rotation3::from_axis_angle(&Vector3::z_axis(), FRAC_PI_2).to_homogeneous();
+        let normals = cloud.transform(&rotation).unwrap().normals().unwrap();
+        assert_close(normals[0], [0.0, 1.0, 0.0]);
+        assert_eq!(normals[1], [0.0; 3]);
+
+        let flatten = Matrix4::new_nonuniform_scaling(&Vector3::new(1.0, 1.0, 0.0));
+        assert!(cloud.transform(&flatten).is_err());
+        assert!(scattered().transform(&flatten).is_ok());
+    }
+}
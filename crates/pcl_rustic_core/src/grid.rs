@@ -0,0 +1,289 @@
+use std::collections::HashMap;
+
+use ndarray::Array3;
+use polars::prelude::*;
+
+use crate::TablePointCloud;
+
+/// Cell budget used by [`TablePointCloud::to_occupancy_grid_3d`]: 256 MiB of
+/// `u8` cells.
+pub const DEFAULT_MAX_GRID_CELLS: usize = 1 << 28;
+
+/// Cell counts along x, y and z.
+pub(crate) type GridDims = (usize, usize, usize);
+
+impl TablePointCloud {
+    /// Dense occupancy grid with `leaf_size` cells: 1 where a cell holds at
+    /// least one point, 0 elsewhere.
+    ///
+    /// Returns the grid indexed `[ix, iy, iz]` and its origin, the min corner
+    /// of the bounding box. Errors on an empty cloud or when the grid would
+    /// exceed [`DEFAULT_MAX_GRID_CELLS`].
+    pub fn to_occupancy_grid_3d(
+        &self,
+        leaf_size: f64,
+    ) -> Result<(Array3<u8>, [f64; 3]), PolarsError> {
+        self.to_occupancy_grid_3d_with_limit(leaf_size, DEFAULT_MAX_GRID_CELLS)
+    }
+
+    /// [`TablePointCloud::to_occupancy_grid_3d`] with an explicit cap on the
+    /// number of cells.
+    pub fn to_occupancy_grid_3d_with_limit(
+        &self,
+        leaf_size: f64,
+        max_cells: usize,
+    ) -> Result<(Array3<u8>, [f64; 3]), PolarsError> {
+        let (origin, dims) = self.grid_layout(leaf_size, max_cells)?;
+        let mut grid = Array3::<u8>::zeros(dims);
+        for p in self.xyz()? {
+            grid[cell_of(&p, &origin, leaf_size, dims)] = 1;
+        }
+        Ok((grid, origin))
+    }
+
+    /// Mean of `column` per voxel of a fixed `dims`-sized grid whose min
+    /// corner is `origin`, indexed `[ix, iy, iz]`.
+    ///
+    /// Points outside the grid and null/NaN values are ignored; voxels that
+    /// receive no value hold NaN.
+    pub fn voxelize_to_grid(
+        &self,
+        origin: [f64; 3],
+        voxel_size: f64,
+        dims: [usize; 3],
+        column: &str,
+    ) -> Result<Array3<f64>, PolarsError> {
+        if !(voxel_size.is_finite() && voxel_size > 0.0) {
+            polars_bail!(InvalidOperation: "voxel size must be positive and finite, got {}", voxel_size);
+        }
+        let values = self.column_f64(column)?;
+        let shape = (dims[0], dims[1], dims[2]);
+        let mut sums = Array3::<f64>::zeros(shape);
+        let mut counts = Array3::<u32>::zeros(shape);
+        for (p, value) in self.xyz()?.iter().zip(values) {
+            if value.is_nan() {
+                continue;
+            }
+            let cell = [0, 1, 2].map(|axis| ((p[axis] - origin[axis]) / voxel_size).floor());
+            if (0..3).any(|axis| !(cell[axis] >= 0.0 && cell[axis] < dims[axis] as f64)) {
+                continue;
+            }
+            let index = (cell[0] as usize, cell[1] as usize, cell[2] as usize);
+            sums[index] += value;
+            counts[index] += 1;
+        }
+        Ok(ndarray::Zip::from(&sums)
+            .and(&counts)
+            .map_collect(|&sum, &count| {
+                if count == 0 {
+                    f64::NAN
+                } else {
+                    sum / count as f64
+                }
+            }))
+    }
+
+    /// Splits the cloud into square XY tiles of side `tile_size`.
+    ///
+    /// Tiles are keyed by `(floor(x / tile_size), floor(y / tile_size))`,
+    /// so the layout is anchored at the world origin and matches across
+    /// clouds. Each tile keeps every attribute and its points' original
+    /// order; only non-empty tiles are returned.
+    pub fn tile_xy(&self, tile_size: f64) -> Result<HashMap<(i64, i64), Self>, PolarsError> {
+        if !(tile_size.is_finite() && tile_size > 0.0) {
+            polars_bail!(InvalidOperation: "tile size must be positive and finite, got {}", tile_size);
+        }
+        let mut members: HashMap<(i64, i64), Vec<usize>> = HashMap::new();
+        for (i, (x, y)) in self.x()?.into_iter().zip(self.y()?).enumerate() {
+            let key = (
+                (x / tile_size).floor() as i64,
+                (y / tile_size).floor() as i64,
+            );
+            members.entry(key).or_default().push(i);
+        }
+        members
+            .into_iter()
+            .map(|(key, indices)| Ok((key, self.take(&indices)?)))
+            .collect()
+    }
+
+    /// Origin and `[nx, ny, nz]` cell counts of a grid covering the cloud.
+    pub(crate) fn grid_layout(
+        &self,
+        leaf_size: f64,
+        max_cells: usize,
+    ) -> Result<([f64; 3], GridDims), PolarsError> {
+        if !(leaf_size.is_finite() && leaf_size > 0.0) {
+            polars_bail!(InvalidOperation: "leaf size must be positive and finite, got {}", leaf_size);
+        }
+        let (min, max) = self.aabb()?;
+        let dims = [0, 1, 2].map(|axis| ((max[axis] - min[axis]) / leaf_size).floor() + 1.0);
+        let cells = dims.iter().product::<f64>();
+        if cells.is_nan() || cells > max_cells as f64 {
+            polars_bail!(
+                ComputeError: "grid of {} x {} x {} cells exceeds the limit of {} cells",
+                dims[0], dims[1], dims[2], max_cells
+            );
+        }
+        Ok((min, (dims[0] as usize, dims[1] as usize, dims[2] as usize)))
+    }
+}
+
+/// Grid index of a point, clamped so the max corner lands in the last cell.
+pub(crate) fn cell_of(p: &[f64; 3], origin: &[f64; 3], leaf_size: f64, dims: GridDims) -> GridDims {
+    let index = |axis: usize, len: usize| {
+        (((p[axis] - origin[axis]) / leaf_size).floor() as usize).min(len - 1)
+    };
+    (index(0, dims.0), index(1, dims.1), index(2, dims.2))
+}
+
+/// Offsets to the six face neighbours of a cell.
+const FACE_NEIGHBORS: [[isize; 3]; 6] = [
+    [-1, 0, 0],
+    [1, 0, 0],
+    [0, -1, 0],
+    [0, 1, 0],
+    [0, 0, -1],
+    [0, 0, 1],
+];
+
+/// Morphological dilation of an occupancy grid with a 6-connected
+/// structuring element, applied `iterations` times: a cell becomes occupied
+/// when it or any face neighbour is. Non-zero cells count as occupied and
+/// the result holds 0/1.
+pub fn dilate(grid: &Array3<u8>, iterations: usize) -> Array3<u8> {
+    morph(grid, iterations, true)
+}
+
+/// Morphological erosion, the counterpart of [`dilate`]: a cell stays
+/// occupied only when it and every face neighbour are. Neighbours outside
+/// the grid are ignored, so a fully occupied grid is left unchanged.
+pub fn erode(grid: &Array3<u8>, iterations: usize) -> Array3<u8> {
+    morph(grid, iterations, false)
+}
+
+fn morph(grid: &Array3<u8>, iterations: usize, dilate: bool) -> Array3<u8> {
+    let mut current = grid.mapv(|cell| u8::from(cell != 0));
+    let dims = current.dim();
+    for _ in 0..iterations {
+        current = Array3::from_shape_fn(dims, |(i, j, k)| {
+            let neighbors = FACE_NEIGHBORS.iter().filter_map(|offset| {
+                let i = i.checked_add_signed(offset[0]).filter(|&i| i < dims.0)?;
+                let j = j.checked_add_signed(offset[1]).filter(|&j| j < dims.1)?;
+                let k = k.checked_add_signed(offset[2]).filter(|&k| k < dims.2)?;
+                Some(current[(i, j, k)])
+            });
+            let mut cells = std::iter::once(current[(i, j, k)]).chain(neighbors);
+            let occupied = if dilate {
+                cells.any(|cell| cell == 1)
+            } else {
+                cells.all(|cell| cell == 1)
+            };
+            u8::from(occupied)
+        });
+    }
+    current
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cloud(points: &[[f64; 3]]) -> TablePointCloud {
+        TablePointCloud::from_point_array(points).unwrap()
+    }
+
+    #[test]
+    fn occupancy_grid_marks_point_cells() {
+        let cloud = cloud(&[[1.0, 1.0, 1.0], [1.2, 1.1, 1.4], [3.0, 2.0, 1.0]]);
+        let (grid, origin) = cloud.to_occupancy_grid_3d(0.5).unwrap();
+        assert_eq!(origin, [1.0, 1.0, 1.0]);
+        assert_eq!(grid.dim(), (5, 3, 1));
+        assert_eq!(grid[(0, 0, 0)], 1);
+        assert_eq!(grid[(4, 2, 0)], 1);
+        assert_eq!(grid[(2, 1, 0)], 0);
+        assert_eq!(grid.sum(), 2);
+
+        assert!(cloud.to_occupancy_grid_3d_with_limit(0.5, 14).is_err());
+        assert!(cloud.to_occupancy_grid_3d(1e-9).is_err());
+    }
+
+    #[test]
+    fn tiles_a_two_by_two_layout() {
+        let mut points = Vec::new();
+        for (tx, ty, count) in [
+            (0.0, 0.0, 1),
+            (10.0, 0.0, 2),
+            (0.0, 10.0, 3),
+            (10.0, 10.0, 4),
+        ] {
+            for i in 0..count {
+                points.push([tx + 1.0 + i as f64, ty + 5.0, i as f64]);
+            }
+        }
+        let mut cloud = cloud(&points);
+        cloud
+            .set_attribute(Series::new("intensity".into(), vec![1.0; points.len()]))
+            .unwrap();
+
+        let tiles = cloud.tile_xy(10.0).unwrap();
+        assert_eq!(tiles.len(), 4);
+        let counts = [(0, 0), (1, 0), (0, 1), (1, 1)].map(|key| tiles[&key].len());
+        assert_eq!(counts, [1, 2, 3, 4]);
+        assert_eq!(tiles[&(1, 1)].attribute_names(), ["intensity"]);
+        assert_eq!(tiles[&(0, 1)].z().unwrap(), [0.0, 1.0, 2.0]);
+
+        assert!(cloud.tile_xy(0.0).is_err());
+    }
+
+    #[test]
+    fn voxel_grid_holds_cell_means() {
+        let mut cloud = cloud(&[
+            [0.1, 0.1, 0.1],
+            [0.4, 0.2, 0.3],
+            [1.5, 0.5, 0.5],
+            [1.6, 0.6, 0.4],
+            [1.7, 0.7, 0.3],
+            [5.0, 0.0, 0.0],
+        ]);
+        cloud
+            .set_attribute(Series::new(
+                "intensity".into(),
+                vec![Some(2.0), Some(4.0), Some(1.0), None, Some(3.0), Some(9.0)],
+            ))
+            .unwrap();
+
+        let grid = cloud
+            .voxelize_to_grid([0.0; 3], 1.0, [2, 2, 1], "intensity")
+            .unwrap();
+        assert_eq!(grid.dim(), (2, 2, 1));
+        assert_eq!(grid[(0, 0, 0)], 3.0);
+        // The null is skipped and the point at x = 5 lies outside the grid.
+        assert_eq!(grid[(1, 0, 0)], 2.0);
+        assert!(grid[(0, 1, 0)].is_nan());
+        assert!(grid[(1, 1, 0)].is_nan());
+    }
+
+    #[test]
+    fn dilating_a_single_cell() {
+        let mut grid = Array3::<u8>::zeros((5, 5, 5));
+        grid[(2, 2, 2)] = 7;
+        let once = dilate(&grid, 1);
+        assert_eq!(once.sum(), 7);
+        for offset in FACE_NEIGHBORS {
+            let cell = [0, 1, 2].map(|axis| (2 + offset[axis]) as usize);
+            assert_eq!(once[(cell[0], cell[1], cell[2])], 1);
+        }
+        assert_eq!(once[(1, 1, 2)], 0);
+        // The 6-connected ball of radius 2 holds 25 cells.
+        assert_eq!(dilate(&grid, 2).sum(), 25);
+        assert_eq!(erode(&once, 1).sum(), 1);
+
+        // Cells on the boundary must not reach outside the grid.
+        let mut corner = Array3::<u8>::zeros((2, 2, 2));
+        corner[(0, 0, 0)] = 1;
+        assert_eq!(dilate(&corner, 1).sum(), 4);
+        let full = Array3::<u8>::ones((2, 2, 2));
+        assert_eq!(erode(&full, 3), full);
+    }
+}
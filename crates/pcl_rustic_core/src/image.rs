@@ -0,0 +1,199 @@
+use ndarray::Array2;
+use polars::prelude::*;
+
+use crate::TablePointCloud;
+
+impl TablePointCloud {
+    /// Range image of an organized cloud: a `[row, col]` array of shape
+    /// `(height, width)` holding each pixel's distance from the sensor, the
+    /// local origin. Invalid (NaN) points give NaN pixels.
+    ///
+    /// Errors on an unorganized cloud, which has no pixel grid; project it
+    /// spherically instead.
+    pub fn to_range_image(&self) -> Result<Array2<f64>, PolarsError> {
+        let Some((width, height)) = self.organized_dims() else {
+            polars_bail!(InvalidOperation: "range image needs an organized cloud; use a spherical projection for an unorganized one");
+        };
+        let ranges: Vec<f64> = self
+            .xyz()?
+            .into_iter()
+            .map(|[x, y, z]| (x * x + y * y + z * z).sqrt())
+            .collect();
+        Array2::from_shape_vec((height, width), ranges)
+            .map_err(|err| polars_err!(ShapeMismatch: "range image: {}", err))
+    }
+
+    /// Back-projects a spinning-lidar range image into a cloud.
+    ///
+    /// Row `i` is the ring at elevation `ring_angles[i]` and column `j` the
+    /// azimuth `j * horizontal_resolution`, both in radians; a pixel of
+    /// range `r` becomes `r * (cos(el) cos(az), cos(el) sin(az), sin(el))`
+    /// about the sensor. Pixels whose range is NaN, infinite or not
+    /// positive are dropped; when none are, the result is organized with
+    /// the image's dimensions.
+    pub fn from_range_image(
+        img: &Array2<f64>,
+        ring_angles: &[f64],
+        horizontal_resolution: f64,
+    ) -> Result<Self, PolarsError> {
+        let (rows, cols) = img.dim();
+        if ring_angles.len() != rows {
+            polars_bail!(ShapeMismatch: "got {} ring angles for a range image of {} rows", ring_angles.len(), rows);
+        }
+        if !horizontal_resolution.is_finite() {
+            polars_bail!(InvalidOperation: "horizontal resolution must be finite, got {}", horizontal_resolution);
+        }
+        let mut x = Vec::with_capacity(img.len());
+        let mut y = Vec::with_capacity(img.len());
+        let mut z = Vec::with_capacity(img.len());
+        for ((row, col), &range) in img.indexed_iter() {
+            if !(range.is_finite() && range > 0.0) {
+                continue;
+            }
+            let (elevation, azimuth) = (ring_angles[row], col as f64 * horizontal_resolution);
+            x.push(range * elevation.cos() * azimuth.cos());
+            y.push(range * elevation.cos() * azimuth.sin());
+            z.push(range * elevation.sin());
+        }
+        let mut cloud = Self::from_xyz(x, y, z)?;
+        if cloud.len() == img.len() && !img.is_empty() {
+            cloud.set_organized(cols, rows)?;
+        }
+        Ok(cloud)
+    }
+}
+
+/// Edge-preserving smoothing of a range image.
+///
+/// Each valid pixel becomes the average of the valid pixels within
+/// `3 * spatial_sigma` of it, weighted by a Gaussian of their pixel
+/// distance (`spatial_sigma`) times a Gaussian of their range difference
+/// (`range_sigma`), so neighbors across a depth edge barely contribute.
+/// Pixels that are NaN, infinite or not positive count as invalid: they are
+/// left as is and never blurred into their neighbors. Non-positive or
+/// non-finite sigmas leave the image unchanged.
+pub fn bilateral_filter(
+    range_image: &Array2<f64>,
+    spatial_sigma: f64,
+    range_sigma: f64,
+) -> Array2<f64> {
+    let valid = |r: f64| r.is_finite() && r > 0.0;
+    let sigmas_usable = [spatial_sigma, range_sigma]
+        .iter()
+        .all(|s| s.is_finite() && *s > 0.0);
+    if !sigmas_usable {
+        return range_image.clone();
+    }
+    let (rows, cols) = range_image.dim();
+    let reach = (3.0 * spatial_sigma).ceil() as usize;
+    let (spatial_scale, range_scale) = (
+        -0.5 / (spatial_sigma * spatial_sigma),
+        -0.5 / (range_sigma * range_sigma),
+    );
+    Array2::from_shape_fn((rows, cols), |(row, col)| {
+        let center = range_image[(row, col)];
+        if !valid(center) {
+            return center;
+        }
+        let (mut sum, mut total) = (0.0, 0.0);
+        for r in row.saturating_sub(reach)..(row + reach + 1).min(rows) {
+            for c in col.saturating_sub(reach)..(col + reach + 1).min(cols) {
+                let value = range_image[(r, c)];
+                if !valid(value) {
+                    continue;
+                }
+                let (dr, dc) = (r.abs_diff(row) as f64, c.abs_diff(col) as f64);
+                let weight = ((dr * dr + dc * dc) * spatial_scale
+                    + (value - center).powi(2) * range_scale)
+                    .exp();
+                sum += weight * value;
+                total += weight;
+            }
+        }
+        sum / total
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn range_image_of_organized_cloud() {
+        let mut cloud = TablePointCloud::from_xyz(
+            vec![3.0, 0.0, 1.0, 0.0, f64::NAN, 2.0],
+            vec![4.0, 0.0, 2.0, 5.0, 0.0, 3.0],
+            vec![0.0, 2.0, 2.0, 12.0, 0.0, 6.0],
+        )
+        .unwrap();
+        assert!(cloud.to_range_image().is_err());
+
+        cloud.set_organized(3, 2).unwrap();
+        let image = cloud.to_range_image().unwrap();
+        assert_eq!(image.dim(), (2, 3));
+        assert_eq!(image.row(0).to_vec(), [5.0, 2.0, 3.0]);
+        assert_eq!(image[(1, 0)], 13.0);
+        assert!(image[(1, 1)].is_nan());
+        assert_eq!(image[(1, 2)], 7.0);
+    }
+
+    #[test]
+    fn bilateral_filter_keeps_a_step_edge() {
+        // Near wall (2 m) on the left, far wall (10 m) on the right, with a
+        // ripple on the near side and an invalid pixel in each half.
+        let mut image = Array2::from_shape_fn((8, 8), |(row, col)| {
+            if col < 4 {
+                2.0 + if (row + col) % 2 == 0 { 0.05 } else { -0.05 }
+            } else {
+                10.0
+            }
+        });
+        image[(3, 1)] = 0.0;
+        image[(5, 6)] = f64::NAN;
+
+        let filtered = bilateral_filter(&image, 1.5, 0.5);
+        for row in 0..8 {
+            for col in 0..8 {
+                let value = filtered[(row, col)];
+                match (row, col) {
+                    (3, 1) => assert_eq!(value, 0.0),
+                    (5, 6) => assert!(value.is_nan()),
+                    (_, col) if col < 4 => assert!((value - 2.0).abs() < 0.05, "{value}"),
+                    _ => assert!((value - 10.0).abs() < 1e-9, "{value}"),
+                }
+            }
+        }
+        // The ripple is damped.
+        assert!((filtered[(0, 0)] - 2.0).abs() < 0.5 * 0.05);
+        assert_eq!(bilateral_filter(&image, 0.0, 0.5)[(0, 0)], image[(0, 0)]);
+    }
+
+    #[test]
+    fn range_image_round_trip_recovers_points() {
+        let rings = [-0.2f64, -0.05, 0.1];
+        let (cols, resolution) = (16, std::f64::consts::TAU / 16.0);
+        let (mut x, mut y, mut z) = (Vec::new(), Vec::new(), Vec::new());
+        for (row, elevation) in rings.iter().enumerate() {
+            for col in 0..cols {
+                let range = 5.0 + row as f64 + 0.25 * col as f64;
+                let azimuth = col as f64 * resolution;
+                x.push(range * elevation.cos() * azimuth.cos());
+                y.push(range * elevation.cos() * azimuth.sin());
+                z.push(range * elevation.sin());
+            }
+        }
+        let mut cloud = TablePointCloud::from_xyz(x, y, z).unwrap();
+        cloud.set_organized(cols, rings.len()).unwrap();
+
+        let mut image = cloud.to_range_image().unwrap();
+        let restored = TablePointCloud::from_range_image(&image, &rings, resolution).unwrap();
+        assert!(restored.approx_eq(&cloud, 1e-9));
+        assert_eq!(restored.organized_dims(), Some((cols, rings.len())));
+
+        image[(1, 3)] = 0.0;
+        let sparse = TablePointCloud::from_range_image(&image, &rings, resolution).unwrap();
+        assert_eq!(sparse.len(), cloud.len() - 1);
+        assert_eq!(sparse.organized_dims(), None);
+        assert!(TablePointCloud::from_range_image(&image, &rings[..2], resolution).is_err());
+    }
+}
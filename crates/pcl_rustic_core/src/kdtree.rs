@@ -0,0 +1,385 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+use ndarray::{Array1, Array2, Axis};
+use polars::prelude::{polars_err, PolarsError};
+
+use crate::TablePointCloud;
+
+/// Subtrees smaller than this are built serially by
+/// [`KdTree::build_parallel`]; spawning tasks for them costs more than it
+/// saves.
+const PARALLEL_BUILD_THRESHOLD: usize = 1 << 14;
+
+#[derive(Debug, Clone, Copy)]
+struct Entry {
+    point: [f64; 3],
+    index: usize,
+}
+
+/// Squared distance paired with a point index, ordered by distance.
+#[derive(Debug, Clone, Copy)]
+struct Candidate {
+    dist2: f64,
+    index: usize,
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.dist2
+            .total_cmp(&other.dist2)
+            .then(self.index.cmp(&other.index))
+    }
+}
+
+/// A balanced 3D k-d tree over point indices.
+///
+/// Each tree is stored implicitly: every slice has its splitting node at the
+/// midpoint, with the split axis cycling x, y, z by depth. To support
+/// [`KdTree::insert`] the structure is a forest of such trees whose sizes
+/// grow in powers of two; queries visit all of them. Query results report
+/// the original point index and the Euclidean (not squared) distance.
+#[derive(Debug, Clone, Default)]
+pub struct KdTree {
+    /// `levels[i]` is either empty or a tree of `2^i..2^(i+1)` entries.
+    levels: Vec<Vec<Entry>>,
+}
+
+impl KdTree {
+    /// Builds a tree where each point's index is its position in `points`.
+    pub fn new(points: &[[f64; 3]]) -> Self {
+        let entries: Vec<Entry> = points
+            .iter()
+            .enumerate()
+            .map(|(index, &point)| Entry { point, index })
+            .collect();
+        let mut tree = Self::default();
+        tree.place(entries);
+        tree
+    }
+
+    /// Builds a tree over the coordinates of a cloud.
+    pub fn from_cloud(cloud: &TablePointCloud) -> Result<Self, PolarsError> {
+        Ok(Self::new(&cloud.xyz()?))
+    }
+
+    /// Builds a tree over the coordinates of a cloud, splitting subtrees
+    /// across a rayon pool of `threads` workers (the global pool when
+    /// `None`).
+    ///
+    /// The resulting tree is identical to [`KdTree::from_cloud`]'s, so every
+    /// query returns the same results.
+    pub fn build_parallel(
+        cloud: &TablePointCloud,
+        threads: Option<usize>,
+    ) -> Result<Self, PolarsError> {
+        let mut entries: Vec<Entry> = cloud
+            .xyz()?
+            .into_iter()
+            .enumerate()
+            .map(|(index, point)| Entry { point, index })
+            .collect();
+        let mut tree = Self::default();
+        if entries.is_empty() {
+            return Ok(tree);
+        }
+        match threads {
+            Some(threads) => rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()
+                .map_err(
+                    |err| polars_err!(ComputeError: "could not start k-d tree build pool: {}", err),
+                )?
+                .install(|| build_parallel(&mut entries, 0)),
+            None => build_parallel(&mut entries, 0),
+        }
+        let level = entries.len().ilog2() as usize;
+        tree.levels.resize_with(level + 1, Vec::new);
+        tree.levels[level] = entries;
+        Ok(tree)
+    }
+
+    pub fn len(&self) -> usize {
+        self.levels.iter().map(Vec::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.levels.iter().all(Vec::is_empty)
+    }
+
+    /// Adds a point reported as `index` by later queries.
+    ///
+    /// Rather than rebalancing, the new point is merged with the smaller
+    /// trees of the forest like a binary counter carry, so each point is
+    /// rebuilt `O(log n)` times overall and queries stay exact.
+    pub fn insert(&mut self, index: usize, point: [f64; 3]) {
+        self.place(vec![Entry { point, index }]);
+    }
+
+    /// Builds `carry` into the level matching its size, first absorbing any
+    /// tree already occupying that level.
+    fn place(&mut self, mut carry: Vec<Entry>) {
+        if carry.is_empty() {
+            return;
+        }
+        loop {
+            let level = carry.len().ilog2() as usize;
+            if self.levels.len() <= level {
+                self.levels.resize_with(level + 1, Vec::new);
+            }
+            if self.levels[level].is_empty() {
+                build(&mut carry, 0);
+                self.levels[level] = carry;
+                return;
+            }
+            carry.append(&mut self.levels[level]);
+        }
+    }
+
+    /// Closest point to `query`, or `None` for an empty tree.
+    pub fn nearest(&self, query: [f64; 3]) -> Option<(usize, f64)> {
+        self.knn(query, 1).into_iter().next()
+    }
+
+    /// The `k` closest points to `query`, nearest first.
+    pub fn knn(&self, query: [f64; 3], k: usize) -> Vec<(usize, f64)> {
+        if k == 0 {
+            return Vec::new();
+        }
+        let mut heap = BinaryHeap::with_capacity(k + 1);
+        for level in &self.levels {
+            knn_search(level, 0, &query, k, &mut heap);
+        }
+        finish(heap.into_sorted_vec())
+    }
+
+    /// All points within `radius` of `query` (inclusive), nearest first.
+    pub fn radius_search(&self, query: [f64; 3], radius: f64) -> Vec<(usize, f64)> {
+        let mut found = Vec::new();
+        for level in &self.levels {
+            radius_search(level, 0, &query, radius * radius, &mut found);
+        }
+        found.sort_unstable();
+        finish(found)
+    }
+}
+
+fn finish(candidates: Vec<Candidate>) -> Vec<(usize, f64)> {
+    candidates
+        .into_iter()
+        .map(|c| (c.index, c.dist2.sqrt()))
+        .collect()
+}
+
+fn dist2(a: &[f64; 3], b: &[f64; 3]) -> f64 {
+    (a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2) + (a[2] - b[2]).powi(2)
+}
+
+impl TablePointCloud {
+    /// Closest point to `query` by exhaustive search, or `None` for an
+    /// empty cloud.
+    ///
+    /// The distances come from one broadcast subtraction over the `(N, 3)`
+    /// coordinate matrix, which vectorizes well; for a single query on a
+    /// cloud of up to around a thousand points this is cheaper than
+    /// building a [`KdTree`]. Ties go to the lowest index.
+    pub fn nearest_brute(&self, query: [f64; 3]) -> Option<(usize, f64)> {
+        let points = self.xyz().ok()?;
+        let matrix = Array2::from_shape_vec((points.len(), 3), points.concat()).ok()?;
+        let dist2 = (matrix - Array1::from_vec(query.to_vec()))
+            .mapv(|d| d * d)
+            .sum_axis(Axis(1));
+        dist2
+            .iter()
+            .enumerate()
+            .filter(|(_, d)| !d.is_nan())
+            .min_by(|a, b| a.1.total_cmp(b.1))
+            .map(|(index, d)| (index, d.sqrt()))
+    }
+}
+
+fn build(entries: &mut [Entry], depth: usize) {
+    if entries.len() <= 1 {
+        return;
+    }
+    let axis = depth % 3;
+    let mid = entries.len() / 2;
+    entries.select_nth_unstable_by(mid, |a, b| a.point[axis].total_cmp(&b.point[axis]));
+    let (left, rest) = entries.split_at_mut(mid);
+    build(left, depth + 1);
+    build(&mut rest[1..], depth + 1);
+}
+
+/// [`build`] with the two halves of large slices built in parallel.
+fn build_parallel(entries: &mut [Entry], depth: usize) {
+    if entries.len() < PARALLEL_BUILD_THRESHOLD {
+        build(entries, depth);
+        return;
+    }
+    let axis = depth % 3;
+    let mid = entries.len() / 2;
+    entries.select_nth_unstable_by(mid, |a, b| a.point[axis].total_cmp(&b.point[axis]));
+    let (left, rest) = entries.split_at_mut(mid);
+    rayon::join(
+        || build_parallel(left, depth + 1),
+        || build_parallel(&mut rest[1..], depth + 1),
+    );
+}
+
+fn knn_search(
+    entries: &[Entry],
+    depth: usize,
+    query: &[f64; 3],
+    k: usize,
+    heap: &mut BinaryHeap<Candidate>,
+) {
+    if entries.is_empty() {
+        return;
+    }
+    let mid = entries.len() / 2;
+    let node = &entries[mid];
+    let candidate = Candidate {
+        dist2: dist2(&node.point, query),
+        index: node.index,
+    };
+    if heap.len() < k {
+        heap.push(candidate);
+    } else if heap.peek().is_some_and(|worst| candidate < *worst) {
+        heap.pop();
+        heap.push(candidate);
+    }
+
+    let axis = depth % 3;
+    let diff = query[axis] - node.point[axis];
+    let (near, far) = if diff < 0.0 {
+        (&entries[..mid], &entries[mid + 1..])
+    } else {
+        (&entries[mid + 1..], &entries[..mid])
+    };
+    knn_search(near, depth + 1, query, k, heap);
+    if heap.len() < k || heap.peek().is_some_and(|worst| diff * diff <= worst.dist2) {
+        knn_search(far, depth + 1, query, k, heap);
+    }
+}
+
+fn radius_search(
+    entries: &[Entry],
+    depth: usize,
+    query: &[f64; 3],
+    radius2: f64,
+    found: &mut Vec<Candidate>,
+) {
+    if entries.is_empty() {
+        return;
+    }
+    let mid = entries.len() / 2;
+    let node = &entries[mid];
+    let d2 = dist2(&node.point, query);
+    if d2 <= radius2 {
+        found.push(Candidate {
+            dist2: d2,
+            index: node.index,
+        });
+    }
+
+    let axis = depth % 3;
+    let diff = query[axis] - node.point[axis];
+    if diff <= 0.0 || diff * diff <= radius2 {
+        radius_search(&entries[..mid], depth + 1, query, radius2, found);
+    }
+    if diff >= 0.0 || diff * diff <= radius2 {
+        radius_search(&entries[mid + 1..], depth + 1, query, radius2, found);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    use super::*;
+
+    fn random_points(n: usize, seed: u64) -> Vec<[f64; 3]> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        (0..n)
+            .map(|_| std::array::from_fn(|_| rng.random_range(-10.0..10.0)))
+            .collect()
+    }
+
+    fn brute_nearest(points: &[[f64; 3]], query: [f64; 3]) -> (usize, f64) {
+        points
+            .iter()
+            .enumerate()
+            .map(|(index, p)| (index, dist2(p, &query).sqrt()))
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .unwrap()
+    }
+
+    #[test]
+    fn interleaved_inserts_match_brute_force() {
+        let points = random_points(300, 1);
+        let queries = random_points(20, 2);
+        let mut tree = KdTree::default();
+        for (index, &point) in points.iter().enumerate() {
+            tree.insert(index, point);
+            assert_eq!(tree.len(), index + 1);
+            for &query in &queries {
+                let (found, dist) = tree.nearest(query).unwrap();
+                let (expected, expected_dist) = brute_nearest(&points[..=index], query);
+                assert_eq!(dist, expected_dist);
+                assert_eq!(found, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn brute_force_matches_tree() {
+        for (n, seed) in [(1, 3), (50, 4), (800, 5)] {
+            let points = random_points(n, seed);
+            let cloud = TablePointCloud::from_point_array(&points).unwrap();
+            let tree = KdTree::from_cloud(&cloud).unwrap();
+            for query in random_points(25, seed + 100) {
+                let (index, dist) = cloud.nearest_brute(query).unwrap();
+                let (expected, expected_dist) = tree.nearest(query).unwrap();
+                assert!((dist - expected_dist).abs() < 1e-12);
+                assert_eq!(index, expected);
+            }
+        }
+        let empty = TablePointCloud::from_xyz(Vec::new(), Vec::new(), Vec::new()).unwrap();
+        assert_eq!(empty.nearest_brute([0.0; 3]), None);
+    }
+
+    #[test]
+    fn parallel_build_matches_serial_build() {
+        // Above the threshold, so the top splits really run in parallel.
+        let points = random_points(PARALLEL_BUILD_THRESHOLD * 3 + 17, 6);
+        let cloud = TablePointCloud::from_point_array(&points).unwrap();
+        let serial = KdTree::from_cloud(&cloud).unwrap();
+        let order = |tree: &KdTree| -> Vec<usize> {
+            tree.levels.iter().flatten().map(|e| e.index).collect()
+        };
+        for threads in [Some(1), Some(3), None] {
+            let parallel = KdTree::build_parallel(&cloud, threads).unwrap();
+            assert_eq!(order(&parallel), order(&serial));
+            for query in random_points(30, 7) {
+                assert_eq!(parallel.knn(query, 8), serial.knn(query, 8));
+            }
+        }
+        let empty = TablePointCloud::from_xyz(Vec::new(), Vec::new(), Vec::new()).unwrap();
+        assert!(KdTree::build_parallel(&empty, Some(2)).unwrap().is_empty());
+    }
+}
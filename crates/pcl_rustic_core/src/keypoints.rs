@@ -0,0 +1,137 @@
+use nalgebra::{Matrix3, Vector3};
+use polars::prelude::*;
+
+use crate::kdtree::KdTree;
+use crate::TablePointCloud;
+
+/// Fewest neighbors (the point included) within the salient radius for a
+/// point to be considered as an ISS keypoint.
+const ISS_MIN_NEIGHBORS: usize = 5;
+
+impl TablePointCloud {
+    /// Intrinsic Shape Signatures keypoints, as indices in ascending order.
+    ///
+    /// For every point the scatter matrix of its neighbors within
+    /// `salient_radius` gives eigenvalues `l1 >= l2 >= l3`. A point is a
+    /// candidate when `l2 / l1 < gamma21` and `l3 / l2 < gamma32`, which
+    /// rejects neighborhoods without distinct principal directions, and it
+    /// needs at least 5 neighbors. Candidates are then thinned by
+    /// non-maximum suppression: a candidate is kept only if its `l3`, which
+    /// measures variation in all three directions, exceeds that of every
+    /// other candidate within `non_max_radius` (ties go to the lower
+    /// index). Flat neighborhoods (`l3 == 0`) never qualify. Ratios of
+    /// 0.975 are a common choice.
+    pub fn detect_iss_keypoints(
+        &self,
+        salient_radius: f64,
+        non_max_radius: f64,
+        gamma21: f64,
+        gamma32: f64,
+    ) -> Result<Vec<usize>, PolarsError> {
+        for (name, radius) in [("salient", salient_radius), ("non-max", non_max_radius)] {
+            if !(radius.is_finite() && radius > 0.0) {
+                polars_bail!(InvalidOperation: "ISS {} radius must be positive and finite, got {}", name, radius);
+            }
+        }
+        let points = self.xyz()?;
+        let tree = KdTree::new(&points);
+
+        // Smallest eigenvalue of each candidate, NaN for rejected points.
+        let saliency: Vec<f64> = points
+            .iter()
+            .map(|&p| {
+                let neighbors = tree.radius_search(p, salient_radius);
+                if neighbors.len() < ISS_MIN_NEIGHBORS {
+                    return f64::NAN;
+                }
+                let n = neighbors.len() as f64;
+                let centroid = neighbors
+                    .iter()
+                    .map(|&(j, _)| Vector3::from(points[j]))
+                    .sum::<Vector3<f64>>()
+                    / n;
+                let scatter = neighbors.iter().fold(Matrix3::zeros(), |acc, &(j, _)| {
+                    let d = Vector3::from(points[j]) - centroid;
+                    acc + d * d.transpose()
+                }) / n;
+                let mut l: Vec<f64> = scatter.symmetric_eigenvalues().iter().cloned().collect();
+                l.sort_unstable_by(|a, b| b.total_cmp(a));
+                if l[2] > 0.0 && l[1] / l[0] < gamma21 && l[2] / l[1] < gamma32 {
+                    l[2]
+                } else {
+                    f64::NAN
+                }
+            })
+            .collect();
+
+        Ok((0..points.len())
+            .filter(|&i| !saliency[i].is_nan())
+            .filter(|&i| {
+                tree.radius_search(points[i], non_max_radius)
+                    .into_iter()
+                    .all(|(j, _)| {
+                        j == i
+                            || saliency[j].is_nan()
+                            || saliency[i] > saliency[j]
+                            || (saliency[i] == saliency[j] && i < j)
+                    })
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Surface of the cube `[0, 4]^3`, sampled on a 0.5 grid.
+    fn cube_surface() -> Vec<[f64; 3]> {
+        let steps: Vec<f64> = (0..=8).map(|i| 0.5 * i as f64).collect();
+        let mut points = Vec::new();
+        for &x in &steps {
+            for &y in &steps {
+                for &z in &steps {
+                    if [x, y, z].iter().any(|&v| v == 0.0 || v == 4.0) {
+                        points.push([x, y, z]);
+                    }
+                }
+            }
+        }
+        points
+    }
+
+    #[test]
+    fn keypoints_land_on_cube_corners() {
+        let points = cube_surface();
+        let cloud = TablePointCloud::from_point_array(&points).unwrap();
+        let keypoints = cloud.detect_iss_keypoints(1.5, 2.5, 0.975, 0.975).unwrap();
+
+        let corners: Vec<[f64; 3]> = (0..8)
+            .map(|c| [c & 1, c >> 1 & 1, c >> 2 & 1].map(|bit| 4.0 * bit as f64))
+            .collect();
+        let distance = |a: [f64; 3], b: [f64; 3]| (Vector3::from(a) - Vector3::from(b)).norm();
+        for &i in &keypoints {
+            let nearest = corners
+                .iter()
+                .map(|&c| distance(points[i], c))
+                .fold(f64::INFINITY, f64::min);
+            assert!(
+                nearest <= 1.0,
+                "keypoint {:?} is far from every corner",
+                points[i]
+            );
+        }
+        // One keypoint per corner, the rest suppressed.
+        assert_eq!(keypoints.len(), 8);
+        for &corner in &corners {
+            assert!(
+                keypoints
+                    .iter()
+                    .any(|&i| distance(points[i], corner) <= 1.0),
+                "no keypoint near corner {corner:?}"
+            );
+        }
+        assert!(keypoints.windows(2).all(|w| w[0] < w[1]));
+        assert!(cloud.detect_iss_keypoints(0.0, 1.0, 0.975, 0.975).is_err());
+    }
+}
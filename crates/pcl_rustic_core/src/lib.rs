@@ -1,3 +1,60 @@
+#[cfg(feature = "arrow")]
+mod arrow_interop;
+mod bounding;
+mod classification;
+pub mod cloud;
+mod cluster;
+mod color;
+mod compare;
+mod crop;
+mod descriptors;
+pub mod error;
+mod expr;
+mod features;
+mod geometry;
+mod grid;
+mod image;
+pub mod kdtree;
+mod keypoints;
+pub mod mesh;
+mod order;
+pub mod plane;
+pub mod point;
+pub mod registration;
+mod resample;
+mod sample;
+mod scanline;
+mod smooth;
+mod stats;
+mod time;
+pub mod transform;
+mod vertex;
+mod visibility;
+#[cfg(feature = "rerun")]
+mod viz;
+
+pub use classification::asprs_class_name;
+pub use cloud::{TablePointCloud, VectorAttribute, VectorKind, XyzOffsets};
+pub use color::Colormap;
+pub use compare::DEFAULT_MAX_DISTANCE_MATRIX_POINTS;
+pub use error::PcdError;
+pub use expr::{ColumnExpr, ThresholdMode};
+pub use features::Reduce;
+pub use grid::{dilate, erode, DEFAULT_MAX_GRID_CELLS};
+pub use image::bilateral_filter;
+pub use kdtree::KdTree;
+pub use mesh::Mesh;
+pub use plane::PlaneModel;
+pub use point::Point;
+pub use registration::{
+    estimate_rigid_transform, estimate_rigid_transform_weighted, estimate_similarity_transform,
+    find_correspondences, icp_point_to_point, ransac_feature_match, ransac_feature_match_with_seed,
+    IcpResult, IcpStep,
+};
+pub use resample::MAX_UPSAMPLE_FACTOR;
+pub use transform::{CoordinateFrame, Transform};
+pub use vertex::{VertexBuffer, VertexField};
+
 pub fn hello_from_core() -> String {
     "Hello from pcl_rustic core!".to_string()
 }
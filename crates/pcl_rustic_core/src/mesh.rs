@@ -0,0 +1,335 @@
+use std::collections::{HashSet, VecDeque};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use nalgebra::Vector3;
+
+use crate::kdtree::KdTree;
+use crate::{PcdError, TablePointCloud};
+
+/// Relative slack of the empty-ball test, so points lying on the ball's
+/// surface (as in regular grids) do not count as inside it.
+const EMPTY_BALL_TOLERANCE: f64 = 1e-7;
+
+/// A triangle mesh.
+///
+/// `faces` index into `vertices` and are wound counter-clockwise when seen
+/// from the side their normal points to.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Mesh {
+    pub vertices: Vec<[f64; 3]>,
+    pub faces: Vec<[usize; 3]>,
+}
+
+impl Mesh {
+    /// Writes the mesh as Wavefront OBJ text: one `v x y z` line per vertex,
+    /// then one `f a b c` line per face with 1-based indices.
+    pub fn write_obj<W: Write>(&self, mut writer: W) -> Result<(), PcdError> {
+        for [x, y, z] in &self.vertices {
+            writeln!(writer, "v {x} {y} {z}")?;
+        }
+        for [a, b, c] in &self.faces {
+            writeln!(writer, "f {} {} {}", a + 1, b + 1, c + 1)?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// [`Mesh::write_obj`] to a file at `path`, e.g. for import into
+    /// Blender.
+    pub fn to_obj<P: AsRef<Path>>(&self, path: P) -> Result<(), PcdError> {
+        self.write_obj(BufWriter::new(File::create(path)?))
+    }
+}
+
+/// A front edge `from -> to`, wound as in the face it came from, with that
+/// face's third vertex and the ball center resting on it.
+#[derive(Debug, Clone, Copy)]
+struct FrontEdge {
+    from: usize,
+    to: usize,
+    opposite: usize,
+    center: Vector3<f64>,
+}
+
+struct Pivoter<'a> {
+    points: Vec<Vector3<f64>>,
+    normals: Vec<Vector3<f64>>,
+    tree: &'a KdTree,
+    radius: f64,
+    /// Directed edges of the faces built so far; each may occur once, which
+    /// keeps the orientation consistent and every edge on at most two faces.
+    edges: HashSet<(usize, usize)>,
+    used: Vec<bool>,
+    faces: Vec<[usize; 3]>,
+}
+
+impl TablePointCloud {
+    /// Ball-pivoting surface reconstruction.
+    ///
+    /// A ball of `radius` is rolled over the points: a triangle is formed
+    /// wherever the ball touches three points with no other point inside
+    /// it, then the ball pivots around the triangle's edges to pick up
+    /// neighboring triangles until the front is exhausted, after which a new
+    /// seed triangle is searched. Faces are wound to agree with the
+    /// `nx`/`ny`/`nz` normals, which are required; triangles whose normal
+    /// disagrees with any of their vertex normals are rejected.
+    ///
+    /// The mesh vertices are the cloud's points in row order, so indices
+    /// line up with the cloud's attributes. `radius` should be somewhat
+    /// larger than the typical point spacing; holes appear where it is too
+    /// small and detail is lost where it is too large.
+    pub fn ball_pivoting(&self, radius: f64) -> Result<Mesh, PcdError> {
+        if !(radius.is_finite() && radius > 0.0) {
+            return Err(PcdError::InvalidInput(format!(
+                "ball radius must be positive and finite, got {radius}"
+            )));
+        }
+        let vertices = self.xyz()?;
+        let tree = KdTree::new(&vertices);
+        let mut pivoter = Pivoter {
+            points: vertices.iter().map(|&p| Vector3::from(p)).collect(),
+            normals: self
+                .normals()?
+                .into_iter()
+                .map(|n| Vector3::from(n).normalize())
+                .collect(),
+            tree: &tree,
+            radius,
+            edges: HashSet::new(),
+            used: vec![false; vertices.len()],
+            faces: Vec::new(),
+        };
+        for seed in 0..vertices.len() {
+            if pivoter.used[seed] {
+                continue;
+            }
+            if let Some(front) = pivoter.seed_triangle(seed) {
+                pivoter.expand(front);
+            }
+        }
+        Ok(Mesh {
+            vertices,
+            faces: pivoter.faces,
+        })
+    }
+}
+
+impl Pivoter<'_> {
+    /// Center of the ball of `self.radius` touching `a`, `b` and `c`, on the
+    /// side their counter-clockwise normal points to.
+    fn ball_center(&self, [a, b, c]: [usize; 3]) -> Option<Vector3<f64>> {
+        let (pa, pb, pc) = (self.points[a], self.points[b], self.points[c]);
+        let (ab, ac) = (pb - pa, pc - pa);
+        let normal = ab.cross(&ac);
+        let n2 = normal.norm_squared();
+        if n2 <= f64::EPSILON * ab.norm_squared() * ac.norm_squared() {
+            return None;
+        }
+        let offset = (normal.cross(&ab) * ac.norm_squared()
+            + ac.cross(&normal) * ab.norm_squared())
+            / (2.0 * n2);
+        let height2 = self.radius * self.radius - offset.norm_squared();
+        if height2 < 0.0 {
+            return None;
+        }
+        Some(pa + offset + normal / n2.sqrt() * height2.sqrt())
+    }
+
+    /// Whether the counter-clockwise normal of `face` agrees with the
+    /// normals of all its vertices.
+    fn normals_agree(&self, face: [usize; 3]) -> bool {
+        let [a, b, c] = face.map(|i| self.points[i]);
+        let normal = (b - a).cross(&(c - a));
+        face.iter().all(|&i| normal.dot(&self.normals[i]) > 0.0)
+    }
+
+    /// Whether no point other than the face's own lies inside the ball.
+    fn ball_is_empty(&self, center: &Vector3<f64>, face: [usize; 3]) -> bool {
+        let inner = self.radius * (1.0 - EMPTY_BALL_TOLERANCE);
+        self.tree
+            .radius_search([center.x, center.y, center.z], inner)
+            .iter()
+            .all(|(i, _)| face.contains(i))
+    }
+
+    fn face_edges([a, b, c]: [usize; 3]) -> [(usize, usize); 3] {
+        [(a, b), (b, c), (c, a)]
+    }
+
+    /// The ball center if `face` can be added: it is well formed, agrees
+    /// with the normals, rests on an empty ball and reuses no directed edge.
+    fn accept(&self, face: [usize; 3]) -> Option<Vector3<f64>> {
+        if Self::face_edges(face)
+            .iter()
+            .any(|edge| self.edges.contains(edge))
+            || !self.normals_agree(face)
+        {
+            return None;
+        }
+        let center = self.ball_center(face)?;
+        self.ball_is_empty(&center, face).then_some(center)
+    }
+
+    fn add_face(&mut self, face: [usize; 3]) {
+        self.edges.extend(Self::face_edges(face));
+        for i in face {
+            self.used[i] = true;
+        }
+        self.faces.push(face);
+    }
+
+    /// Finds a triangle on unused points around `seed`, adds it and returns
+    /// its edges as the initial front.
+    fn seed_triangle(&mut self, seed: usize) -> Option<Vec<FrontEdge>> {
+        let p = self.points[seed];
+        let neighbors: Vec<usize> = self
+            .tree
+            .radius_search([p.x, p.y, p.z], 2.0 * self.radius)
+            .into_iter()
+            .map(|(i, _)| i)
+            .filter(|&i| i != seed && !self.used[i])
+            .collect();
+        for (n, &j) in neighbors.iter().enumerate() {
+            for &k in &neighbors[n + 1..] {
+                for face in [[seed, j, k], [seed, k, j]] {
+                    if let Some(center) = self.accept(face) {
+                        self.add_face(face);
+                        return Some(front_of(face, center));
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// Pivots the ball around front edges until none can be extended.
+    fn expand(&mut self, front: Vec<FrontEdge>) {
+        let mut front: VecDeque<FrontEdge> = front.into();
+        while let Some(edge) = front.pop_front() {
+            // Already closed from the other side since it was queued.
+            if self.edges.contains(&(edge.to, edge.from)) {
+                continue;
+            }
+            if let Some((vertex, center)) = self.pivot(&edge) {
+                let face = [edge.to, edge.from, vertex];
+                self.add_face(face);
+                front.extend(
+                    front_of(face, center)
+                        .into_iter()
+                        .filter(|e| (e.from, e.to) != (edge.to, edge.from)),
+                );
+            }
+        }
+    }
+
+    /// The point the ball hits first when rolled over `edge`, away from the
+    /// face it rests on, with the new ball center.
+    fn pivot(&self, edge: &FrontEdge) -> Option<(usize, Vector3<f64>)> {
+        let (from, to) = (self.points[edge.from], self.points[edge.to]);
+        let mid = (from + to) / 2.0;
+        let axis = (to - from).normalize();
+        let start = edge.center - mid;
+        self.tree
+            .radius_search([mid.x, mid.y, mid.z], 2.0 * self.radius)
+            .into_iter()
+            .map(|(v, _)| v)
+            .filter(|&v| v != edge.from && v != edge.to && v != edge.opposite)
+            .filter_map(|v| {
+                let center = self.accept([edge.to, edge.from, v])?;
+                let end = center - mid;
+                let angle = axis.dot(&start.cross(&end)).atan2(start.dot(&end));
+                Some((angle.rem_euclid(std::f64::consts::TAU), v, center))
+            })
+            .min_by(|a, b| a.0.total_cmp(&b.0))
+            .map(|(_, v, center)| (v, center))
+    }
+}
+
+fn front_of([a, b, c]: [usize; 3], center: Vector3<f64>) -> Vec<FrontEdge> {
+    [(a, b, c), (b, c, a), (c, a, b)]
+        .into_iter()
+        .map(|(from, to, opposite)| FrontEdge {
+            from,
+            to,
+            opposite,
+            center,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use polars::prelude::*;
+
+    use super::*;
+
+    /// A `side` x `side` unit grid on z = 0 with upward normals.
+    fn planar_patch(side: usize) -> TablePointCloud {
+        let n = side * side;
+        let mut cloud = TablePointCloud::from_xyz(
+            (0..n).map(|i| (i % side) as f64).collect(),
+            (0..n).map(|i| (i / side) as f64).collect(),
+            vec![0.0; n],
+        )
+        .unwrap();
+        for (name, value) in ["nx", "ny", "nz"].into_iter().zip([0.0, 0.0, 1.0]) {
+            cloud
+                .set_attribute(Series::new(name.into(), vec![value; n]))
+                .unwrap();
+        }
+        cloud
+    }
+
+    #[test]
+    fn planar_patch_is_fully_triangulated() {
+        let cloud = planar_patch(6);
+        let mesh = cloud.ball_pivoting(0.8).unwrap();
+        assert_eq!(mesh.vertices, cloud.xyz().unwrap());
+
+        let mut area = 0.0;
+        let mut seen = HashSet::new();
+        for face in &mesh.faces {
+            assert!(face.iter().all(|&v| v < mesh.vertices.len()), "{face:?}");
+            let [a, b, c] = face.map(|v| Vector3::from(mesh.vertices[v]));
+            let normal = (b - a).cross(&(c - a));
+            // Wound counter-clockwise seen from above, like the normals.
+            assert!(normal.z > 0.0, "{face:?}");
+            area += normal.norm() / 2.0;
+            let mut key = *face;
+            key.sort_unstable();
+            assert!(seen.insert(key), "duplicate face {face:?}");
+        }
+        // 25 unit cells, two triangles each, with no overlap or hole.
+        assert_eq!(mesh.faces.len(), 50);
+        assert!((area - 25.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn ball_pivoting_needs_normals_and_a_radius() {
+        let bare =
+            TablePointCloud::from_xyz(vec![0.0, 1.0, 0.0], vec![0.0, 0.0, 1.0], vec![0.0; 3])
+                .unwrap();
+        assert!(bare.ball_pivoting(1.0).is_err());
+        assert!(planar_patch(3).ball_pivoting(0.0).is_err());
+    }
+
+    #[test]
+    fn single_triangle_obj_text() {
+        let mesh = Mesh {
+            vertices: vec![[0.0, 0.0, 0.0], [1.5, 0.0, 0.0], [0.0, 2.0, -0.25]],
+            faces: vec![[0, 1, 2]],
+        };
+        let expected = "v 0 0 0\nv 1.5 0 0\nv 0 2 -0.25\nf 1 2 3\n";
+        let mut text = Vec::new();
+        mesh.write_obj(&mut text).unwrap();
+        assert_eq!(String::from_utf8(text).unwrap(), expected);
+
+        let path = std::env::temp_dir().join(format!("pcl_rustic_mesh_{}.obj", std::process::id()));
+        mesh.to_obj(&path).unwrap();
+        let written = std::fs::read_to_string(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(written.unwrap(), expected);
+    }
+}
@@ -0,0 +1,138 @@
+use polars::prelude::*;
+
+use crate::TablePointCloud;
+
+impl TablePointCloud {
+    /// Reorders all rows by `column` using a stable polars sort, nulls
+    /// last. NaNs sort above every number.
+    pub fn sort_by(&self, column: &str, descending: bool) -> Result<Self, PolarsError> {
+        let order = self.data().column(column)?.arg_sort(SortOptions {
+            descending,
+            nulls_last: true,
+            maintain_order: true,
+            ..Default::default()
+        });
+        let order: Vec<usize> = order.into_no_null_iter().map(|i| i as usize).collect();
+        self.take(&order)
+    }
+
+    /// Reorders points along a Morton (Z-order) curve so that points close
+    /// in space end up close in storage.
+    ///
+    /// Coordinates are quantized to `bits` bits per axis (1 to 21) over the
+    /// bounding box and interleaved into a 64-bit code; points sharing a
+    /// code keep their relative order. An empty cloud is returned as is.
+    pub fn sort_morton(&self, bits: u32) -> Result<Self, PolarsError> {
+        if !(1..=21).contains(&bits) {
+            polars_bail!(InvalidOperation: "Morton codes need 1 to 21 bits per axis, got {}", bits);
+        }
+        if self.is_empty() {
+            return Ok(self.clone());
+        }
+        let (min, max) = self.aabb()?;
+        let cells = ((1u64 << bits) - 1) as f64;
+        let codes: Vec<u64> = self
+            .xyz()?
+            .iter()
+            .map(|p| {
+                let cell = |axis: usize| {
+                    let extent = max[axis] - min[axis];
+                    if extent > 0.0 {
+                        ((p[axis] - min[axis]) / extent * cells).round() as u64
+                    } else {
+                        0
+                    }
+                };
+                morton_code(cell(0), cell(1), cell(2), bits)
+            })
+            .collect();
+        let mut order: Vec<usize> = (0..self.len()).collect();
+        order.sort_by_key(|&i| codes[i]);
+        self.take(&order)
+    }
+}
+
+/// Interleaves the low `bits` bits of each axis as `...z1 y1 x1 z0 y0 x0`.
+fn morton_code(x: u64, y: u64, z: u64, bits: u32) -> u64 {
+    (0..bits).fold(0, |code, bit| {
+        code | ((x >> bit) & 1) << (3 * bit)
+            | ((y >> bit) & 1) << (3 * bit + 1)
+            | ((z >> bit) & 1) << (3 * bit + 2)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::seq::SliceRandom;
+    use rand::SeedableRng;
+
+    use super::*;
+
+    /// Mean distance between points adjacent in storage.
+    fn mean_step(cloud: &TablePointCloud) -> f64 {
+        let points = cloud.xyz().unwrap();
+        let total: f64 = points
+            .windows(2)
+            .map(|w| {
+                (0..3)
+                    .map(|a| (w[1][a] - w[0][a]).powi(2))
+                    .sum::<f64>()
+                    .sqrt()
+            })
+            .sum();
+        total / (points.len() - 1) as f64
+    }
+
+    #[test]
+    fn morton_sort_keeps_neighbours_close() {
+        let mut cells: Vec<(f64, f64)> = (0..16)
+            .flat_map(|i| (0..16).map(move |j| (i as f64, j as f64)))
+            .collect();
+        cells.shuffle(&mut StdRng::seed_from_u64(11));
+        let cloud = TablePointCloud::from_xyz(
+            cells.iter().map(|c| c.0).collect(),
+            cells.iter().map(|c| c.1).collect(),
+            vec![0.0; cells.len()],
+        )
+        .unwrap();
+
+        let sorted = cloud.sort_morton(4).unwrap();
+        assert_eq!(sorted.len(), cloud.len());
+        let (before, after) = (mean_step(&cloud), mean_step(&sorted));
+        assert!(after < 2.0, "mean step {after}");
+        assert!(after < before / 3.0, "{after} vs {before}");
+        // The first quadrant of the Z curve is visited before any other.
+        let first: Vec<[f64; 3]> = sorted.xyz().unwrap()[..64].to_vec();
+        assert!(first.iter().all(|p| p[0] < 8.0 && p[1] < 8.0));
+    }
+
+    #[test]
+    fn morton_code_interleaves_bits() {
+        assert_eq!(morton_code(0b11, 0b00, 0b01, 2), 0b001_101);
+        assert_eq!(morton_code(1, 1, 1, 1), 0b111);
+    }
+
+    #[test]
+    fn sort_by_keeps_attributes_aligned() {
+        let mut cloud = TablePointCloud::from_xyz(
+            vec![3.0, -1.0, 2.0, -1.0],
+            vec![30.0, 10.0, 20.0, 11.0],
+            vec![0.0; 4],
+        )
+        .unwrap();
+        cloud
+            .set_attribute(Series::new("id".into(), vec![3i32, 1, 2, 4]))
+            .unwrap();
+
+        let ascending = cloud.sort_by("x", false).unwrap();
+        assert_eq!(ascending.x().unwrap(), [-1.0, -1.0, 2.0, 3.0]);
+        assert_eq!(ascending.y().unwrap(), [10.0, 11.0, 20.0, 30.0]);
+        // Ties keep their storage order.
+        assert_eq!(ascending.attribute("id").unwrap(), [1.0, 4.0, 2.0, 3.0]);
+
+        let descending = cloud.sort_by("id", true).unwrap();
+        assert_eq!(descending.x().unwrap(), [-1.0, 3.0, 2.0, -1.0]);
+        assert!(cloud.sort_by("intensity", false).is_err());
+    }
+}
@@ -0,0 +1,148 @@
+use nalgebra::{Matrix3, Vector3};
+use polars::prelude::*;
+
+use crate::TablePointCloud;
+
+/// A plane `normal . p + d = 0` with a unit `normal`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlaneModel {
+    pub normal: [f64; 3],
+    pub d: f64,
+    /// Root-mean-square distance of the points the plane was fitted to;
+    /// 0 for a plane built with [`PlaneModel::new`].
+    pub rms_residual: f64,
+}
+
+impl PlaneModel {
+    /// Plane `normal . p + d = 0`; `normal` is normalized, scaling `d`
+    /// along with it. Errors on a zero or non-finite normal.
+    pub fn new(normal: [f64; 3], d: f64) -> Result<Self, PolarsError> {
+        let n = Vector3::from(normal);
+        let norm = n.norm();
+        if !(norm.is_finite() && norm > 0.0) {
+            polars_bail!(InvalidOperation: "plane normal must be non-zero and finite, got {:?}", normal);
+        }
+        Ok(Self {
+            normal: (n / norm).into(),
+            d: d / norm,
+            rms_residual: 0.0,
+        })
+    }
+
+    /// Signed distance of `point`, positive on the side the normal points to.
+    pub fn signed_distance(&self, point: [f64; 3]) -> f64 {
+        Vector3::from(self.normal).dot(&Vector3::from(point)) + self.d
+    }
+
+    /// [`PlaneModel::signed_distance`] of every point of `cloud`, in order.
+    pub fn signed_distances(&self, cloud: &TablePointCloud) -> Result<Vec<f64>, PolarsError> {
+        Ok(cloud
+            .xyz()?
+            .into_iter()
+            .map(|p| self.signed_distance(p))
+            .collect())
+    }
+}
+
+impl TablePointCloud {
+    /// Adds a `plane_distance` column with each point's signed distance to
+    /// `plane`.
+    pub fn distance_to_plane(&self, plane: &PlaneModel) -> Result<Self, PolarsError> {
+        let mut out = self.clone();
+        out.set_attribute(Series::new(
+            "plane_distance".into(),
+            plane.signed_distances(self)?,
+        ))?;
+        Ok(out)
+    }
+
+    /// Total-least-squares plane through the centroid, with the normal
+    /// along the covariance's smallest-eigenvalue eigenvector.
+    ///
+    /// Needs no iterations but is not robust to outliers. The normal's
+    /// sign is chosen so its largest-magnitude component is positive.
+    /// Errors with fewer than three points or when the points are
+    /// (nearly) collinear, which leaves the plane undetermined.
+    pub fn fit_plane_pca(&self) -> Result<PlaneModel, PolarsError> {
+        if self.len() < 3 {
+            polars_bail!(ComputeError: "plane fit needs at least 3 points, got {}", self.len());
+        }
+        let centroid = Vector3::from(self.centroid()?);
+        let points = self.xyz()?;
+        let covariance = points.iter().fold(Matrix3::zeros(), |acc, &p| {
+            let v = Vector3::from(p) - centroid;
+            acc + v * v.transpose()
+        }) / points.len() as f64;
+
+        let eigen = covariance.symmetric_eigen();
+        let mut order = [0, 1, 2];
+        order.sort_by(|&a, &b| eigen.eigenvalues[a].total_cmp(&eigen.eigenvalues[b]));
+        let (smallest, middle) = (eigen.eigenvalues[order[0]], eigen.eigenvalues[order[1]]);
+        if middle <= covariance.trace() * 1e-12 {
+            polars_bail!(ComputeError: "points are collinear, the plane is undetermined");
+        }
+        let mut normal: Vector3<f64> = eigen.eigenvectors.column(order[0]).normalize();
+        if normal[normal.iamax()] < 0.0 {
+            normal = -normal;
+        }
+        Ok(PlaneModel {
+            normal: normal.into(),
+            d: -normal.dot(&centroid),
+            rms_residual: smallest.max(0.0).sqrt(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    use super::*;
+
+    #[test]
+    fn pca_fit_recovers_noisy_plane() {
+        let normal = Vector3::new(1.0, 2.0, 5.0).normalize();
+        let u = normal.cross(&Vector3::x()).normalize();
+        let v = normal.cross(&u);
+        let offset = normal * 3.0;
+        let mut rng = StdRng::seed_from_u64(9);
+        let points: Vec<Vector3<f64>> = (0..400)
+            .map(|i| {
+                let (a, b) = ((i % 20) as f64 - 10.0, (i / 20) as f64 - 10.0);
+                offset + u * a + v * b + normal * rng.random_range(-0.01..0.01)
+            })
+            .collect();
+        let cloud = TablePointCloud::from_xyz(
+            points.iter().map(|p| p.x).collect(),
+            points.iter().map(|p| p.y).collect(),
+            points.iter().map(|p| p.z).collect(),
+        )
+        .unwrap();
+
+        let plane = cloud.fit_plane_pca().unwrap();
+        let angle = Vector3::from(plane.normal).angle(&normal);
+        assert!(angle < 0.1f64.to_radians(), "normal off by {angle} rad");
+        assert!((plane.d + 3.0).abs() < 1e-2, "d = {}", plane.d);
+        assert!(plane.rms_residual > 0.0 && plane.rms_residual < 0.01);
+
+        let line =
+            TablePointCloud::from_xyz(vec![0.0, 1.0, 2.0], vec![0.0; 3], vec![0.0; 3]).unwrap();
+        assert!(line.fit_plane_pca().is_err());
+    }
+
+    #[test]
+    fn signed_distances_to_z_zero_are_heights() {
+        let z = vec![-2.0, -0.5, 0.0, 1.25, 3.0];
+        let cloud = TablePointCloud::from_xyz(vec![1.0; 5], vec![-3.0; 5], z.clone()).unwrap();
+        // A scaled normal is normalized along with d.
+        let plane = PlaneModel::new([0.0, 0.0, 2.0], 0.0).unwrap();
+        assert_eq!(plane.signed_distances(&cloud).unwrap(), z);
+        let with_column = cloud.distance_to_plane(&plane).unwrap();
+        assert_eq!(with_column.attribute("plane_distance").unwrap(), z);
+
+        let lifted = PlaneModel::new([0.0, 0.0, -2.0], 2.0).unwrap();
+        let expected: Vec<f64> = z.iter().map(|z| 1.0 - z).collect();
+        assert_eq!(lifted.signed_distances(&cloud).unwrap(), expected);
+    }
+}
@@ -0,0 +1,511 @@
+use std::collections::HashMap;
+
+use polars::prelude::*;
+
+use crate::cloud::COORDINATE_COLUMNS;
+use crate::TablePointCloud;
+
+/// A single point with coordinates and named scalar attributes.
+///
+/// Integer attributes such as labels or ring numbers are kept separately
+/// in `int_attributes` so they stay exact.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Point<T> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+    pub attributes: HashMap<String, T>,
+    pub int_attributes: HashMap<String, i64>,
+}
+
+impl<T> Point<T> {
+    pub fn new(x: T, y: T, z: T) -> Self {
+        Self {
+            x,
+            y,
+            z,
+            attributes: HashMap::new(),
+            int_attributes: HashMap::new(),
+        }
+    }
+
+    /// Builder-style variant of [`Point::set_attribute`].
+    pub fn with_attribute(mut self, name: &str, value: T) -> Self {
+        self.set_attribute(name, value);
+        self
+    }
+
+    pub fn set_attribute(&mut self, name: &str, value: T) {
+        self.attributes.insert(name.to_string(), value);
+    }
+
+    pub fn get_attribute(&self, name: &str) -> Option<&T> {
+        self.attributes.get(name)
+    }
+
+    pub fn set_int_attribute(&mut self, name: &str, value: i64) {
+        self.int_attributes.insert(name.to_string(), value);
+    }
+
+    pub fn get_int_attribute(&self, name: &str) -> Option<i64> {
+        self.int_attributes.get(name).copied()
+    }
+}
+
+impl Point<f64> {
+    /// Linear interpolation `self + t * (other - self)`; `t = 0` gives
+    /// `self` and `t = 1` gives `other`.
+    ///
+    /// Float attributes present on both points are interpolated the same
+    /// way. Integer attributes are categorical, so a shared one takes the
+    /// value of the nearer endpoint (`self` for `t < 0.5`). Attributes found
+    /// on only one of the points are dropped.
+    pub fn lerp(&self, other: &Point<f64>, t: f64) -> Point<f64> {
+        let mix = |a: f64, b: f64| a + t * (b - a);
+        let mut point = Point::new(
+            mix(self.x, other.x),
+            mix(self.y, other.y),
+            mix(self.z, other.z),
+        );
+        for (name, &a) in &self.attributes {
+            if let Some(&b) = other.attributes.get(name) {
+                point.set_attribute(name, mix(a, b));
+            }
+        }
+        for (name, &a) in &self.int_attributes {
+            if let Some(&b) = other.int_attributes.get(name) {
+                point.set_int_attribute(name, if t < 0.5 { a } else { b });
+            }
+        }
+        point
+    }
+
+    /// Whether the coordinates and the attributes present on both points
+    /// agree within an absolute tolerance `tol`, for comparisons that
+    /// should not trip over rounding noise.
+    ///
+    /// Two NaNs count as equal, a NaN and a number do not. Shared integer
+    /// attributes must match exactly; attributes found on only one of the
+    /// points are ignored.
+    pub fn approx_eq(&self, other: &Point<f64>, tol: f64) -> bool {
+        let close = |a: f64, b: f64| (a.is_nan() && b.is_nan()) || (a - b).abs() <= tol;
+        close(self.x, other.x)
+            && close(self.y, other.y)
+            && close(self.z, other.z)
+            && self
+                .attributes
+                .iter()
+                .all(|(name, &a)| other.attributes.get(name).is_none_or(|&b| close(a, b)))
+            && self
+                .int_attributes
+                .iter()
+                .all(|(name, a)| other.int_attributes.get(name).is_none_or(|b| a == b))
+    }
+}
+
+impl TablePointCloud {
+    /// Builds a cloud from individual points.
+    ///
+    /// Every attribute name seen on any point becomes a column (sorted by
+    /// name): float attributes give `f64` columns with NaN for points
+    /// lacking them, integer attributes give `i64` columns with nulls. A
+    /// name used for both kinds becomes an `f64` column. Columns are
+    /// accumulated in a single pass over the points.
+    pub fn from_points(points: &[Point<f64>]) -> Result<Self, PolarsError> {
+        let n = points.len();
+        let (mut x, mut y, mut z) = (
+            Vec::with_capacity(n),
+            Vec::with_capacity(n),
+            Vec::with_capacity(n),
+        );
+        let mut floats: HashMap<&str, Vec<f64>> = HashMap::new();
+        let mut ints: HashMap<&str, Vec<Option<i64>>> = HashMap::new();
+        for (i, point) in points.iter().enumerate() {
+            x.push(point.x);
+            y.push(point.y);
+            z.push(point.z);
+            for (name, &value) in &point.attributes {
+                let values = floats
+                    .entry(name.as_str())
+                    .or_insert_with(|| Vec::with_capacity(n));
+                // Back-fill the points that came before without this key.
+                values.resize(i, f64::NAN);
+                values.push(value);
+            }
+            for (name, &value) in &point.int_attributes {
+                let values = ints
+                    .entry(name.as_str())
+                    .or_insert_with(|| Vec::with_capacity(n));
+                values.resize(i, None);
+                values.push(Some(value));
+            }
+        }
+
+        let mut columns: Vec<Series> = Vec::with_capacity(floats.len() + ints.len());
+        for (name, mut values) in ints {
+            values.resize(n, None);
+            match floats.get_mut(name) {
+                Some(merged) => {
+                    merged.resize(n, f64::NAN);
+                    for (slot, value) in merged.iter_mut().zip(values) {
+                        if let Some(value) = value {
+                            *slot = value as f64;
+                        }
+                    }
+                }
+                None => columns.push(Series::new(name.into(), values)),
+            }
+        }
+        for (name, mut values) in floats {
+            values.resize(n, f64::NAN);
+            columns.push(Series::new(name.into(), values));
+        }
+        columns.sort_unstable_by(|a, b| a.name().cmp(b.name()));
+
+        let mut cloud = Self::from_xyz(x, y, z)?;
+        for series in columns {
+            cloud.set_attribute(series)?;
+        }
+        Ok(cloud)
+    }
+
+    /// Reconstructs every row as a [`Point`]. Integer columns become
+    /// integer attributes and other numeric columns `f64` attributes; null
+    /// values are left out of the point.
+    pub fn to_points(&self) -> Result<Vec<Point<f64>>, PolarsError> {
+        let columns = PointColumns::new(self)?;
+        Ok((0..self.len()).map(|i| columns.point(i)).collect())
+    }
+
+    /// [`TablePointCloud::to_points`] populating only the attributes named
+    /// in `columns`, which avoids filling every point's maps when few
+    /// attributes are needed. Coordinates are always included, so naming
+    /// `x`, `y` or `z` has no effect; an empty slice gives bare points.
+    /// Errors on a missing column.
+    pub fn to_points_with(&self, columns: &[&str]) -> Result<Vec<Point<f64>>, PolarsError> {
+        let mut names: Vec<String> = Vec::with_capacity(columns.len());
+        for &name in columns {
+            self.data().column(name)?;
+            if !COORDINATE_COLUMNS.contains(&name) && !names.iter().any(|n| n == name) {
+                names.push(name.to_string());
+            }
+        }
+        let columns = PointColumns::with_attributes(self, names)?;
+        Ok((0..self.len()).map(|i| columns.point(i)).collect())
+    }
+
+    /// Folds `f` over the points as [`TablePointCloud::to_points`] would
+    /// build them, one at a time, without materializing the whole vector.
+    pub fn fold_points<B>(
+        &self,
+        init: B,
+        f: impl Fn(B, &Point<f64>) -> B,
+    ) -> Result<B, PolarsError> {
+        let columns = PointColumns::new(self)?;
+        Ok((0..self.len()).fold(init, |acc, i| f(acc, &columns.point(i))))
+    }
+
+    /// Lazily yields the points, built as by [`TablePointCloud::to_points`],
+    /// whose coordinates lie within the axis-aligned box `[min, max]`,
+    /// boundary included; collecting it matches
+    /// [`TablePointCloud::crop_box`] followed by `to_points`.
+    ///
+    /// Only the coordinates are read up front. Rows outside the box are
+    /// skipped before any attribute is touched, and the attributes of the
+    /// rows inside are read one value at a time, so no column is copied.
+    /// Unlike the other point accessors this returns no `Result`, to be
+    /// piped straight into a consumer: coordinates are numeric in every
+    /// cloud, and an attribute value that cannot be read as a number is
+    /// left out of the point, like a null.
+    pub fn points_within_box(
+        &self,
+        min: [f64; 3],
+        max: [f64; 3],
+    ) -> impl Iterator<Item = Point<f64>> + '_ {
+        let [x, y, z] = [self.x(), self.y(), self.z()].map(Result::unwrap_or_default);
+        let attributes: Vec<(String, &Column)> = self
+            .attribute_names()
+            .into_iter()
+            .filter_map(|name| {
+                let column = self.data().column(&name).ok()?;
+                Some((name, column))
+            })
+            .collect();
+        (0..x.len().min(y.len()).min(z.len())).filter_map(move |i| {
+            let p = [x[i], y[i], z[i]];
+            if !(0..3).all(|axis| p[axis] >= min[axis] && p[axis] <= max[axis]) {
+                return None;
+            }
+            let mut point = Point::new(p[0], p[1], p[2]);
+            for (name, column) in &attributes {
+                let Ok(value) = column.get(i) else { continue };
+                if column.dtype().is_integer() {
+                    if let Some(value) = value.extract::<i64>() {
+                        point.set_int_attribute(name, value);
+                    }
+                } else if let Some(value) = value.extract::<f64>() {
+                    point.set_attribute(name, value);
+                }
+            }
+            Some(point)
+        })
+    }
+}
+
+/// Columns cached for row-wise point reconstruction.
+pub(crate) struct PointColumns {
+    x: Vec<f64>,
+    y: Vec<f64>,
+    z: Vec<f64>,
+    floats: Vec<(String, Vec<Option<f64>>)>,
+    ints: Vec<(String, Vec<Option<i64>>)>,
+}
+
+impl PointColumns {
+    pub(crate) fn new(cloud: &TablePointCloud) -> Result<Self, PolarsError> {
+        Self::with_attributes(cloud, cloud.attribute_names())
+    }
+
+    /// Caches only the named attribute columns, which must exist.
+    pub(crate) fn with_attributes(
+        cloud: &TablePointCloud,
+        names: Vec<String>,
+    ) -> Result<Self, PolarsError> {
+        let mut floats = Vec::new();
+        let mut ints = Vec::new();
+        for name in names {
+            let column = cloud.data().column(&name)?;
+            if column.dtype().is_integer() {
+                let column = column.cast(&DataType::Int64)?;
+                ints.push((name, column.i64()?.iter().collect()));
+            } else {
+                let column = column.cast(&DataType::Float64)?;
+                floats.push((name, column.f64()?.iter().collect()));
+            }
+        }
+        Ok(Self {
+            x: cloud.x()?,
+            y: cloud.y()?,
+            z: cloud.z()?,
+            floats,
+            ints,
+        })
+    }
+
+    pub(crate) fn point(&self, i: usize) -> Point<f64> {
+        let mut point = Point::new(self.x[i], self.y[i], self.z[i]);
+        for (name, values) in &self.floats {
+            if let Some(value) = values[i] {
+                point.set_attribute(name, value);
+            }
+        }
+        for (name, values) in &self.ints {
+            if let Some(value) = values[i] {
+                point.set_int_attribute(name, value);
+            }
+        }
+        point
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::*;
+
+    /// The original per-key `from_points`: collect every key, then look
+    /// each one up on every point.
+    fn from_points_per_key(points: &[Point<f64>]) -> TablePointCloud {
+        let keys: BTreeSet<&String> = points.iter().flat_map(|p| p.attributes.keys()).collect();
+        let mut cloud = TablePointCloud::from_xyz(
+            points.iter().map(|p| p.x).collect(),
+            points.iter().map(|p| p.y).collect(),
+            points.iter().map(|p| p.z).collect(),
+        )
+        .unwrap();
+        for key in keys {
+            let values: Vec<f64> = points
+                .iter()
+                .map(|p| p.get_attribute(key).copied().unwrap_or(f64::NAN))
+                .collect();
+            cloud
+                .set_attribute(Series::new(key.as_str().into(), values))
+                .unwrap();
+        }
+        cloud
+    }
+
+    #[test]
+    fn single_pass_from_points_matches_per_key_build() {
+        let names = ["intensity", "range", "reflectivity", "time"];
+        let points: Vec<Point<f64>> = (0..2000)
+            .map(|i| {
+                let v = i as f64;
+                let mut point = Point::new(v, -v, 0.5 * v);
+                // Vary which attributes each point carries, including
+                // keys that first appear late.
+                for (k, name) in names.iter().enumerate() {
+                    if (i + k) % (k + 2) != 0 && (k < 3 || i > 1500) {
+                        point.set_attribute(name, v * (k + 1) as f64);
+                    }
+                }
+                point
+            })
+            .collect();
+
+        let fast = TablePointCloud::from_points(&points).unwrap();
+        let reference = from_points_per_key(&points);
+        assert_eq!(fast.attribute_names(), names);
+        assert!(fast.approx_eq(&reference, 0.0));
+    }
+
+    #[test]
+    fn large_integer_attribute_round_trips() {
+        let mut a = Point::new(0.0, 0.0, 0.0);
+        a.set_int_attribute("classification", 2_147_483_647);
+        a.set_int_attribute("ring", 9_007_199_254_740_993);
+        let mut b = Point::new(1.0, 0.0, 0.0);
+        b.set_int_attribute("classification", 2);
+
+        let cloud = TablePointCloud::from_points(&[a, b]).unwrap();
+        assert_eq!(
+            cloud.data().column("classification").unwrap().dtype(),
+            &DataType::Int64
+        );
+        let points = cloud.to_points().unwrap();
+        assert_eq!(
+            points[0].get_int_attribute("classification"),
+            Some(2_147_483_647)
+        );
+        // 2^53 + 1 is not representable as f64, so this only holds for an
+        // integer column.
+        assert_eq!(
+            points[0].get_int_attribute("ring"),
+            Some(9_007_199_254_740_993)
+        );
+        assert_eq!(points[1].get_int_attribute("classification"), Some(2));
+        assert_eq!(points[1].get_int_attribute("ring"), None);
+    }
+
+    #[test]
+    fn lerp_halfway_is_the_midpoint() {
+        let mut a = Point::new(0.0, 2.0, -4.0)
+            .with_attribute("intensity", 10.0)
+            .with_attribute("only_a", 1.0);
+        a.set_int_attribute("label", 3);
+        let mut b = Point::new(2.0, 4.0, 4.0).with_attribute("intensity", 20.0);
+        b.set_int_attribute("label", 7);
+
+        let mid = a.lerp(&b, 0.5);
+        assert_eq!((mid.x, mid.y, mid.z), (1.0, 3.0, 0.0));
+        assert_eq!(mid.get_attribute("intensity"), Some(&15.0));
+        assert_eq!(mid.get_attribute("only_a"), None);
+        assert_eq!(mid.get_int_attribute("label"), Some(7));
+        assert_eq!(a.lerp(&b, 0.25).get_int_attribute("label"), Some(3));
+        assert!(a.lerp(&b, 0.0).approx_eq(&a, 0.0));
+    }
+
+    #[test]
+    fn fold_sums_coordinates() {
+        let mut cloud = TablePointCloud::sample_box([-1.0; 3], [4.0; 3], 200, 3).unwrap();
+        cloud
+            .set_attribute(Series::new("weight".into(), vec![2.0; 200]))
+            .unwrap();
+        let (sum, weight) = cloud
+            .fold_points(([0.0; 3], 0.0), |(sum, weight), p| {
+                (
+                    [sum[0] + p.x, sum[1] + p.y, sum[2] + p.z],
+                    weight + p.get_attribute("weight").unwrap(),
+                )
+            })
+            .unwrap();
+        let centroid = cloud.centroid().unwrap();
+        for axis in 0..3 {
+            assert!((sum[axis] - centroid[axis] * 200.0).abs() < 1e-9);
+        }
+        assert_eq!(weight, 400.0);
+    }
+
+    #[test]
+    fn points_within_box_match_the_cropped_cloud() {
+        let mut cloud = TablePointCloud::sample_box([-2.0; 3], [2.0; 3], 500, 11).unwrap();
+        let n = cloud.len();
+        cloud
+            .set_attribute(Series::new(
+                "intensity".into(),
+                (0..n).map(|i| i as f64 * 0.5).collect::<Vec<_>>(),
+            ))
+            .unwrap();
+        cloud
+            .set_attribute(Series::new(
+                "label".into(),
+                (0..n as i32)
+                    .map(|i| (i % 3 != 0).then_some(i % 5))
+                    .collect::<Vec<_>>(),
+            ))
+            .unwrap();
+        let (min, max) = ([-1.0, -0.5, 0.0], [1.0, 1.5, 2.0]);
+
+        let streamed: Vec<Point<f64>> = cloud.points_within_box(min, max).collect();
+        let cropped = cloud.crop_box(min, max).unwrap().to_points().unwrap();
+        assert!(!streamed.is_empty() && streamed.len() < n);
+        assert_eq!(streamed, cropped);
+        assert_eq!(cloud.points_within_box([3.0; 3], [4.0; 3]).count(), 0);
+    }
+
+    #[test]
+    fn approx_eq_tolerates_rounding_noise() {
+        let mut a = Point::new(1.0, 2.0, 3.0)
+            .with_attribute("intensity", 0.3)
+            .with_attribute("range", f64::NAN);
+        a.set_int_attribute("label", 4);
+        let mut b = Point::new(1.0 + 1e-12, 2.0 - 1e-12, 3.0)
+            .with_attribute("intensity", 0.1 + 0.2)
+            .with_attribute("range", f64::NAN)
+            .with_attribute("only_b", 9.0);
+        b.set_int_attribute("label", 4);
+        assert_ne!(a, b);
+        assert!(a.approx_eq(&b, 1e-9));
+        assert!(b.approx_eq(&a, 1e-9));
+
+        assert!(!a.approx_eq(&Point::new(1.1, 2.0, 3.0), 1e-3));
+        assert!(!a.approx_eq(&b.clone().with_attribute("intensity", 0.5), 1e-3));
+        assert!(!a.approx_eq(&b.clone().with_attribute("range", 1.0), 1e-3));
+        let mut relabeled = b.clone();
+        relabeled.set_int_attribute("label", 5);
+        assert!(!a.approx_eq(&relabeled, 1e-3));
+        assert!(!Point::new(f64::NAN, 0.0, 0.0).approx_eq(&Point::new(0.0, 0.0, 0.0), 1.0));
+    }
+
+    #[test]
+    fn to_points_with_fills_only_requested_attributes() {
+        let mut cloud =
+            TablePointCloud::from_xyz(vec![1.0, 2.0], vec![3.0, 4.0], vec![5.0, 6.0]).unwrap();
+        cloud
+            .set_attribute(Series::new("intensity".into(), vec![0.5, 0.75]))
+            .unwrap();
+        cloud
+            .set_attribute(Series::new("label".into(), vec![Some(3i32), None]))
+            .unwrap();
+        cloud
+            .set_attribute(Series::new("range".into(), vec![10.0, 20.0]))
+            .unwrap();
+
+        let points = cloud
+            .to_points_with(&["label", "x", "intensity", "label"])
+            .unwrap();
+        assert_eq!(points.len(), 2);
+        assert_eq!((points[1].x, points[1].y, points[1].z), (2.0, 4.0, 6.0));
+        assert_eq!(points[0].get_attribute("intensity"), Some(&0.5));
+        assert_eq!(points[0].get_int_attribute("label"), Some(3));
+        assert_eq!(points[1].get_int_attribute("label"), None);
+        assert!(points
+            .iter()
+            .all(|p| p.get_attribute("range").is_none() && p.get_attribute("x").is_none()));
+
+        let bare = cloud.to_points_with(&[]).unwrap();
+        assert_eq!(bare[0], Point::new(1.0, 3.0, 5.0));
+        assert!(cloud.to_points_with(&["missing"]).is_err());
+    }
+}
@@ -0,0 +1,631 @@
+use nalgebra::{Matrix3, Matrix4, Point3, Vector3};
+use ndarray::{Array2, ArrayView1};
+use polars::prelude::{polars_bail, PolarsError};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use crate::kdtree::KdTree;
+use crate::{PcdError, TablePointCloud};
+
+/// Estimates the rigid transform (rotation and translation, no scaling)
+/// mapping `src` onto `dst` in the least-squares sense.
+///
+/// Implements the Kabsch/Umeyama solution on the cross-covariance of the
+/// centered correspondences, with a reflection fix so the result is a
+/// proper rotation. `src[i]` must correspond to `dst[i]`; at least three
+/// non-collinear correspondences are required.
+pub fn estimate_rigid_transform(
+    src: &[[f64; 3]],
+    dst: &[[f64; 3]],
+) -> Result<Matrix4<f64>, PcdError> {
+    umeyama(src, dst, &vec![1.0; src.len()], false).map(|(matrix, _)| matrix)
+}
+
+/// Like [`estimate_rigid_transform`], but minimizing the weighted sum of
+/// squared residuals, so correspondences with a larger `weights[i]` pull
+/// harder on the solution.
+///
+/// Weights must be finite and non-negative with a positive sum; the
+/// correspondences with positive weight must satisfy the requirements of
+/// the unweighted estimate.
+pub fn estimate_rigid_transform_weighted(
+    src: &[[f64; 3]],
+    dst: &[[f64; 3]],
+    weights: &[f64],
+) -> Result<Matrix4<f64>, PcdError> {
+    umeyama(src, dst, weights, false).map(|(matrix, _)| matrix)
+}
+
+/// Like [`estimate_rigid_transform`], but also estimates a uniform scale
+/// (Umeyama with scaling).
+///
+/// Returns the 4x4 similarity transform, whose linear block is the scaled
+/// rotation, together with the scale factor.
+pub fn estimate_similarity_transform(
+    src: &[[f64; 3]],
+    dst: &[[f64; 3]],
+) -> Result<(Matrix4<f64>, f64), PcdError> {
+    umeyama(src, dst, &vec![1.0; src.len()], true)
+}
+
+/// Pairs every source point with its nearest target point, as
+/// `(source_index, target_index, distance)` in source order.
+///
+/// With `max_distance`, pairs farther apart than it are dropped, as an
+/// outlier gate before solving with [`estimate_rigid_transform`]. An empty
+/// target yields no pairs.
+pub fn find_correspondences(
+    source: &TablePointCloud,
+    target: &TablePointCloud,
+    max_distance: Option<f64>,
+) -> Result<Vec<(usize, usize, f64)>, PolarsError> {
+    if let Some(max) = max_distance {
+        if max.is_nan() || max < 0.0 {
+            polars_bail!(InvalidOperation: "maximum correspondence distance must be non-negative, got {}", max);
+        }
+    }
+    let tree = KdTree::from_cloud(target)?;
+    Ok(source
+        .xyz()?
+        .into_iter()
+        .enumerate()
+        .filter_map(|(i, p)| {
+            let (j, distance) = tree.nearest(p)?;
+            max_distance
+                .is_none_or(|max| distance <= max)
+                .then_some((i, j, distance))
+        })
+        .collect())
+}
+
+/// One iteration of [`icp_point_to_point`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IcpStep {
+    /// RMS distance of this iteration's correspondences after its update.
+    pub rmse: f64,
+    /// Frobenius norm of `delta - I`, where `delta` is the update applied
+    /// on top of the previous estimate.
+    pub delta_norm: f64,
+    /// Number of correspondences the update was solved from.
+    pub correspondences: usize,
+}
+
+/// Outcome of [`icp_point_to_point`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct IcpResult {
+    /// Transform mapping the source onto the target.
+    pub transform: Matrix4<f64>,
+    /// RMSE of the last iteration, as in [`IcpStep::rmse`].
+    pub rmse: f64,
+    /// Iterations run.
+    pub iterations: usize,
+    /// Whether an update fell below the tolerance before the iteration
+    /// limit.
+    pub converged: bool,
+    /// One entry per iteration when history was requested, otherwise
+    /// empty.
+    pub iteration_log: Vec<IcpStep>,
+}
+
+/// Point-to-point ICP aligning `source` to `target`.
+///
+/// Each iteration pairs every moved source point with its nearest target
+/// point (dropping pairs farther than `max_distance`, if given), solves
+/// the update with [`estimate_rigid_transform`] and composes it with the
+/// estimate so far. Iteration stops after `max_iterations` or once the
+/// update's [`IcpStep::delta_norm`] drops below `tolerance`. With
+/// `record_history`, every iteration is logged in
+/// [`IcpResult::iteration_log`] for convergence plots.
+///
+/// Errors when an iteration is left with fewer than three usable
+/// correspondences.
+pub fn icp_point_to_point(
+    source: &TablePointCloud,
+    target: &TablePointCloud,
+    max_iterations: usize,
+    max_distance: Option<f64>,
+    tolerance: f64,
+    record_history: bool,
+) -> Result<IcpResult, PcdError> {
+    if let Some(max) = max_distance {
+        if max.is_nan() || max < 0.0 {
+            return Err(PcdError::InvalidInput(format!(
+                "maximum correspondence distance must be non-negative, got {max}"
+            )));
+        }
+    }
+    if tolerance.is_nan() || tolerance < 0.0 {
+        return Err(PcdError::InvalidInput(format!(
+            "ICP tolerance must be non-negative, got {tolerance}"
+        )));
+    }
+    let source_points = source.xyz()?;
+    let target_points = target.xyz()?;
+    let tree = KdTree::new(&target_points);
+    let mut result = IcpResult {
+        transform: Matrix4::identity(),
+        rmse: f64::NAN,
+        iterations: 0,
+        converged: false,
+        iteration_log: Vec::new(),
+    };
+    while result.iterations < max_iterations {
+        let (src, dst): (Vec<[f64; 3]>, Vec<[f64; 3]>) = source_points
+            .iter()
+            .filter_map(|&p| {
+                let moved = apply(&result.transform, p);
+                let (j, distance) = tree.nearest(moved)?;
+                max_distance
+                    .is_none_or(|max| distance <= max)
+                    .then_some((moved, target_points[j]))
+            })
+            .unzip();
+        let delta = estimate_rigid_transform(&src, &dst)?;
+        result.transform = delta * result.transform;
+        result.iterations += 1;
+
+        let step = IcpStep {
+            rmse: (src
+                .iter()
+                .zip(&dst)
+                .map(|(&s, &d)| (Vector3::from(apply(&delta, s)) - Vector3::from(d)).norm_squared())
+                .sum::<f64>()
+                / src.len() as f64)
+                .sqrt(),
+            delta_norm: (delta - Matrix4::identity()).norm(),
+            correspondences: src.len(),
+        };
+        result.rmse = step.rmse;
+        if record_history {
+            result.iteration_log.push(step);
+        }
+        if step.delta_norm < tolerance {
+            result.converged = true;
+            break;
+        }
+    }
+    Ok(result)
+}
+
+/// Edge-length agreement required between a sampled source triangle and
+/// its matched target triangle, as the ratio of the shorter to the longer.
+const RANSAC_EDGE_SIMILARITY: f64 = 0.9;
+
+/// Coarse global registration from feature matches, returning the
+/// transform mapping `src` onto `dst`.
+///
+/// Uses a fixed seed; see [`ransac_feature_match_with_seed`].
+pub fn ransac_feature_match(
+    src: &TablePointCloud,
+    dst: &TablePointCloud,
+    src_feat: &Array2<f64>,
+    dst_feat: &Array2<f64>,
+    distance_threshold: f64,
+    max_iter: usize,
+) -> Result<Matrix4<f64>, PcdError> {
+    ransac_feature_match_with_seed(
+        src,
+        dst,
+        src_feat,
+        dst_feat,
+        distance_threshold,
+        max_iter,
+        0,
+    )
+}
+
+/// [`ransac_feature_match`] with an explicit seed for the sampling.
+///
+/// Every source point is matched to the target point with the nearest
+/// descriptor (one row per point, e.g. from
+/// [`TablePointCloud::compute_fpfh`]). Each of `max_iter` rounds samples
+/// three matches, skips them unless the source and target triangles have
+/// similar edge lengths, and solves a rigid transform from them; matches
+/// that this transform brings within `distance_threshold` are its
+/// inliers. The hypothesis with the most inliers wins and is refined on
+/// all of them, ready to seed [`icp_point_to_point`].
+///
+/// Errors when the descriptors do not line up with the clouds or no round
+/// produced a valid hypothesis.
+pub fn ransac_feature_match_with_seed(
+    src: &TablePointCloud,
+    dst: &TablePointCloud,
+    src_feat: &Array2<f64>,
+    dst_feat: &Array2<f64>,
+    distance_threshold: f64,
+    max_iter: usize,
+    seed: u64,
+) -> Result<Matrix4<f64>, PcdError> {
+    if !(distance_threshold.is_finite() && distance_threshold > 0.0) {
+        return Err(PcdError::InvalidInput(format!(
+            "inlier distance threshold must be positive and finite, got {distance_threshold}"
+        )));
+    }
+    if src_feat.nrows() != src.len() || dst_feat.nrows() != dst.len() {
+        return Err(PcdError::InvalidInput(format!(
+            "expected one descriptor per point, got {} for {} source and {} for {} target points",
+            src_feat.nrows(),
+            src.len(),
+            dst_feat.nrows(),
+            dst.len()
+        )));
+    }
+    if src_feat.ncols() != dst_feat.ncols() {
+        return Err(PcdError::InvalidInput(format!(
+            "descriptor lengths differ: {} vs {}",
+            src_feat.ncols(),
+            dst_feat.ncols()
+        )));
+    }
+    let dst_points = dst.xyz()?;
+    let (src_matched, dst_matched): (Vec<[f64; 3]>, Vec<[f64; 3]>) = src
+        .xyz()?
+        .into_iter()
+        .zip(src_feat.rows())
+        .filter_map(|(p, feature)| {
+            nearest_descriptor(&feature, dst_feat).map(|j| (p, dst_points[j]))
+        })
+        .collect();
+    if src_matched.len() < 3 {
+        return Err(PcdError::InvalidInput(format!(
+            "at least 3 feature matches are required, got {}",
+            src_matched.len()
+        )));
+    }
+
+    let inliers = |matrix: &Matrix4<f64>| -> Vec<usize> {
+        (0..src_matched.len())
+            .filter(|&i| {
+                let moved = Vector3::from(apply(matrix, src_matched[i]));
+                (moved - Vector3::from(dst_matched[i])).norm() <= distance_threshold
+            })
+            .collect()
+    };
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut best: Vec<usize> = Vec::new();
+    for _ in 0..max_iter {
+        let sample = rand::seq::index::sample(&mut rng, src_matched.len(), 3).into_vec();
+        let (s, d): (Vec<[f64; 3]>, Vec<[f64; 3]>) = sample
+            .iter()
+            .map(|&i| (src_matched[i], dst_matched[i]))
+            .unzip();
+        let similar = (0..3).all(|a| {
+            let b = (a + 1) % 3;
+            let ls = (Vector3::from(s[a]) - Vector3::from(s[b])).norm();
+            let ld = (Vector3::from(d[a]) - Vector3::from(d[b])).norm();
+            ls.min(ld) >= RANSAC_EDGE_SIMILARITY * ls.max(ld)
+        });
+        if !similar {
+            continue;
+        }
+        let Ok(matrix) = estimate_rigid_transform(&s, &d) else {
+            continue;
+        };
+        let candidate = inliers(&matrix);
+        if candidate.len() > best.len() {
+            best = candidate;
+        }
+    }
+    let (s, d): (Vec<[f64; 3]>, Vec<[f64; 3]>) = best
+        .iter()
+        .map(|&i| (src_matched[i], dst_matched[i]))
+        .unzip();
+    estimate_rigid_transform(&s, &d).map_err(|_| {
+        PcdError::InvalidInput(format!(
+            "RANSAC found no valid hypothesis in {max_iter} iterations"
+        ))
+    })
+}
+
+/// Row of `features` closest to `query` in Euclidean distance, skipping
+/// rows with NaNs.
+fn nearest_descriptor(query: &ArrayView1<f64>, features: &Array2<f64>) -> Option<usize> {
+    features
+        .rows()
+        .into_iter()
+        .enumerate()
+        .map(|(j, row)| {
+            let d2: f64 = row.iter().zip(query).map(|(a, b)| (a - b) * (a - b)).sum();
+            (j, d2)
+        })
+        .filter(|(_, d2)| !d2.is_nan())
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(j, _)| j)
+}
+
+fn apply(matrix: &Matrix4<f64>, [x, y, z]: [f64; 3]) -> [f64; 3] {
+    let p = matrix.transform_point(&Point3::new(x, y, z));
+    [p.x, p.y, p.z]
+}
+
+fn umeyama(
+    src: &[[f64; 3]],
+    dst: &[[f64; 3]],
+    weights: &[f64],
+    with_scale: bool,
+) -> Result<(Matrix4<f64>, f64), PcdError> {
+    check_correspondences(src, dst)?;
+    check_weights(weights, src.len())?;
+    if weights.contains(&0.0) {
+        // Zero-weight pairs do not constrain the fit; what remains must.
+        let (src_used, dst_used): (Vec<[f64; 3]>, Vec<[f64; 3]>) = src
+            .iter()
+            .zip(dst)
+            .zip(weights)
+            .filter(|(_, &w)| w > 0.0)
+            .map(|((&s, &d), _)| (s, d))
+            .unzip();
+        check_correspondences(&src_used, &dst_used)?;
+    }
+    let (src_centroid, dst_centroid) = (centroid(src, weights), centroid(dst, weights));
+    let covariance = cross_covariance(src, dst, weights, &src_centroid, &dst_centroid);
+
+    let svd = covariance.svd(true, true);
+    let (u, v) = (svd.u.unwrap(), svd.v_t.unwrap().transpose());
+    let reflection = Vector3::new(1.0, 1.0, (v * u.transpose()).determinant().signum());
+    let rotation = v * Matrix3::from_diagonal(&reflection) * u.transpose();
+
+    let scale = if with_scale {
+        let src_variance: f64 = src
+            .iter()
+            .zip(weights)
+            .map(|(&p, &w)| w * (Vector3::from(p) - src_centroid).norm_squared())
+            .sum();
+        svd.singular_values.dot(&reflection) / src_variance
+    } else {
+        1.0
+    };
+    let linear = rotation * scale;
+    let translation = dst_centroid - linear * src_centroid;
+    Ok((assemble(&linear, &translation), scale))
+}
+
+fn check_weights(weights: &[f64], len: usize) -> Result<(), PcdError> {
+    if weights.len() != len {
+        return Err(PcdError::InvalidInput(format!(
+            "got {} weights for {} correspondences",
+            weights.len(),
+            len
+        )));
+    }
+    if weights.iter().any(|w| !w.is_finite() || *w < 0.0) {
+        return Err(PcdError::InvalidInput(
+            "weights must be finite and non-negative".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn check_correspondences(src: &[[f64; 3]], dst: &[[f64; 3]]) -> Result<(), PcdError> {
+    if src.len() != dst.len() {
+        return Err(PcdError::InvalidInput(format!(
+            "correspondence sets differ in length: {} vs {}",
+            src.len(),
+            dst.len()
+        )));
+    }
+    if src.len() < 3 {
+        return Err(PcdError::InvalidInput(format!(
+            "at least 3 correspondences are required, got {}",
+            src.len()
+        )));
+    }
+    if is_collinear(src) || is_collinear(dst) {
+        return Err(PcdError::InvalidInput(
+            "correspondences are collinear, the rotation is not determined".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Weighted mean of `points`; the weights must have a positive sum.
+fn centroid(points: &[[f64; 3]], weights: &[f64]) -> Vector3<f64> {
+    let total: f64 = weights.iter().sum();
+    points
+        .iter()
+        .zip(weights)
+        .map(|(&p, &w)| Vector3::from(p) * w)
+        .sum::<Vector3<f64>>()
+        / total
+}
+
+/// `sum(w * (s - cs) * (d - cd)^T)` over all correspondences.
+fn cross_covariance(
+    src: &[[f64; 3]],
+    dst: &[[f64; 3]],
+    weights: &[f64],
+    src_centroid: &Vector3<f64>,
+    dst_centroid: &Vector3<f64>,
+) -> Matrix3<f64> {
+    src.iter()
+        .zip(dst)
+        .zip(weights)
+        .map(|((&s, &d), &w)| {
+            (Vector3::from(s) - src_centroid) * (Vector3::from(d) - dst_centroid).transpose() * w
+        })
+        .sum()
+}
+
+/// A point set is collinear when its scatter has fewer than two
+/// significant principal directions.
+fn is_collinear(points: &[[f64; 3]]) -> bool {
+    let ones = vec![1.0; points.len()];
+    let c = centroid(points, &ones);
+    let scatter = cross_covariance(points, points, &ones, &c, &c);
+    let mut eigenvalues: Vec<f64> = scatter.symmetric_eigenvalues().iter().cloned().collect();
+    eigenvalues.sort_unstable_by(|a, b| b.total_cmp(a));
+    eigenvalues[1] <= eigenvalues[0].max(f64::MIN_POSITIVE) * 1e-12
+}
+
+fn assemble(linear: &Matrix3<f64>, translation: &Vector3<f64>) -> Matrix4<f64> {
+    let mut m = Matrix4::identity();
+    m.fixed_view_mut::<3, 3>(0, 0).copy_from(linear);
+    m.fixed_view_mut::<3, 1>(0, 3).copy_from(translation);
+    m
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::Rotation3;
+    use rand::Rng;
+
+    use super::*;
+
+    fn known_pose() -> Matrix4<f64> {
+        let mut pose = Rotation3::from_euler_angles(0.3, -0.2, 1.1).to_homogeneous();
+        pose.fixed_view_mut::<3, 1>(0, 3)
+            .copy_from(&Vector3::new(1.0, -2.0, 0.5));
+        pose
+    }
+
+    fn corners() -> Vec<[f64; 3]> {
+        vec![
+            [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [0.0, 2.0, 0.0],
+            [0.0, 0.0, 3.0],
+            [1.0, 1.0, 1.0],
+        ]
+    }
+
+    fn assert_matrix_close(actual: &Matrix4<f64>, expected: &Matrix4<f64>, tol: f64) {
+        assert!(
+            (actual - expected).abs().max() < tol,
+            "{actual} != {expected}"
+        );
+    }
+
+    #[test]
+    fn rigid_transform_recovers_known_pose() {
+        let pose = known_pose();
+        let src = corners();
+        let dst: Vec<[f64; 3]> = src.iter().map(|&p| apply(&pose, p)).collect();
+        let estimate = estimate_rigid_transform(&src, &dst).unwrap();
+        assert_matrix_close(&estimate, &pose, 1e-9);
+    }
+
+    #[test]
+    fn rigid_transform_rejects_bad_correspondences() {
+        let src = corners();
+        assert!(estimate_rigid_transform(&src, &src[..4]).is_err());
+        let line = [[0.0, 0.0, 0.0], [1.0, 1.0, 1.0], [2.0, 2.0, 2.0]];
+        assert!(estimate_rigid_transform(&line, &line).is_err());
+    }
+
+    #[test]
+    fn similarity_transform_recovers_scale() {
+        let pose = known_pose();
+        let src = corners();
+        let dst: Vec<[f64; 3]> = src
+            .iter()
+            .map(|&p| apply(&pose, p.map(|v| v * 2.0)))
+            .collect();
+        let (estimate, scale) = estimate_similarity_transform(&src, &dst).unwrap();
+        assert!((scale - 2.0).abs() < 1e-9, "scale {scale}");
+        assert_matrix_close(&estimate, &(pose * Matrix4::new_scaling(2.0)), 1e-9);
+    }
+
+    #[test]
+    fn correspondences_pair_shifted_points_with_their_originals() {
+        let target_points = corners();
+        let source_points: Vec<[f64; 3]> = target_points
+            .iter()
+            .map(|p| [p[0] + 0.1, p[1] - 0.05, p[2]])
+            .collect();
+        let cloud = |points: &[[f64; 3]]| TablePointCloud::from_point_array(points).unwrap();
+        let (source, target) = (cloud(&source_points), cloud(&target_points));
+        let shift = (0.1f64.powi(2) + 0.05f64.powi(2)).sqrt();
+
+        let pairs = find_correspondences(&source, &target, None).unwrap();
+        assert_eq!(pairs.len(), target_points.len());
+        for (k, &(i, j, distance)) in pairs.iter().enumerate() {
+            assert_eq!((i, j), (k, k));
+            assert!((distance - shift).abs() < 1e-12);
+        }
+
+        // Moving one source point away leaves it unpaired under the gate.
+        let mut far = source_points.clone();
+        far[2] = [10.0, 10.0, 10.0];
+        let gated = find_correspondences(&cloud(&far), &target, Some(0.5)).unwrap();
+        assert_eq!(
+            gated.iter().map(|&(i, j, _)| (i, j)).collect::<Vec<_>>(),
+            [(0, 0), (1, 1), (3, 3), (4, 4)]
+        );
+        assert!(find_correspondences(&source, &target, Some(-1.0)).is_err());
+        let empty = cloud(&[]);
+        assert!(find_correspondences(&source, &empty, None)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn zero_weight_ignores_an_outlier() {
+        let pose = known_pose();
+        let src = corners();
+        let mut dst: Vec<[f64; 3]> = src.iter().map(|&p| apply(&pose, p)).collect();
+        dst[4] = [50.0, -20.0, 7.0];
+        assert!(estimate_rigid_transform(&src, &dst).unwrap() != pose);
+        let estimate =
+            estimate_rigid_transform_weighted(&src, &dst, &[1.0, 2.0, 1.0, 0.5, 0.0]).unwrap();
+        assert_matrix_close(&estimate, &pose, 1e-9);
+        assert!(estimate_rigid_transform_weighted(&src, &dst, &[0.0; 5]).is_err());
+    }
+
+    #[test]
+    fn icp_log_tracks_a_converging_run() {
+        let source = TablePointCloud::sample_box([-2.0; 3], [2.0; 3], 300, 8).unwrap();
+        let mut pose = Rotation3::from_euler_angles(0.04, -0.03, 0.06).to_homogeneous();
+        pose.fixed_view_mut::<3, 1>(0, 3)
+            .copy_from(&Vector3::new(0.1, -0.05, 0.08));
+        let target = source.transform(&pose).unwrap();
+
+        let result = icp_point_to_point(&source, &target, 50, None, 1e-10, true).unwrap();
+        assert!(result.converged);
+        assert_eq!(result.iteration_log.len(), result.iterations);
+        assert!(result
+            .iteration_log
+            .windows(2)
+            .all(|w| w[1].rmse <= w[0].rmse + 1e-12));
+        assert!(result
+            .iteration_log
+            .iter()
+            .all(|s| s.correspondences == 300));
+        assert_eq!(result.rmse, result.iteration_log.last().unwrap().rmse);
+        assert!(result.rmse < 1e-9);
+        assert_matrix_close(&result.transform, &pose, 1e-8);
+
+        let quiet = icp_point_to_point(&source, &target, 50, None, 1e-10, false).unwrap();
+        assert!(quiet.iteration_log.is_empty());
+        assert_eq!(quiet.iterations, result.iterations);
+    }
+
+    #[test]
+    fn ransac_recovers_a_large_rotation_despite_bad_matches() {
+        let src = TablePointCloud::sample_box([-3.0; 3], [3.0; 3], 200, 12).unwrap();
+        let mut pose = Rotation3::from_euler_angles(1.2, -0.6, 2.5).to_homogeneous();
+        pose.fixed_view_mut::<3, 1>(0, 3)
+            .copy_from(&Vector3::new(4.0, -1.0, 2.0));
+        let dst = src.transform(&pose).unwrap();
+
+        // Distinct descriptors per point, with every third target row
+        // swapped so a third of the matches are wrong.
+        let mut rng = StdRng::seed_from_u64(13);
+        let src_feat = Array2::from_shape_fn((200, 8), |_| rng.random_range(0.0..1.0));
+        let mut dst_feat = src_feat.clone();
+        for i in (0..198).step_by(3) {
+            for c in 0..8 {
+                dst_feat.swap((i, c), (i + 1, c));
+            }
+        }
+
+        let estimate =
+            ransac_feature_match_with_seed(&src, &dst, &src_feat, &dst_feat, 0.05, 200, 1).unwrap();
+        assert_matrix_close(&estimate, &pose, 1e-6);
+        let unseeded = ransac_feature_match(&src, &dst, &src_feat, &dst_feat, 0.05, 200).unwrap();
+        assert_matrix_close(&unseeded, &pose, 1e-6);
+
+        let short = src_feat.slice(ndarray::s![..10, ..]).to_owned();
+        assert!(ransac_feature_match(&src, &dst, &short, &dst_feat, 0.05, 200).is_err());
+        assert!(ransac_feature_match(&src, &dst, &src_feat, &dst_feat, 0.0, 200).is_err());
+    }
+}
@@ -0,0 +1,96 @@
+use std::collections::HashSet;
+
+use polars::prelude::*;
+
+use crate::kdtree::KdTree;
+use crate::TablePointCloud;
+
+/// Upper bound on [`TablePointCloud::upsample_grid`] output, as a multiple
+/// of the input size.
+pub const MAX_UPSAMPLE_FACTOR: usize = 16;
+
+impl TablePointCloud {
+    /// Densifies the cloud by adding points every `step` along the segment
+    /// from each point to each of its `k` nearest neighbors.
+    ///
+    /// The original points come first, unchanged. Added points interpolate
+    /// every numeric attribute linearly (as `f64`) and leave other
+    /// attributes null; each neighbor pair is filled once. Output stops
+    /// growing at [`MAX_UPSAMPLE_FACTOR`] times the input size, leaving
+    /// the remaining segments unfilled.
+    pub fn upsample_grid(&self, k: usize, step: f64) -> Result<Self, PolarsError> {
+        if !(step.is_finite() && step > 0.0) {
+            polars_bail!(InvalidOperation: "upsampling step must be positive and finite, got {}", step);
+        }
+        let points = self.xyz()?;
+        let tree = KdTree::new(&points);
+        let budget = points.len() * (MAX_UPSAMPLE_FACTOR - 1);
+
+        let mut samples: Vec<(usize, usize, f64)> = Vec::new();
+        let mut filled: HashSet<(usize, usize)> = HashSet::new();
+        'points: for (i, &p) in points.iter().enumerate() {
+            for (j, dist) in tree.knn(p, k + 1) {
+                if j == i || !filled.insert((i.min(j), i.max(j))) {
+                    continue;
+                }
+                let count = ((dist / step).ceil() as usize).saturating_sub(1);
+                for s in 1..=count {
+                    if samples.len() == budget {
+                        break 'points;
+                    }
+                    samples.push((i, j, s as f64 / (count + 1) as f64));
+                }
+            }
+        }
+
+        let interpolate = |values: &[f64]| -> Vec<f64> {
+            samples
+                .iter()
+                .map(|&(i, j, t)| values[i] + t * (values[j] - values[i]))
+                .collect()
+        };
+        let mut added = Self::from_xyz(
+            interpolate(&self.x()?),
+            interpolate(&self.y()?),
+            interpolate(&self.z()?),
+        )?;
+        added.set_origin(self.origin());
+        for name in self.attribute_names() {
+            if self.data().column(&name)?.dtype().is_primitive_numeric() {
+                let values = interpolate(&self.column_f64(&name)?);
+                added.set_attribute(Series::new(name.as_str().into(), values))?;
+            }
+        }
+        Self::concat(&[self, &added])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn upsampling_grows_within_the_cap() {
+        let x: Vec<f64> = (0..10).map(|i| i as f64).collect();
+        let mut cloud = TablePointCloud::from_xyz(x.clone(), vec![0.0; 10], vec![0.0; 10]).unwrap();
+        cloud
+            .set_attribute(Series::new("intensity".into(), x.clone()))
+            .unwrap();
+
+        // Unit spacing at step 0.25 puts three points in each of the nine
+        // gaps.
+        let dense = cloud.upsample_grid(1, 0.25).unwrap();
+        assert_eq!(dense.len(), 10 + 9 * 3);
+        assert!(dense.slice(0, 10).approx_eq(&cloud, 0.0));
+        let added = dense.slice(10, dense.len() - 10);
+        assert_eq!(added.x().unwrap(), added.attribute("intensity").unwrap());
+
+        let capped = cloud.upsample_grid(9, 1e-3).unwrap();
+        assert_eq!(capped.len(), 10 * MAX_UPSAMPLE_FACTOR);
+
+        cloud.set_origin([500.0, 0.0, 0.0]);
+        let shifted = cloud.upsample_grid(1, 0.25).unwrap();
+        assert_eq!(shifted.origin(), cloud.origin());
+        assert_eq!(shifted.slice(10, 27).xyz().unwrap(), added.xyz().unwrap());
+    }
+}
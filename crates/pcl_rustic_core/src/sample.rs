@@ -0,0 +1,230 @@
+use std::collections::BTreeMap;
+
+use polars::prelude::*;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::TablePointCloud;
+
+impl TablePointCloud {
+    /// `n` points drawn uniformly inside the axis-aligned box `[min, max]`.
+    /// The same seed always yields the same cloud.
+    pub fn sample_box(
+        min: [f64; 3],
+        max: [f64; 3],
+        n: usize,
+        seed: u64,
+    ) -> Result<Self, PolarsError> {
+        if (0..3)
+            .any(|axis| !(min[axis].is_finite() && max[axis].is_finite() && min[axis] <= max[axis]))
+        {
+            polars_bail!(InvalidOperation: "box corners must be finite with min <= max, got {:?} and {:?}", min, max);
+        }
+        let mut rng = StdRng::seed_from_u64(seed);
+        let points: Vec<[f64; 3]> = (0..n)
+            .map(|_| {
+                [0, 1, 2].map(|axis| min[axis] + rng.random::<f64>() * (max[axis] - min[axis]))
+            })
+            .collect();
+        Self::from_point_array(&points)
+    }
+
+    /// `n` points drawn uniformly inside the ball of `radius` around
+    /// `center`. The same seed always yields the same cloud.
+    pub fn sample_sphere(
+        center: [f64; 3],
+        radius: f64,
+        n: usize,
+        seed: u64,
+    ) -> Result<Self, PolarsError> {
+        if !(radius.is_finite() && radius >= 0.0) {
+            polars_bail!(InvalidOperation: "sphere radius must be non-negative and finite, got {}", radius);
+        }
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut points = Vec::with_capacity(n);
+        // Rejection sampling from the enclosing cube accepts about 52% of
+        // candidates, which is cheaper than sampling radius and direction.
+        while points.len() < n {
+            let v: [f64; 3] = [0; 3].map(|_| rng.random::<f64>() * 2.0 - 1.0);
+            if v.iter().map(|c| c * c).sum::<f64>() <= 1.0 {
+                points.push([0, 1, 2].map(|axis| center[axis] + radius * v[axis]));
+            }
+        }
+        Self::from_point_array(&points)
+    }
+
+    /// Exactly `n` points, e.g. for fixed-size training batches.
+    ///
+    /// When the cloud has at least `n` points a uniform subset is drawn
+    /// without replacement and kept in storage order. Otherwise every point
+    /// is kept and the shortfall is made up by duplicating points drawn
+    /// with replacement, so the output is the original followed by the
+    /// duplicates. The same seed always yields the same result. Errors when
+    /// asking for points from an empty cloud.
+    pub fn resample_to(&self, n: usize, seed: u64) -> Result<Self, PolarsError> {
+        let len = self.len();
+        let mut rng = StdRng::seed_from_u64(seed);
+        let indices: Vec<usize> = if len >= n {
+            let mut indices = rand::seq::index::sample(&mut rng, len, n).into_vec();
+            indices.sort_unstable();
+            indices
+        } else {
+            if len == 0 {
+                polars_bail!(ComputeError: "cannot resample an empty cloud to {} points", n);
+            }
+            (0..len)
+                .chain((len..n).map(|_| rng.random_range(0..len)))
+                .collect()
+        };
+        self.take(&indices)
+    }
+
+    /// Up to `per_class` points from each label of the integer column
+    /// `label_column`, for class-balanced training sets.
+    ///
+    /// Each class is sampled uniformly without replacement; classes with
+    /// fewer points are taken whole. The result holds the classes one after
+    /// another in ascending label order, each in storage order. Points with
+    /// a null label are dropped. The same seed always yields the same
+    /// result.
+    pub fn balanced_sample(
+        &self,
+        per_class: usize,
+        label_column: &str,
+        seed: u64,
+    ) -> Result<Self, PolarsError> {
+        let column = self.data().column(label_column)?;
+        if !column.dtype().is_integer() {
+            polars_bail!(SchemaMismatch: "label column '{}' must be integer, got {}", label_column, column.dtype());
+        }
+        let mut classes: BTreeMap<i64, Vec<usize>> = BTreeMap::new();
+        for (i, label) in column.cast(&DataType::Int64)?.i64()?.iter().enumerate() {
+            if let Some(label) = label {
+                classes.entry(label).or_default().push(i);
+            }
+        }
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut indices = Vec::new();
+        for members in classes.into_values() {
+            if members.len() <= per_class {
+                indices.extend(members);
+                continue;
+            }
+            let mut picked =
+                rand::seq::index::sample(&mut rng, members.len(), per_class).into_vec();
+            picked.sort_unstable();
+            indices.extend(picked.into_iter().map(|j| members[j]));
+        }
+        self.take(&indices)
+    }
+
+    /// A cloud with the given coordinates and no attributes.
+    pub(crate) fn from_point_array(points: &[[f64; 3]]) -> Result<Self, PolarsError> {
+        Self::from_xyz(
+            points.iter().map(|p| p[0]).collect(),
+            points.iter().map(|p| p[1]).collect(),
+            points.iter().map(|p| p[2]).collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generated_points_stay_inside() {
+        let (min, max) = ([-1.0, 2.0, 0.0], [3.0, 2.5, 10.0]);
+        let cloud = TablePointCloud::sample_box(min, max, 1000, 1).unwrap();
+        assert_eq!(cloud.len(), 1000);
+        for p in cloud.xyz().unwrap() {
+            assert!(
+                (0..3).all(|axis| min[axis] <= p[axis] && p[axis] <= max[axis]),
+                "{p:?}"
+            );
+        }
+        let again = TablePointCloud::sample_box(min, max, 1000, 1).unwrap();
+        assert!(again.approx_eq(&cloud, 0.0));
+
+        let center = [5.0, -5.0, 1.0];
+        let ball = TablePointCloud::sample_sphere(center, 2.0, 1000, 2).unwrap();
+        assert_eq!(ball.len(), 1000);
+        for p in ball.xyz().unwrap() {
+            let d2: f64 = (0..3).map(|axis| (p[axis] - center[axis]).powi(2)).sum();
+            assert!(d2 <= 4.0 + 1e-12, "{p:?}");
+        }
+
+        assert!(TablePointCloud::sample_box(max, min, 1, 0).is_err());
+        assert!(TablePointCloud::sample_sphere(center, -1.0, 1, 0).is_err());
+    }
+
+    /// Points `0..n` along x, so each point's x is its original index.
+    fn indexed(n: usize) -> TablePointCloud {
+        TablePointCloud::from_xyz(
+            (0..n).map(|i| i as f64).collect(),
+            vec![0.0; n],
+            vec![0.0; n],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn resample_down_draws_distinct_points() {
+        let cloud = indexed(100);
+        let down = cloud.resample_to(30, 5).unwrap();
+        let x = down.x().unwrap();
+        assert_eq!(x.len(), 30);
+        // Without replacement and in storage order: strictly increasing.
+        assert!(x.windows(2).all(|w| w[0] < w[1]), "{x:?}");
+        assert!(down.approx_eq(&cloud.resample_to(30, 5).unwrap(), 0.0));
+        assert!(!down.approx_eq(&cloud.resample_to(30, 6).unwrap(), 0.0));
+        assert!(cloud.resample_to(100, 1).unwrap().approx_eq(&cloud, 0.0));
+    }
+
+    #[test]
+    fn resample_up_keeps_originals_then_duplicates() {
+        let cloud = indexed(10);
+        let up = cloud.resample_to(25, 5).unwrap();
+        let x = up.x().unwrap();
+        assert_eq!(x.len(), 25);
+        assert_eq!(x[..10], cloud.x().unwrap()[..]);
+        assert!(x[10..].iter().all(|v| (0.0..10.0).contains(v)));
+        assert!(up.approx_eq(&cloud.resample_to(25, 5).unwrap(), 0.0));
+        assert!(indexed(0).resample_to(3, 0).is_err());
+        assert!(indexed(0).resample_to(0, 0).unwrap().is_empty());
+    }
+
+    #[test]
+    fn balanced_sample_evens_out_classes() {
+        // 90 points of class 1, 12 of class 2 and one unlabeled point.
+        let mut cloud = indexed(103);
+        let labels: Vec<Option<i32>> = (0..103)
+            .map(|i| match i {
+                102 => None,
+                i if i % 8 == 0 && i < 96 => Some(2),
+                _ => Some(1),
+            })
+            .collect();
+        cloud
+            .set_attribute(Series::new("label".into(), labels))
+            .unwrap();
+
+        let count = |cloud: &TablePointCloud, label: f64| {
+            cloud
+                .attribute("label")
+                .unwrap()
+                .into_iter()
+                .filter(|l| *l == label)
+                .count()
+        };
+        let balanced = cloud.balanced_sample(20, "label", 3).unwrap();
+        assert_eq!((count(&balanced, 1.0), count(&balanced, 2.0)), (20, 12));
+        assert_eq!(balanced.len(), 32);
+        let even = cloud.balanced_sample(10, "label", 3).unwrap();
+        assert_eq!((count(&even, 1.0), count(&even, 2.0)), (10, 10));
+        assert!(even.approx_eq(&cloud.balanced_sample(10, "label", 3).unwrap(), 0.0));
+
+        assert!(cloud.balanced_sample(10, "missing", 0).is_err());
+        assert!(cloud.balanced_sample(10, "x", 0).is_err());
+    }
+}
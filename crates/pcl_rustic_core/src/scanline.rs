@@ -0,0 +1,93 @@
+use polars::prelude::*;
+
+use crate::cloud::TIME_COLUMNS;
+use crate::TablePointCloud;
+
+impl TablePointCloud {
+    /// Splits the cloud into scan lines by azimuth continuity.
+    ///
+    /// Points are taken in capture order: sorted by the first of
+    /// `timestamp`, `gps_time`, `time` or `time_offset` present, otherwise
+    /// in stored order. The azimuth is `atan2(y, x)` about the local origin
+    /// (the sensor). A new line starts whenever consecutive azimuths differ by
+    /// more than `azimuth_gap_deg` degrees, which includes the wrap across
+    /// the +-180 degree seam. A sweep starting elsewhere than the seam is
+    /// therefore split there. Lines are returned in capture order; an empty
+    /// cloud yields none.
+    pub fn split_scanlines(&self, azimuth_gap_deg: f64) -> Result<Vec<Self>, PolarsError> {
+        if !(azimuth_gap_deg.is_finite() && azimuth_gap_deg > 0.0) {
+            polars_bail!(InvalidOperation: "azimuth gap must be positive and finite, got {}", azimuth_gap_deg);
+        }
+        let ordered = match TIME_COLUMNS.into_iter().find(|name| self.has_column(name)) {
+            Some(time) => self.sort_by(time, false)?,
+            None => self.clone(),
+        };
+        let azimuths: Vec<f64> = ordered
+            .x()?
+            .into_iter()
+            .zip(ordered.y()?)
+            .map(|(x, y)| y.atan2(x).to_degrees())
+            .collect();
+
+        let mut lines: Vec<Vec<usize>> = Vec::new();
+        for (i, &azimuth) in azimuths.iter().enumerate() {
+            match lines.last_mut() {
+                Some(line) if (azimuth - azimuths[i - 1]).abs() <= azimuth_gap_deg => line.push(i),
+                _ => lines.push(vec![i]),
+            }
+        }
+        lines
+            .into_iter()
+            .map(|indices| ordered.take(&indices))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Points on the unit circle at the given azimuths (degrees), one
+    /// `time` step apart.
+    fn sweep(azimuths: &[f64]) -> TablePointCloud {
+        let mut cloud = TablePointCloud::from_xyz(
+            azimuths.iter().map(|a| a.to_radians().cos()).collect(),
+            azimuths.iter().map(|a| a.to_radians().sin()).collect(),
+            vec![0.0; azimuths.len()],
+        )
+        .unwrap();
+        let time: Vec<f64> = (0..azimuths.len()).map(|i| i as f64).collect();
+        cloud
+            .set_attribute(Series::new("time".into(), time))
+            .unwrap();
+        cloud
+    }
+
+    #[test]
+    fn revolutions_split_at_the_wrap() {
+        // Three revolutions from -175 to 175 degrees in 10 degree steps.
+        let revolution: Vec<f64> = (0..36).map(|i| -175.0 + 10.0 * i as f64).collect();
+        let cloud = sweep(&revolution.repeat(3));
+        let lines = cloud.split_scanlines(15.0).unwrap();
+        assert_eq!(lines.len(), 3);
+        assert!(lines.iter().all(|line| line.len() == 36));
+
+        // Stored order does not matter once a time column is present.
+        let reversed = cloud
+            .take(&(0..cloud.len()).rev().collect::<Vec<_>>())
+            .unwrap();
+        assert_eq!(reversed.split_scanlines(15.0).unwrap().len(), 3);
+
+        // A sweep starting at 90 degrees is cut once at the seam.
+        let offset: Vec<f64> = (0..36).map(|i| 90.0 + 10.0 * i as f64).collect();
+        let wrapped: Vec<f64> = offset
+            .iter()
+            .map(|a| if *a > 180.0 { a - 360.0 } else { *a })
+            .collect();
+        let lines = sweep(&wrapped).split_scanlines(15.0).unwrap();
+        assert_eq!(lines.iter().map(|l| l.len()).collect::<Vec<_>>(), [10, 26]);
+
+        assert!(cloud.split_scanlines(0.0).is_err());
+        assert!(sweep(&[]).split_scanlines(15.0).unwrap().is_empty());
+    }
+}
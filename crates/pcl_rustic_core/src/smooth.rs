@@ -0,0 +1,231 @@
+use nalgebra::{DMatrix, DVector, Matrix3, Vector3};
+use polars::prelude::*;
+
+use crate::kdtree::KdTree;
+use crate::TablePointCloud;
+
+impl TablePointCloud {
+    /// Laplacian smoothing: on each of `iterations` passes, every point
+    /// moves by `lambda` of the way toward the centroid of its `k` nearest
+    /// neighbors (itself excluded).
+    ///
+    /// Neighbors are searched again on every pass. `lambda` is usually in
+    /// `(0, 1]`; large values or many passes shrink the cloud. Attributes
+    /// pass through unchanged.
+    pub fn smooth_laplacian(
+        &self,
+        k: usize,
+        lambda: f64,
+        iterations: usize,
+    ) -> Result<Self, PolarsError> {
+        if k == 0 {
+            polars_bail!(InvalidOperation: "Laplacian smoothing needs k > 0");
+        }
+        if !lambda.is_finite() {
+            polars_bail!(InvalidOperation: "smoothing factor must be finite, got {}", lambda);
+        }
+        let mut points = self.xyz()?;
+        for _ in 0..iterations {
+            let tree = KdTree::new(&points);
+            points = points
+                .iter()
+                .enumerate()
+                .map(|(i, &p)| {
+                    let neighbors: Vec<usize> = tree
+                        .knn(p, k + 1)
+                        .into_iter()
+                        .map(|(j, _)| j)
+                        .filter(|&j| j != i)
+                        .take(k)
+                        .collect();
+                    if neighbors.is_empty() {
+                        return p;
+                    }
+                    let n = neighbors.len() as f64;
+                    [0, 1, 2].map(|axis| {
+                        let mean = neighbors.iter().map(|&j| points[j][axis]).sum::<f64>() / n;
+                        p[axis] + lambda * (mean - p[axis])
+                    })
+                })
+                .collect();
+        }
+        self.with_coordinates(
+            points.iter().map(|p| p[0]).collect(),
+            points.iter().map(|p| p[1]).collect(),
+            points.iter().map(|p| p[2]).collect(),
+        )
+    }
+
+    /// Moving least squares smoothing: every point is projected onto a
+    /// polynomial surface of degree `polynomial_order` fitted to the
+    /// neighbors within `radius`.
+    ///
+    /// Neighbors are weighted by `exp(-d² / radius²)`. The surface is a
+    /// height field over the weighted-PCA tangent plane at the
+    /// neighborhood's weighted centroid. Order 0 moves each point to that
+    /// weighted centroid, i.e. plain neighborhood averaging; order 1
+    /// projects onto the tangent plane; order 2 is the usual choice for
+    /// curved surfaces. Where a neighborhood has too few points for the
+    /// requested order, the highest order it supports is used, and a point
+    /// with fewer than three neighbors is left in place. Attributes pass
+    /// through unchanged.
+    pub fn mls_smooth(&self, radius: f64, polynomial_order: usize) -> Result<Self, PolarsError> {
+        if !(radius.is_finite() && radius > 0.0) {
+            polars_bail!(InvalidOperation: "MLS radius must be positive and finite, got {}", radius);
+        }
+        let points = self.xyz()?;
+        let tree = KdTree::new(&points);
+        let smoothed: Vec<[f64; 3]> = points
+            .iter()
+            .map(|&p| {
+                let neighbors: Vec<(Vector3<f64>, f64)> = tree
+                    .radius_search(p, radius)
+                    .into_iter()
+                    .map(|(j, d)| {
+                        (
+                            Vector3::from(points[j]),
+                            (-(d * d) / (radius * radius)).exp(),
+                        )
+                    })
+                    .collect();
+                mls_project(Vector3::from(p), &neighbors, radius, polynomial_order)
+                    .map_or(p, |q| [q.x, q.y, q.z])
+            })
+            .collect();
+        self.with_coordinates(
+            smoothed.iter().map(|p| p[0]).collect(),
+            smoothed.iter().map(|p| p[1]).collect(),
+            smoothed.iter().map(|p| p[2]).collect(),
+        )
+    }
+}
+
+/// Projects `p` onto the MLS surface of its weighted `neighbors`, or `None`
+/// when the neighborhood is too small to define one.
+fn mls_project(
+    p: Vector3<f64>,
+    neighbors: &[(Vector3<f64>, f64)],
+    radius: f64,
+    order: usize,
+) -> Option<Vector3<f64>> {
+    if neighbors.len() < 3 {
+        return None;
+    }
+    let total: f64 = neighbors.iter().map(|(_, w)| w).sum();
+    let centroid = neighbors.iter().map(|(q, w)| q * *w).sum::<Vector3<f64>>() / total;
+    if order == 0 {
+        return Some(centroid);
+    }
+    let covariance = neighbors.iter().fold(Matrix3::zeros(), |acc, (q, w)| {
+        let d = q - centroid;
+        acc + d * d.transpose() * *w
+    });
+    let eigen = covariance.symmetric_eigen();
+    let mut axes = [0, 1, 2];
+    axes.sort_by(|&a, &b| eigen.eigenvalues[a].total_cmp(&eigen.eigenvalues[b]));
+    let normal: Vector3<f64> = eigen.eigenvectors.column(axes[0]).normalize();
+    let u_axis: Vector3<f64> = eigen.eigenvectors.column(axes[2]).normalize();
+    let v_axis = normal.cross(&u_axis);
+    // Tangent coordinates are scaled by the radius to keep the fit well
+    // conditioned.
+    let local = |q: &Vector3<f64>| {
+        let d = q - centroid;
+        (
+            d.dot(&u_axis) / radius,
+            d.dot(&v_axis) / radius,
+            d.dot(&normal),
+        )
+    };
+    let (pu, pv, _) = local(&p);
+    let on_plane = centroid + (u_axis * pu + v_axis * pv) * radius;
+
+    // Highest degree whose (degree + 1)(degree + 2) / 2 coefficients the
+    // neighborhood can determine.
+    let degree = (1..=order)
+        .rev()
+        .find(|d| (d + 1) * (d + 2) / 2 <= neighbors.len())
+        .unwrap_or(1);
+    let exponents: Vec<(i32, i32)> = (0..=degree as i32)
+        .flat_map(|total| (0..=total).map(move |i| (total - i, i)))
+        .collect();
+    let monomials = |u: f64, v: f64| exponents.iter().map(move |&(a, b)| u.powi(a) * v.powi(b));
+    let mut gram = DMatrix::<f64>::zeros(exponents.len(), exponents.len());
+    let mut moment = DVector::<f64>::zeros(exponents.len());
+    for (q, w) in neighbors {
+        let (u, v, h) = local(q);
+        let row = DVector::from_iterator(exponents.len(), monomials(u, v));
+        gram += &row * row.transpose() * *w;
+        moment += row * (h * *w);
+    }
+    let Some(coefficients) = gram.lu().solve(&moment) else {
+        return Some(on_plane);
+    };
+    let height: f64 = monomials(pu, pv)
+        .zip(coefficients.iter())
+        .map(|(m, c)| m * c)
+        .sum();
+    Some(on_plane + normal * height)
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    use super::*;
+
+    /// A `side` x `side` unit grid in the z = 0 plane with uniform height
+    /// noise of `noise`, carrying an `intensity` attribute.
+    fn noisy_plane(side: usize, noise: f64) -> TablePointCloud {
+        let mut rng = StdRng::seed_from_u64(17);
+        let n = side * side;
+        let mut cloud = TablePointCloud::from_xyz(
+            (0..n).map(|i| (i % side) as f64).collect(),
+            (0..n).map(|i| (i / side) as f64).collect(),
+            (0..n).map(|_| rng.random_range(-noise..noise)).collect(),
+        )
+        .unwrap();
+        cloud
+            .set_attribute(Series::new("intensity".into(), vec![5.0; n]))
+            .unwrap();
+        cloud
+    }
+
+    /// Root-mean-square distance to the z = 0 plane.
+    fn rms_height(cloud: &TablePointCloud) -> f64 {
+        let z = cloud.z().unwrap();
+        (z.iter().map(|z| z * z).sum::<f64>() / z.len() as f64).sqrt()
+    }
+
+    #[test]
+    fn laplacian_smoothing_reduces_plane_noise() {
+        let cloud = noisy_plane(20, 0.2);
+        let smoothed = cloud.smooth_laplacian(8, 0.5, 5).unwrap();
+        let (before, after) = (rms_height(&cloud), rms_height(&smoothed));
+        assert!(after < before / 2.0, "RMS {before} -> {after}");
+        assert_eq!(smoothed.attribute("intensity").unwrap(), vec![5.0; 400]);
+        assert!(smoothed
+            .smooth_laplacian(8, 0.5, 0)
+            .unwrap()
+            .approx_eq(&smoothed, 0.0));
+        assert!(cloud.smooth_laplacian(0, 0.5, 1).is_err());
+    }
+
+    #[test]
+    fn mls_smoothing_reduces_plane_noise() {
+        let cloud = noisy_plane(20, 0.2);
+        let before = rms_height(&cloud);
+        // A radius wide enough that a quadratic cannot follow the noise.
+        for order in [0, 1, 2] {
+            let smoothed = cloud.mls_smooth(3.0, order).unwrap();
+            let after = rms_height(&smoothed);
+            assert!(
+                after < before / 2.0,
+                "order {order}: RMS {before} -> {after}"
+            );
+            assert_eq!(smoothed.len(), cloud.len());
+            assert_eq!(smoothed.attribute("intensity").unwrap(), vec![5.0; 400]);
+        }
+        assert!(cloud.mls_smooth(0.0, 2).is_err());
+    }
+}
@@ -0,0 +1,190 @@
+use std::collections::HashMap;
+
+use polars::prelude::*;
+
+use crate::TablePointCloud;
+
+impl TablePointCloud {
+    /// Approximate heap size of the point data in bytes, as estimated by
+    /// polars for the underlying frame.
+    pub fn estimated_size_bytes(&self) -> usize {
+        self.data().estimated_size()
+    }
+
+    /// A short human-readable description for logs: point count,
+    /// approximate size, then one `name: dtype` line per column.
+    pub fn summary(&self) -> String {
+        let mut out = format!(
+            "{} points, {} columns, ~{} bytes",
+            self.len(),
+            self.data().width(),
+            self.estimated_size_bytes()
+        );
+        for column in self.data().columns() {
+            out.push_str(&format!("\n  {}: {}", column.name(), column.dtype()));
+        }
+        out
+    }
+
+    /// Histogram of a numeric column as `(bin center, count)` pairs.
+    ///
+    /// Without an explicit `range` the column's min/max is used (a constant
+    /// column gets a unit-wide range around its value). Values outside the
+    /// range, nulls and NaNs are ignored; the upper edge belongs to the last
+    /// bin.
+    pub fn attribute_histogram(
+        &self,
+        column: &str,
+        bins: usize,
+        range: Option<(f64, f64)>,
+    ) -> Result<Vec<(f64, u64)>, PolarsError> {
+        if bins == 0 {
+            polars_bail!(InvalidOperation: "histogram needs at least one bin");
+        }
+        let values: Vec<f64> = self
+            .column_f64(column)?
+            .into_iter()
+            .filter(|v| !v.is_nan())
+            .collect();
+        let (lo, hi) = match range {
+            Some((lo, hi)) => {
+                if lo.is_nan() || hi.is_nan() || lo >= hi {
+                    polars_bail!(InvalidOperation: "histogram range must satisfy min < max, got ({}, {})", lo, hi);
+                }
+                (lo, hi)
+            }
+            None => {
+                if values.is_empty() {
+                    polars_bail!(ComputeError: "column '{}' has no values to derive a histogram range from", column);
+                }
+                let lo = values.iter().cloned().fold(f64::INFINITY, f64::min);
+                let hi = values.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+                if lo == hi {
+                    (lo - 0.5, hi + 0.5)
+                } else {
+                    (lo, hi)
+                }
+            }
+        };
+
+        let width = (hi - lo) / bins as f64;
+        let mut counts = vec![0u64; bins];
+        for v in values.into_iter().filter(|v| (lo..=hi).contains(v)) {
+            let bin = (((v - lo) / width) as usize).min(bins - 1);
+            counts[bin] += 1;
+        }
+        Ok(counts
+            .into_iter()
+            .enumerate()
+            .map(|(i, count)| (lo + (i as f64 + 0.5) * width, count))
+            .collect())
+    }
+
+    /// Histogram of points per occupied voxel, showing whether a cloud is
+    /// uniform (one dominant bin) or clumpy (counts spread over many bins).
+    ///
+    /// Voxels of side `voxel_size` are anchored at the world origin and
+    /// only occupied ones are counted. The `bins` equal-width bins span the
+    /// smallest to largest occupancy, the upper edge belonging to the last
+    /// bin; equal occupancies all land in the first bin. Errors on an empty
+    /// cloud.
+    pub fn density_histogram(&self, voxel_size: f64, bins: usize) -> Result<Vec<u64>, PolarsError> {
+        if !(voxel_size.is_finite() && voxel_size > 0.0) {
+            polars_bail!(InvalidOperation: "voxel size must be positive and finite, got {}", voxel_size);
+        }
+        if bins == 0 {
+            polars_bail!(InvalidOperation: "histogram needs at least one bin");
+        }
+        self.ensure_non_empty("density histogram")?;
+        let mut occupancy: HashMap<[i64; 3], u64> = HashMap::new();
+        for p in self.xyz()? {
+            *occupancy
+                .entry(p.map(|v| (v / voxel_size).floor() as i64))
+                .or_default() += 1;
+        }
+        let lo = *occupancy.values().min().expect("non-empty cloud");
+        let hi = *occupancy.values().max().expect("non-empty cloud");
+        let mut counts = vec![0u64; bins];
+        for &n in occupancy.values() {
+            let bin = if hi == lo {
+                0
+            } else {
+                (((n - lo) as f64 / (hi - lo) as f64 * bins as f64) as usize).min(bins - 1)
+            };
+            counts[bin] += 1;
+        }
+        Ok(counts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn with_column(values: Vec<Option<f64>>) -> TablePointCloud {
+        let n = values.len();
+        let mut cloud =
+            TablePointCloud::from_xyz(vec![0.0; n], vec![0.0; n], vec![0.0; n]).unwrap();
+        cloud
+            .set_attribute(Series::new("intensity".into(), values))
+            .unwrap();
+        cloud
+    }
+
+    #[test]
+    fn histogram_of_uniform_column() {
+        let mut values: Vec<Option<f64>> = (0..100).map(|v| Some(v as f64)).collect();
+        values.push(None);
+        values.push(Some(f64::NAN));
+        let cloud = with_column(values);
+
+        let histogram = cloud
+            .attribute_histogram("intensity", 10, Some((0.0, 100.0)))
+            .unwrap();
+        let expected: Vec<(f64, u64)> = (0..10).map(|i| (i as f64 * 10.0 + 5.0, 10)).collect();
+        assert_eq!(histogram, expected);
+
+        // The data range is [0, 99]: the top bin also takes the maximum.
+        let counts: Vec<u64> = cloud
+            .attribute_histogram("intensity", 3, None)
+            .unwrap()
+            .into_iter()
+            .map(|(_, count)| count)
+            .collect();
+        assert_eq!(counts, [33, 33, 34]);
+        assert!(cloud.attribute_histogram("missing", 3, None).is_err());
+    }
+
+    #[test]
+    fn estimated_size_grows_with_point_count() {
+        let sizes: Vec<usize> = [10, 1_000, 100_000]
+            .into_iter()
+            .map(|n| with_column(vec![Some(1.0); n]).estimated_size_bytes())
+            .collect();
+        assert!(sizes.windows(2).all(|pair| pair[0] < pair[1]), "{sizes:?}");
+        // Four f64 columns take at least 32 bytes per point.
+        assert!(sizes[2] >= 32 * 100_000);
+
+        let summary = with_column(vec![Some(1.0); 3]).summary();
+        assert!(summary.starts_with("3 points, 4 columns, ~"));
+        assert!(summary.contains("\n  intensity: f64"));
+    }
+
+    #[test]
+    fn density_histogram_of_a_clumpy_cloud() {
+        // Ten voxels with a single point, one with 11 and two with 20.
+        let mut points: Vec<[f64; 3]> = (0..10).map(|i| [i as f64 + 0.5, 0.5, 0.5]).collect();
+        for (cell, count) in [(0.0, 11), (1.0, 20), (2.0, 20)] {
+            points.extend((0..count).map(|k| [cell + 0.5, 5.5, 0.01 * k as f64]));
+        }
+        let cloud = TablePointCloud::from_point_array(&points).unwrap();
+        // Occupancies span 1..=20: 11 lands in the third of four bins.
+        assert_eq!(cloud.density_histogram(1.0, 4).unwrap(), [10, 0, 1, 2]);
+
+        let uniform =
+            TablePointCloud::from_xyz(vec![0.5, 1.5, 2.5], vec![0.5; 3], vec![0.5; 3]).unwrap();
+        assert_eq!(uniform.density_histogram(1.0, 3).unwrap(), [3, 0, 0]);
+        assert!(cloud.density_histogram(0.0, 4).is_err());
+        assert!(with_column(vec![]).density_histogram(1.0, 4).is_err());
+    }
+}
@@ -0,0 +1,138 @@
+use polars::prelude::*;
+
+use crate::cloud::TIME_COLUMNS;
+use crate::TablePointCloud;
+
+impl TablePointCloud {
+    /// Earliest and latest value of the first of `timestamp`, `gps_time`,
+    /// `time` or `time_offset` present. Nulls and NaNs are ignored; errors
+    /// if there is no such column or it holds no valid value.
+    pub fn time_range(&self) -> Result<(f64, f64), PolarsError> {
+        let name = self.time_column()?;
+        let (min, max) = self
+            .column_f64(name)?
+            .into_iter()
+            .filter(|t| !t.is_nan())
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), t| {
+                (min.min(t), max.max(t))
+            });
+        if min > max {
+            polars_bail!(ComputeError: "time range is undefined: '{}' has no valid values", name);
+        }
+        Ok((min, max))
+    }
+
+    /// Keeps the points with `start <= time < end`, so consecutive windows
+    /// of a stream never share a point. Points with a null or NaN time are
+    /// dropped.
+    pub fn filter_time(&self, start: f64, end: f64) -> Result<Self, PolarsError> {
+        if start.is_nan() || end.is_nan() {
+            polars_bail!(InvalidOperation: "time window bounds must not be NaN");
+        }
+        let mask: BooleanChunked = self
+            .column_f64(self.time_column()?)?
+            .into_iter()
+            .map(|t| t >= start && t < end)
+            .collect();
+        self.filter(&mask)
+    }
+
+    /// Shifts the time column so `t0` becomes zero, rewriting it as `f64`;
+    /// nulls stay null. Pass the first value of [`TablePointCloud::time_range`]
+    /// to make times relative to the first point.
+    pub fn rebase_time(&self, t0: f64) -> Result<Self, PolarsError> {
+        if !t0.is_finite() {
+            polars_bail!(InvalidOperation: "time origin must be finite, got {}", t0);
+        }
+        let name = self.time_column()?;
+        let rebased = self
+            .data()
+            .clone()
+            .lazy()
+            .select([(col(name).cast(DataType::Float64) - lit(t0)).alias(name)])
+            .collect()?;
+        let mut out = self.clone();
+        out.set_attribute(rebased.column(name)?.as_materialized_series().clone())?;
+        Ok(out)
+    }
+
+    fn time_column(&self) -> Result<&'static str, PolarsError> {
+        TIME_COLUMNS
+            .into_iter()
+            .find(|name| self.has_column(name))
+            .ok_or_else(|| polars_err!(ColumnNotFound: "cloud has no time column (expected one of {})", TIME_COLUMNS.join(", ")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn timed(name: &str, times: Vec<Option<f64>>) -> TablePointCloud {
+        let n = times.len();
+        let mut cloud = TablePointCloud::from_xyz(
+            (0..n).map(|i| i as f64).collect(),
+            vec![0.0; n],
+            vec![0.0; n],
+        )
+        .unwrap();
+        cloud
+            .set_attribute(Series::new(name.into(), times))
+            .unwrap();
+        cloud
+    }
+
+    #[test]
+    fn windows_and_rebases_known_timestamps() {
+        let cloud = timed(
+            "time",
+            vec![
+                Some(100.5),
+                Some(100.0),
+                None,
+                Some(101.0),
+                Some(f64::NAN),
+                Some(100.25),
+            ],
+        );
+        assert_eq!(cloud.time_range().unwrap(), (100.0, 101.0));
+
+        // Half-open window: 101.0 is left for the next one.
+        let window = cloud.filter_time(100.0, 101.0).unwrap();
+        assert_eq!(window.x().unwrap(), [0.0, 1.0, 5.0]);
+        let next = cloud.filter_time(101.0, 102.0).unwrap();
+        assert_eq!(next.x().unwrap(), [3.0]);
+
+        let rebased = cloud.rebase_time(100.0).unwrap();
+        let times = rebased
+            .data()
+            .column("time")
+            .unwrap()
+            .f64()
+            .unwrap()
+            .clone();
+        assert_eq!(times.get(0), Some(0.5));
+        assert_eq!(times.get(2), None);
+        assert_eq!(times.get(5), Some(0.25));
+        assert_eq!(rebased.time_range().unwrap(), (0.0, 1.0));
+    }
+
+    #[test]
+    fn falls_back_to_time_offset_and_errors_without_time() {
+        let cloud = timed("time_offset", vec![Some(3.0), Some(1.0)]);
+        assert_eq!(cloud.time_range().unwrap(), (1.0, 3.0));
+        assert_eq!(
+            timed("gps_time", vec![Some(7.0), Some(5.0)])
+                .time_range()
+                .unwrap(),
+            (5.0, 7.0)
+        );
+        assert_eq!(cloud.filter_time(0.0, 2.0).unwrap().len(), 1);
+
+        let untimed = timed("intensity", vec![Some(1.0)]);
+        assert!(untimed.time_range().is_err());
+        assert!(untimed.filter_time(0.0, 1.0).is_err());
+        assert!(untimed.rebase_time(0.0).is_err());
+        assert!(timed("time", vec![None]).time_range().is_err());
+    }
+}
@@ -0,0 +1,183 @@
+use nalgebra::{Matrix3, Matrix4, RealField, Rotation3};
+
+/// A rigid or affine 3D transform stored as a homogeneous 4x4 matrix.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform<T: RealField + Copy> {
+    matrix: Matrix4<T>,
+}
+
+impl<T: RealField + Copy> Transform<T> {
+    pub fn identity() -> Self {
+        Self {
+            matrix: Matrix4::identity(),
+        }
+    }
+
+    pub fn from_matrix(matrix: Matrix4<T>) -> Self {
+        Self { matrix }
+    }
+
+    /// Rotation from roll, pitch and yaw (radians) in the ZYX convention:
+    /// `R = Rz(yaw) * Ry(pitch) * Rx(roll)`, with no translation.
+    pub fn from_euler(roll: T, pitch: T, yaw: T) -> Self {
+        Self {
+            matrix: Rotation3::from_euler_angles(roll, pitch, yaw).to_homogeneous(),
+        }
+    }
+
+    /// Assembles `[R | t]` from a 3x3 rotation (or any linear block) and a
+    /// translation.
+    pub fn from_rotation_translation(rotation: &Matrix3<T>, translation: [T; 3]) -> Self {
+        let mut matrix = Matrix4::identity();
+        matrix.fixed_view_mut::<3, 3>(0, 0).copy_from(rotation);
+        for (row, value) in translation.into_iter().enumerate() {
+            matrix[(row, 3)] = value;
+        }
+        Self { matrix }
+    }
+
+    /// Splits the transform into its 3x3 linear block and translation,
+    /// inverting [`Transform::from_rotation_translation`]; the bottom row is
+    /// ignored.
+    pub fn to_rotation_translation(&self) -> (Matrix3<T>, [T; 3]) {
+        (
+            self.matrix.fixed_view::<3, 3>(0, 0).into_owned(),
+            self.translation(),
+        )
+    }
+
+    pub fn matrix(&self) -> &Matrix4<T> {
+        &self.matrix
+    }
+
+    /// Roll, pitch and yaw (radians) of the rotation block, inverting
+    /// [`Transform::from_euler`].
+    ///
+    /// Pitch lies in `[-pi/2, pi/2]`. At gimbal lock (pitch within about
+    /// 1e-6 rad of +-90 degrees) roll and yaw are not separable; roll is
+    /// reported as 0 and the whole rotation about the vertical goes into
+    /// yaw. The block is assumed to be a pure rotation.
+    pub fn to_euler(&self) -> (T, T, T) {
+        let m = &self.matrix;
+        let sin_pitch = -m[(2, 0)];
+        let lock = T::one() - nalgebra::convert(5e-13);
+        if sin_pitch.abs() >= lock {
+            let pitch = T::frac_pi_2() * sin_pitch.signum();
+            let yaw = (-m[(0, 1)]).atan2(m[(1, 1)]);
+            return (T::zero(), pitch, yaw);
+        }
+        let roll = m[(2, 1)].atan2(m[(2, 2)]);
+        let pitch = sin_pitch.asin();
+        let yaw = m[(1, 0)].atan2(m[(0, 0)]);
+        (roll, pitch, yaw)
+    }
+
+    pub fn translation(&self) -> [T; 3] {
+        [
+            self.matrix[(0, 3)],
+            self.matrix[(1, 3)],
+            self.matrix[(2, 3)],
+        ]
+    }
+}
+
+impl<T: RealField + Copy> Default for Transform<T> {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl<T: RealField + Copy> From<Matrix4<T>> for Transform<T> {
+    fn from(matrix: Matrix4<T>) -> Self {
+        Self::from_matrix(matrix)
+    }
+}
+
+/// Axis conventions of common 3D frames.
+///
+/// The camera frame is related to the world frames by assuming a level
+/// camera looking north, so its right axis points east.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CoordinateFrame {
+    /// x east, y north, z up.
+    Enu,
+    /// x north, y east, z down.
+    Ned,
+    /// x right, y down, z forward (OpenCV camera convention).
+    Camera,
+}
+
+impl CoordinateFrame {
+    /// Rotation taking coordinates in this frame to ENU; its columns are
+    /// this frame's axes expressed in ENU.
+    pub fn to_enu(self) -> Matrix3<f64> {
+        match self {
+            CoordinateFrame::Enu => Matrix3::identity(),
+            #[rustfmt::skip]
+            CoordinateFrame::Ned => Matrix3::new(
+                0.0, 1.0, 0.0,
+                1.0, 0.0, 0.0,
+                0.0, 0.0, -1.0,
+            ),
+            #[rustfmt::skip]
+            CoordinateFrame::Camera => Matrix3::new(
+                1.0, 0.0, 0.0,
+                0.0, 0.0, 1.0,
+                0.0, -1.0, 0.0,
+            ),
+        }
+    }
+
+    /// Homogeneous matrix re-expressing coordinates given in `from` in
+    /// the `to` frame.
+    pub fn conversion(from: CoordinateFrame, to: CoordinateFrame) -> Matrix4<f64> {
+        (to.to_enu().transpose() * from.to_enu()).to_homogeneous()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::FRAC_PI_2;
+
+    use super::*;
+
+    #[test]
+    fn euler_round_trip() {
+        for (roll, pitch, yaw) in [
+            (0.1f64, -0.2f64, 0.3f64),
+            (-2.5, 1.2, 3.0),
+            (1.0, -1.5, -0.7),
+            (0.0, 0.0, 0.0),
+        ] {
+            let (r, p, y) = Transform::from_euler(roll, pitch, yaw).to_euler();
+            assert!((r - roll).abs() < 1e-12, "roll {r} != {roll}");
+            assert!((p - pitch).abs() < 1e-12, "pitch {p} != {pitch}");
+            assert!((y - yaw).abs() < 1e-12, "yaw {y} != {yaw}");
+        }
+    }
+
+    #[test]
+    fn gimbal_lock_folds_roll_into_yaw() {
+        let transform = Transform::from_euler(0.0, FRAC_PI_2, 0.4);
+        let (roll, pitch, yaw) = transform.to_euler();
+        assert_eq!(roll, 0.0);
+        assert_eq!(pitch, FRAC_PI_2);
+        let rebuilt = Transform::from_euler(roll, pitch, yaw);
+        assert!((rebuilt.matrix() - transform.matrix()).amax() < 1e-12);
+    }
+
+    #[test]
+    fn rotation_translation_round_trip() {
+        let rotation = *Rotation3::from_euler_angles(0.4, -1.1, 2.0).matrix();
+        let translation = [3.5, -7.25, 0.125];
+        let transform = Transform::from_rotation_translation(&rotation, translation);
+        let matrix = transform.matrix();
+        assert_eq!(matrix.fixed_view::<3, 3>(0, 0), rotation);
+        assert_eq!(matrix.row(3), nalgebra::RowVector4::new(0.0, 0.0, 0.0, 1.0));
+        assert_eq!(transform.to_rotation_translation(), (rotation, translation));
+        assert_eq!(
+            Transform::from_rotation_translation(&Matrix3::identity(), [0.0; 3]).matrix(),
+            Transform::<f64>::identity().matrix()
+        );
+    }
+}
@@ -0,0 +1,146 @@
+use polars::prelude::*;
+
+use crate::cloud::COLOR_COLUMNS;
+use crate::TablePointCloud;
+
+/// A per-vertex field of a packed GPU vertex buffer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VertexField {
+    /// Stored (local) `x`, `y`, `z` as three `f32`.
+    Position,
+    /// `red`, `green`, `blue` as three `u8`, rounded and clamped to 0-255;
+    /// nulls become 0.
+    Color,
+    /// `intensity` as one `f32`; nulls become NaN.
+    Intensity,
+}
+
+impl VertexField {
+    /// Size of the field in bytes.
+    pub fn size(self) -> usize {
+        match self {
+            VertexField::Position => 12,
+            VertexField::Color => 3,
+            VertexField::Intensity => 4,
+        }
+    }
+}
+
+/// Interleaved vertex data produced by [`TablePointCloud::to_vertex_buffer`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct VertexBuffer {
+    /// Little-endian vertex records, `stride` bytes each, in point order.
+    pub data: Vec<u8>,
+    /// Size of one vertex record in bytes.
+    pub stride: usize,
+    /// Byte offset of each layout field within a record, in layout order.
+    pub offsets: Vec<usize>,
+}
+
+impl TablePointCloud {
+    /// Packs the fields of `layout` into one interleaved little-endian
+    /// buffer for upload to a graphics API.
+    ///
+    /// Records are tightly packed without padding, so the stride is the sum
+    /// of the field sizes. Positions are the stored coordinates, which stay
+    /// small enough for `f32` when the cloud has a local origin. Errors when
+    /// a field's columns are missing or the layout is empty.
+    pub fn to_vertex_buffer(&self, layout: &[VertexField]) -> Result<VertexBuffer, PolarsError> {
+        if layout.is_empty() {
+            polars_bail!(InvalidOperation: "vertex layout must have at least one field");
+        }
+        let columns: Vec<Vec<Vec<f64>>> = layout
+            .iter()
+            .map(|field| match field {
+                VertexField::Position => Ok(vec![self.x()?, self.y()?, self.z()?]),
+                VertexField::Color => COLOR_COLUMNS
+                    .iter()
+                    .map(|name| self.column_f64(name))
+                    .collect(),
+                VertexField::Intensity => Ok(vec![self.column_f64("intensity")?]),
+            })
+            .collect::<Result<_, PolarsError>>()?;
+
+        let mut offsets = Vec::with_capacity(layout.len());
+        let mut stride = 0;
+        for field in layout {
+            offsets.push(stride);
+            stride += field.size();
+        }
+
+        let mut data = Vec::with_capacity(stride * self.len());
+        for i in 0..self.len() {
+            for (field, values) in layout.iter().zip(&columns) {
+                for channel in values {
+                    match field {
+                        VertexField::Position | VertexField::Intensity => {
+                            data.extend_from_slice(&(channel[i] as f32).to_le_bytes())
+                        }
+                        VertexField::Color => {
+                            let c = channel[i];
+                            data.push(if c.is_nan() {
+                                0
+                            } else {
+                                c.round().clamp(0.0, 255.0) as u8
+                            })
+                        }
+                    }
+                }
+            }
+        }
+        Ok(VertexBuffer {
+            data,
+            stride,
+            offsets,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn packs_positions_and_colors_interleaved() {
+        let mut cloud =
+            TablePointCloud::from_xyz(vec![1.0, -2.5], vec![0.5, 4.0], vec![-0.25, 8.0]).unwrap();
+        for (name, values) in
+            COLOR_COLUMNS
+                .into_iter()
+                .zip([[255.0, 0.4], [12.6, 300.0], [-3.0, f64::NAN]])
+        {
+            cloud
+                .set_attribute(Series::new(name.into(), values.to_vec()))
+                .unwrap();
+        }
+        let layout = [VertexField::Position, VertexField::Color];
+        let buffer = cloud.to_vertex_buffer(&layout).unwrap();
+        assert_eq!(buffer.stride, 15);
+        assert_eq!(buffer.offsets, [0, 12]);
+        assert_eq!(buffer.data.len(), 2 * 15);
+
+        let decode = |record: &[u8]| {
+            let position: Vec<f32> = record[..12]
+                .chunks_exact(4)
+                .map(|b| f32::from_le_bytes(b.try_into().unwrap()))
+                .collect();
+            (position, record[12..15].to_vec())
+        };
+        let records: Vec<_> = buffer
+            .data
+            .chunks_exact(buffer.stride)
+            .map(decode)
+            .collect();
+        assert_eq!(records[0], (vec![1.0, 0.5, -0.25], vec![255, 13, 0]));
+        assert_eq!(records[1], (vec![-2.5, 4.0, 8.0], vec![0, 255, 0]));
+
+        // Fields follow the layout order.
+        let flipped = cloud
+            .to_vertex_buffer(&[VertexField::Color, VertexField::Position])
+            .unwrap();
+        assert_eq!(flipped.offsets, [0, 3]);
+        assert_eq!(flipped.data[..3], buffer.data[12..15]);
+        assert!(cloud.to_vertex_buffer(&[]).is_err());
+        assert!(cloud.to_vertex_buffer(&[VertexField::Intensity]).is_err());
+    }
+}
@@ -0,0 +1,290 @@
+use std::collections::{HashMap, HashSet};
+
+use nalgebra::Vector3;
+use polars::prelude::*;
+
+use crate::TablePointCloud;
+
+impl TablePointCloud {
+    /// Indices, in ascending order, of the points visible from `viewpoint`
+    /// according to Katz's hidden point removal operator.
+    ///
+    /// Points are expressed relative to the viewpoint and spherically
+    /// flipped about a sphere of radius `R = max_norm * 10^radius_param`;
+    /// the visible points are those whose flipped image lies on the convex
+    /// hull of the flipped cloud together with the viewpoint. Larger
+    /// `radius_param` values (typically 1 to 4) report more points visible,
+    /// including some on surfaces seen at grazing angles. Points coinciding
+    /// with the viewpoint are never reported.
+    pub fn hidden_point_removal(
+        &self,
+        viewpoint: [f64; 3],
+        radius_param: f64,
+    ) -> Result<Vec<usize>, PolarsError> {
+        if !radius_param.is_finite() {
+            polars_bail!(InvalidOperation: "HPR radius parameter must be finite, got {}", radius_param);
+        }
+        let eye = Vector3::from(viewpoint);
+        let relative: Vec<Vector3<f64>> = self
+            .xyz()?
+            .into_iter()
+            .map(|p| Vector3::from(p) - eye)
+            .collect();
+        let max_norm = relative.iter().map(|p| p.norm()).fold(0.0, f64::max);
+        if max_norm == 0.0 {
+            return Ok(Vec::new());
+        }
+        let radius = max_norm * 10f64.powf(radius_param);
+        // The viewpoint itself goes last so it cannot shadow a real index.
+        let mut flipped: Vec<Vector3<f64>> = relative
+            .iter()
+            .map(|p| {
+                let norm = p.norm();
+                if norm == 0.0 {
+                    *p
+                } else {
+                    p + p * (2.0 * (radius - norm) / norm)
+                }
+            })
+            .collect();
+        flipped.push(Vector3::zeros());
+
+        let mut visible: Vec<usize> = convex_hull_vertices(&flipped)
+            .into_iter()
+            .filter(|&i| i < relative.len() && relative[i].norm() > 0.0)
+            .collect();
+        visible.sort_unstable();
+        Ok(visible)
+    }
+}
+
+/// A triangle of the hull under construction, wound counter-clockwise when
+/// seen from outside, with the points still outside it.
+struct HullFace {
+    vertices: [usize; 3],
+    normal: Vector3<f64>,
+    offset: f64,
+    outside: Vec<usize>,
+    alive: bool,
+}
+
+impl HullFace {
+    fn new(points: &[Vector3<f64>], vertices: [usize; 3]) -> Self {
+        let [a, b, c] = vertices.map(|i| points[i]);
+        let normal = (b - a).cross(&(c - a)).normalize();
+        Self {
+            vertices,
+            normal,
+            offset: normal.dot(&a),
+            outside: Vec::new(),
+            alive: true,
+        }
+    }
+
+    fn distance(&self, p: &Vector3<f64>) -> f64 {
+        self.normal.dot(p) - self.offset
+    }
+
+    fn edges(&self) -> [(usize, usize); 3] {
+        let [a, b, c] = self.vertices;
+        [(a, b), (b, c), (c, a)]
+    }
+}
+
+/// Indices of the points on the convex hull, found by quickhull. Points
+/// within a small tolerance of a hull face count as inside. Degenerate
+/// (coplanar or smaller) inputs report every point.
+fn convex_hull_vertices(points: &[Vector3<f64>]) -> Vec<usize> {
+    let Some(initial) = initial_simplex(points) else {
+        return (0..points.len()).collect();
+    };
+    let extent = points.iter().map(|p| p.amax()).fold(0.0, f64::max);
+    let eps = extent * 1e-12;
+
+    let centroid = initial.iter().map(|&i| points[i]).sum::<Vector3<f64>>() / 4.0;
+    let mut faces: Vec<HullFace> = Vec::new();
+    for skip in 0..4 {
+        let mut tri: Vec<usize> = (0..4).filter(|&k| k != skip).map(|k| initial[k]).collect();
+        let mut face = HullFace::new(points, [tri[0], tri[1], tri[2]]);
+        if face.distance(&centroid) > 0.0 {
+            tri.swap(1, 2);
+            face = HullFace::new(points, [tri[0], tri[1], tri[2]]);
+        }
+        faces.push(face);
+    }
+    let mut edge_owner: HashMap<(usize, usize), usize> = HashMap::new();
+    for (f, face) in faces.iter().enumerate() {
+        for edge in face.edges() {
+            edge_owner.insert(edge, f);
+        }
+    }
+    for (i, p) in points.iter().enumerate() {
+        if initial.contains(&i) {
+            continue;
+        }
+        if let Some(face) = faces.iter_mut().find(|f| f.distance(p) > eps) {
+            face.outside.push(i);
+        }
+    }
+
+    let mut pending: Vec<usize> = (0..faces.len()).collect();
+    while let Some(f) = pending.pop() {
+        if !faces[f].alive || faces[f].outside.is_empty() {
+            continue;
+        }
+        let apex = *faces[f]
+            .outside
+            .iter()
+            .max_by(|&&a, &&b| {
+                faces[f]
+                    .distance(&points[a])
+                    .total_cmp(&faces[f].distance(&points[b]))
+            })
+            .expect("non-empty outside set");
+        let p = points[apex];
+
+        // Faces visible from the apex form a connected patch around `f`.
+        let mut visible = vec![f];
+        let mut is_visible: HashMap<usize, bool> = HashMap::from([(f, true)]);
+        let mut horizon: Vec<(usize, usize)> = Vec::new();
+        let mut k = 0;
+        while k < visible.len() {
+            let current = visible[k];
+            k += 1;
+            for (a, b) in faces[current].edges() {
+                let neighbor = edge_owner[&(b, a)];
+                let seen_from_apex = *is_visible.entry(neighbor).or_insert_with(|| {
+                    let lit = faces[neighbor].distance(&p) > eps;
+                    if lit {
+                        visible.push(neighbor);
+                    }
+                    lit
+                });
+                if !seen_from_apex {
+                    horizon.push((a, b));
+                }
+            }
+        }
+
+        let mut orphans: Vec<usize> = Vec::new();
+        for &v in &visible {
+            faces[v].alive = false;
+            orphans.append(&mut faces[v].outside);
+            for edge in faces[v].edges() {
+                if edge_owner.get(&edge) == Some(&v) {
+                    edge_owner.remove(&edge);
+                }
+            }
+        }
+        let first_new = faces.len();
+        for (a, b) in horizon {
+            let id = faces.len();
+            let face = HullFace::new(points, [a, b, apex]);
+            for edge in face.edges() {
+                edge_owner.insert(edge, id);
+            }
+            faces.push(face);
+        }
+        for i in orphans {
+            if i == apex {
+                continue;
+            }
+            if let Some(face) = faces[first_new..]
+                .iter_mut()
+                .find(|face| face.distance(&points[i]) > eps)
+            {
+                face.outside.push(i);
+            }
+        }
+        pending.extend(first_new..faces.len());
+    }
+
+    let mut vertices: Vec<usize> = faces
+        .iter()
+        .filter(|f| f.alive)
+        .flat_map(|f| f.vertices)
+        .collect::<HashSet<usize>>()
+        .into_iter()
+        .collect();
+    vertices.sort_unstable();
+    vertices
+}
+
+/// Four affinely independent points to start the hull from, or `None` when
+/// all points are coplanar.
+fn initial_simplex(points: &[Vector3<f64>]) -> Option<[usize; 4]> {
+    let first = points.first()?;
+    let extent = points
+        .iter()
+        .map(|p| (p - first).amax())
+        .fold(0.0, f64::max);
+    if extent == 0.0 {
+        return None;
+    }
+    let tol = extent * 1e-9;
+    let a = 0;
+    let b = (0..points.len()).max_by(|&i, &j| {
+        (points[i] - first)
+            .norm()
+            .total_cmp(&(points[j] - first).norm())
+    })?;
+    let ab = points[b] - points[a];
+    let line_distance = |i: usize| (points[i] - points[a]).cross(&ab).norm() / ab.norm();
+    let c = (0..points.len()).max_by(|&i, &j| line_distance(i).total_cmp(&line_distance(j)))?;
+    if line_distance(c) <= tol {
+        return None;
+    }
+    let normal = ab.cross(&(points[c] - points[a])).normalize();
+    let plane_distance = |i: usize| normal.dot(&(points[i] - points[a])).abs();
+    let d = (0..points.len()).max_by(|&i, &j| plane_distance(i).total_cmp(&plane_distance(j)))?;
+    if plane_distance(d) <= tol {
+        return None;
+    }
+    Some([a, b, c, d])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Surface of the cube `[-1, 1]^3`, sampled on a 0.5 grid.
+    fn cube_surface() -> TablePointCloud {
+        let steps: Vec<f64> = (0..=4).map(|i| -1.0 + 0.5 * i as f64).collect();
+        let (mut x, mut y, mut z) = (Vec::new(), Vec::new(), Vec::new());
+        for &a in &steps {
+            for &b in &steps {
+                for &c in &steps {
+                    if [a, b, c].iter().any(|v| v.abs() == 1.0) {
+                        x.push(a);
+                        y.push(b);
+                        z.push(c);
+                    }
+                }
+            }
+        }
+        TablePointCloud::from_xyz(x, y, z).unwrap()
+    }
+
+    #[test]
+    fn only_the_facing_side_of_a_cube_is_visible() {
+        let cube = cube_surface();
+        let points = cube.xyz().unwrap();
+        let visible = cube.hidden_point_removal([10.0, 0.0, 0.0], 1.0).unwrap();
+        assert!(visible.windows(2).all(|w| w[0] < w[1]));
+        for &i in &visible {
+            assert_eq!(
+                points[i][0], 1.0,
+                "{:?} is not on the facing side",
+                points[i]
+            );
+        }
+        let facing = points.iter().filter(|p| p[0] == 1.0).count();
+        assert_eq!(visible.len(), facing);
+
+        // From a corner direction three faces are in view.
+        let corner = cube.hidden_point_removal([10.0, 10.0, 10.0], 1.0).unwrap();
+        assert!(corner.iter().all(|&i| points[i].contains(&1.0)));
+        assert!(corner.len() > facing);
+        assert!(cube.hidden_point_removal([0.0; 3], f64::NAN).is_err());
+    }
+}
@@ -0,0 +1,58 @@
+use crate::cloud::COLOR_COLUMNS;
+use crate::{PcdError, TablePointCloud};
+
+impl TablePointCloud {
+    /// Logs the cloud as `Points3D` under `entity` on a Rerun recording.
+    ///
+    /// Colors are attached when the cloud carries `red`, `green` and `blue`
+    /// columns; values are clamped to the 0-255 range.
+    pub fn log_to_rerun(
+        &self,
+        stream: &rerun::RecordingStream,
+        entity: &str,
+    ) -> Result<(), PcdError> {
+        let positions: Vec<[f32; 3]> = self
+            .xyz()?
+            .into_iter()
+            .map(|[x, y, z]| [x as f32, y as f32, z as f32])
+            .collect();
+        let mut points = rerun::Points3D::new(positions);
+        if COLOR_COLUMNS.iter().all(|c| self.has_column(c)) {
+            let (r, g, b) = (
+                self.column_f64("red")?,
+                self.column_f64("green")?,
+                self.column_f64("blue")?,
+            );
+            let channel = |v: f64| v.clamp(0.0, 255.0) as u8;
+            let colors = (0..self.len())
+                .map(|i| rerun::Color::from_rgb(channel(r[i]), channel(g[i]), channel(b[i])));
+            points = points.with_colors(colors);
+        }
+        stream.log(entity, &points)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use polars::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn logs_colored_cloud_to_memory_recording() {
+        let mut cloud =
+            TablePointCloud::from_xyz(vec![0.0, 1.0], vec![0.0, 1.0], vec![0.0, 1.0]).unwrap();
+        for name in COLOR_COLUMNS {
+            cloud
+                .set_attribute(Series::new(name.into(), vec![0u8, 255]))
+                .unwrap();
+        }
+        let (stream, storage) = rerun::RecordingStreamBuilder::new("pcl_rustic_test")
+            .memory()
+            .unwrap();
+        let before = storage.num_msgs();
+        cloud.log_to_rerun(&stream, "cloud").unwrap();
+        assert!(storage.num_msgs() > before);
+    }
+}
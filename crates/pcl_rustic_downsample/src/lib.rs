@@ -1,3 +1,205 @@
-pub fn downsample_point_cloud() {
-    // placeholder for downsampling algorithm
+use pcl_rustic_core::TablePointCloud;
+use polars::prelude::PolarsError;
+
+mod sample;
+mod voxel;
+
+/// How [`Downsample::limit_points`] thins a cloud that is over budget.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LimitStrategy {
+    /// Keeps a uniform random subset; the same seed gives the same subset.
+    Random { seed: u64 },
+    /// Keeps exactly `max` points evenly spaced in storage order.
+    Decimate,
+    /// Voxel downsampling with a leaf size grown until the budget is met.
+    Voxel,
+}
+
+/// How [`Downsample::voxel_downsample_with`] reduces the points of a voxel.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VoxelReduction {
+    /// The mean of every column, as in [`Downsample::voxel_downsample`].
+    Mean,
+    /// The input point with the largest value in the named column.
+    MaxBy(String),
+    /// The input point with the smallest value in the named column.
+    MinBy(String),
+}
+
+/// Downsampling operations on point clouds.
+pub trait Downsample: Sized {
+    /// Replaces the points falling into each voxel of side `leaf_size` by
+    /// their mean.
+    ///
+    /// The voxel grid is anchored at the cloud's minimum corner. Every
+    /// numeric attribute is averaged as well (nulls are skipped), so the
+    /// output attributes are `f64`.
+    fn voxel_downsample(&self, leaf_size: f64) -> Result<Self, PolarsError>;
+
+    /// `voxel_downsample` with a choice of how each voxel is reduced.
+    ///
+    /// `MaxBy` and `MinBy` keep an actual input point per voxel, with all
+    /// its attributes and their types, e.g. the brightest return by
+    /// `intensity`. Nulls and NaNs in the column are skipped; a voxel with
+    /// no valid value keeps its first point. Output points follow the order
+    /// of each voxel's first point.
+    fn voxel_downsample_with(
+        &self,
+        leaf_size: f64,
+        reduction: &VoxelReduction,
+    ) -> Result<Self, PolarsError>;
+
+    /// Suggests a `voxel_downsample` leaf size that leaves roughly
+    /// `target_points` points.
+    ///
+    /// This is an approximate heuristic: it divides the bounding box
+    /// volume (or area/length for flat or linear clouds) into about
+    /// `target_points` cells, assuming every cell is occupied. It is close
+    /// for evenly spread clouds and overshoots the size for clumpy or
+    /// hollow ones.
+    fn suggest_voxel_size(&self, target_points: usize) -> Result<f64, PolarsError>;
+
+    /// Merges overlapping clouds, averaging the points that share a voxel
+    /// of side `voxel_size`.
+    ///
+    /// The clouds are stacked with [`TablePointCloud::concat`], so
+    /// attributes missing from some clouds are null there and skipped by
+    /// the averaging, then passed through `voxel_downsample`.
+    fn fuse(clouds: &[&Self], voxel_size: f64) -> Result<Self, PolarsError>;
+
+    /// Thins the cloud to at most `max` points, returning a clone when it
+    /// is already within budget.
+    ///
+    /// `Random` and `Decimate` keep original points in their original
+    /// order; `Voxel` averages them like `voxel_downsample` and usually
+    /// lands somewhat below `max`.
+    fn limit_points(&self, max: usize, strategy: LimitStrategy) -> Result<Self, PolarsError>;
+
+    /// `voxel_downsample` that never leaves fewer than `min_points` points.
+    ///
+    /// When the voxel grid would produce fewer, a random subset of
+    /// `min_points` original points is returned instead (drawn with a fixed
+    /// seed, so the result is reproducible). A cloud smaller than
+    /// `min_points` is returned whole.
+    fn voxel_downsample_min(&self, leaf_size: f64, min_points: usize) -> Result<Self, PolarsError>;
+}
+
+impl Downsample for TablePointCloud {
+    fn voxel_downsample(&self, leaf_size: f64) -> Result<Self, PolarsError> {
+        voxel::voxel_downsample(self, leaf_size)
+    }
+
+    fn voxel_downsample_with(
+        &self,
+        leaf_size: f64,
+        reduction: &VoxelReduction,
+    ) -> Result<Self, PolarsError> {
+        voxel::voxel_downsample_with(self, leaf_size, reduction)
+    }
+
+    fn suggest_voxel_size(&self, target_points: usize) -> Result<f64, PolarsError> {
+        voxel::suggest_voxel_size(self, target_points)
+    }
+
+    fn fuse(clouds: &[&Self], voxel_size: f64) -> Result<Self, PolarsError> {
+        voxel::voxel_downsample(&TablePointCloud::concat(clouds)?, voxel_size)
+    }
+
+    fn limit_points(&self, max: usize, strategy: LimitStrategy) -> Result<Self, PolarsError> {
+        sample::limit_points(self, max, strategy)
+    }
+
+    fn voxel_downsample_min(&self, leaf_size: f64, min_points: usize) -> Result<Self, PolarsError> {
+        let reduced = voxel::voxel_downsample(self, leaf_size)?;
+        if reduced.len() >= min_points {
+            return Ok(reduced);
+        }
+        sample::random_subsample(self, min_points, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use polars::prelude::*;
+
+    use super::*;
+
+    /// A 3x3 grid of unit spacing shifted by `offset` in x and y, with a
+    /// constant `column`.
+    fn grid(offset: f64, column: &str, value: f64) -> TablePointCloud {
+        let cells: Vec<(f64, f64)> = (0..3)
+            .flat_map(|j| (0..3).map(move |i| (i as f64 + offset, j as f64 + offset)))
+            .collect();
+        let mut cloud = TablePointCloud::from_xyz(
+            cells.iter().map(|c| c.0).collect(),
+            cells.iter().map(|c| c.1).collect(),
+            vec![0.0; cells.len()],
+        )
+        .unwrap();
+        cloud
+            .set_attribute(Series::new(column.into(), vec![value; cells.len()]))
+            .unwrap();
+        cloud
+    }
+
+    #[test]
+    fn fuse_averages_overlapping_grids() {
+        let first = grid(0.0, "intensity", 10.0);
+        let mut second = grid(0.2, "intensity", 20.0);
+        second
+            .set_attribute(Series::new("ring".into(), vec![3.0; 9]))
+            .unwrap();
+
+        let fused = TablePointCloud::fuse(&[&first, &second], 1.0).unwrap();
+        assert_eq!(fused.len(), 9);
+        let expected = grid(0.1, "intensity", 15.0);
+        let (x, y) = (fused.x().unwrap(), fused.y().unwrap());
+        for (i, (ex, ey)) in expected
+            .x()
+            .unwrap()
+            .into_iter()
+            .zip(expected.y().unwrap())
+            .enumerate()
+        {
+            assert!((x[i] - ex).abs() < 1e-12 && (y[i] - ey).abs() < 1e-12);
+        }
+        assert_eq!(fused.attribute("intensity").unwrap(), vec![15.0; 9]);
+        // Only the second cloud has rings; its nulls in the first are skipped.
+        assert_eq!(fused.attribute("ring").unwrap(), vec![3.0; 9]);
+    }
+
+    #[test]
+    fn downsampling_keeps_global_positions() {
+        let mut first = grid(0.0, "intensity", 10.0);
+        first.set_origin([1000.0, 2000.0, 30.0]);
+        let global = first.global_xyz().unwrap();
+        let reduced = first.voxel_downsample(0.5).unwrap();
+        assert_eq!(reduced.global_xyz().unwrap(), global);
+        assert_eq!(
+            first.voxel_downsample_min(10.0, 1).unwrap().origin(),
+            first.origin()
+        );
+
+        let mut second = grid(0.0, "intensity", 20.0);
+        second.set_origin([1000.0, 2000.0, 30.0]);
+        let second = second.relocate_origin([0.0; 3]).unwrap();
+        let fused = TablePointCloud::fuse(&[&first, &second], 0.5).unwrap();
+        assert_eq!(fused.origin(), first.origin());
+        assert_eq!(fused.global_xyz().unwrap(), global);
+        assert_eq!(fused.attribute("intensity").unwrap(), vec![15.0; 9]);
+    }
+
+    #[test]
+    fn sparse_cloud_keeps_min_points() {
+        let sparse = TablePointCloud::sample_box([0.0; 3], [1.0; 3], 50, 5).unwrap();
+        // One voxel swallows the whole cloud, so the fallback kicks in.
+        assert_eq!(sparse.voxel_downsample(10.0).unwrap().len(), 1);
+        let kept = sparse.voxel_downsample_min(10.0, 20).unwrap();
+        assert_eq!(kept.len(), 20);
+        assert!(kept.approx_eq(&sparse.voxel_downsample_min(10.0, 20).unwrap(), 0.0));
+
+        assert_eq!(sparse.voxel_downsample_min(10.0, 80).unwrap().len(), 50);
+        let fine = sparse.voxel_downsample_min(1e-3, 20).unwrap();
+        assert_eq!(fine.len(), 50);
+    }
 }
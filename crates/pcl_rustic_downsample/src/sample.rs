@@ -0,0 +1,91 @@
+use pcl_rustic_core::TablePointCloud;
+use polars::prelude::*;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use crate::voxel;
+use crate::LimitStrategy;
+
+pub(crate) fn limit_points(
+    cloud: &TablePointCloud,
+    max: usize,
+    strategy: LimitStrategy,
+) -> Result<TablePointCloud, PolarsError> {
+    if cloud.len() <= max {
+        return Ok(cloud.clone());
+    }
+    if max == 0 {
+        return Ok(cloud.slice(0, 0));
+    }
+    match strategy {
+        LimitStrategy::Random { seed } => random_subsample(cloud, max, seed),
+        LimitStrategy::Decimate => {
+            let indices: Vec<usize> = (0..max).map(|i| i * cloud.len() / max).collect();
+            cloud.take(&indices)
+        }
+        LimitStrategy::Voxel => {
+            let (lo, hi) = cloud.aabb()?;
+            if lo == hi {
+                // Coincident points share a voxel of any size.
+                return voxel::voxel_downsample(cloud, 1.0);
+            }
+            let mut leaf_size = voxel::suggest_voxel_size(cloud, max)?;
+            loop {
+                let reduced = voxel::voxel_downsample(cloud, leaf_size)?;
+                if reduced.len() <= max {
+                    return Ok(reduced);
+                }
+                leaf_size *= 1.1;
+            }
+        }
+    }
+}
+
+/// `count` distinct points drawn uniformly, kept in their original order.
+pub(crate) fn random_subsample(
+    cloud: &TablePointCloud,
+    count: usize,
+    seed: u64,
+) -> Result<TablePointCloud, PolarsError> {
+    let count = count.min(cloud.len());
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut indices = rand::seq::index::sample(&mut rng, cloud.len(), count).into_vec();
+    indices.sort_unstable();
+    cloud.take(&indices)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_strategy_respects_the_budget() {
+        let cloud = TablePointCloud::sample_box([0.0; 3], [5.0; 3], 5_000, 3).unwrap();
+        for strategy in [
+            LimitStrategy::Random { seed: 1 },
+            LimitStrategy::Decimate,
+            LimitStrategy::Voxel,
+        ] {
+            for max in [0, 1, 250, 4_999] {
+                let limited = limit_points(&cloud, max, strategy).unwrap();
+                assert!(
+                    limited.len() <= max,
+                    "{strategy:?} kept {} > {max}",
+                    limited.len()
+                );
+            }
+            let whole = limit_points(&cloud, 5_000, strategy).unwrap();
+            assert!(whole.approx_eq(&cloud, 0.0));
+        }
+        let coincident =
+            TablePointCloud::from_xyz(vec![1.0; 10], vec![2.0; 10], vec![3.0; 10]).unwrap();
+        let merged = limit_points(&coincident, 5, LimitStrategy::Voxel).unwrap();
+        assert_eq!(merged.xyz().unwrap(), [[1.0, 2.0, 3.0]]);
+        assert_eq!(
+            limit_points(&cloud, 250, LimitStrategy::Decimate)
+                .unwrap()
+                .len(),
+            250
+        );
+    }
+}
@@ -0,0 +1,212 @@
+use std::collections::HashMap;
+
+use pcl_rustic_core::TablePointCloud;
+use polars::prelude::*;
+
+use crate::VoxelReduction;
+
+pub(crate) fn voxel_downsample(
+    cloud: &TablePointCloud,
+    leaf_size: f64,
+) -> Result<TablePointCloud, PolarsError> {
+    check_leaf_size(leaf_size)?;
+    let groups = voxel_groups(&cloud.xyz()?, leaf_size);
+    mean_of_groups(cloud, &groups)
+}
+
+pub(crate) fn voxel_downsample_with(
+    cloud: &TablePointCloud,
+    leaf_size: f64,
+    reduction: &VoxelReduction,
+) -> Result<TablePointCloud, PolarsError> {
+    let (column, largest) = match reduction {
+        VoxelReduction::Mean => return voxel_downsample(cloud, leaf_size),
+        VoxelReduction::MaxBy(column) => (column, true),
+        VoxelReduction::MinBy(column) => (column, false),
+    };
+    check_leaf_size(leaf_size)?;
+    let values = cloud.attribute(column)?;
+    let groups = voxel_groups(&cloud.xyz()?, leaf_size);
+    let picks: Vec<usize> = groups
+        .iter()
+        .map(|group| {
+            group
+                .iter()
+                .copied()
+                .filter(|&i| !values[i].is_nan())
+                .reduce(|best, i| {
+                    let better = if largest {
+                        values[i] > values[best]
+                    } else {
+                        values[i] < values[best]
+                    };
+                    if better {
+                        i
+                    } else {
+                        best
+                    }
+                })
+                .unwrap_or(group[0])
+        })
+        .collect();
+    cloud.take(&picks)
+}
+
+pub(crate) fn suggest_voxel_size(
+    cloud: &TablePointCloud,
+    target_points: usize,
+) -> Result<f64, PolarsError> {
+    if target_points == 0 {
+        polars_bail!(InvalidOperation: "target point count must be positive");
+    }
+    let (min, max) = cloud.aabb()?;
+    let extents: Vec<f64> = (0..3).map(|axis| max[axis] - min[axis]).collect();
+    let largest = extents.iter().cloned().fold(0.0, f64::max);
+    if largest == 0.0 {
+        polars_bail!(ComputeError: "all points coincide, any voxel size keeps a single point");
+    }
+    // Only axes with a real extent contribute; a planar cloud is sized by
+    // its area and a linear one by its length. A grid anchored at the min
+    // corner covers each axis with about `extent / size + 0.5` cells on
+    // average, so solve that cell count for the target by bisection.
+    let spanned: Vec<f64> = extents
+        .into_iter()
+        .filter(|&extent| extent > largest * 1e-9)
+        .collect();
+    let cells = |size: f64| spanned.iter().map(|e| e / size + 0.5).product::<f64>();
+    let target = target_points as f64;
+    let (mut lo, mut hi) = (largest * 1e-12, largest * 4.0);
+    for _ in 0..200 {
+        let mid = (lo * hi).sqrt();
+        if cells(mid) > target {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    Ok((lo * hi).sqrt())
+}
+
+fn check_leaf_size(leaf_size: f64) -> Result<(), PolarsError> {
+    if !(leaf_size.is_finite() && leaf_size > 0.0) {
+        polars_bail!(InvalidOperation: "leaf size must be positive and finite, got {}", leaf_size);
+    }
+    Ok(())
+}
+
+fn bounds(points: &[[f64; 3]]) -> Option<([f64; 3], [f64; 3])> {
+    let first = *points.first()?;
+    Some(points.iter().fold((first, first), |(mut min, mut max), p| {
+        for axis in 0..3 {
+            min[axis] = min[axis].min(p[axis]);
+            max[axis] = max[axis].max(p[axis]);
+        }
+        (min, max)
+    }))
+}
+
+/// Point indices grouped by voxel, in order of each voxel's first point.
+pub(crate) fn voxel_groups(points: &[[f64; 3]], leaf_size: f64) -> Vec<Vec<usize>> {
+    let Some((min, _)) = bounds(points) else {
+        return Vec::new();
+    };
+    let mut slots: HashMap<[i64; 3], usize> = HashMap::new();
+    let mut groups: Vec<Vec<usize>> = Vec::new();
+    for (i, p) in points.iter().enumerate() {
+        let key = [0, 1, 2].map(|axis| ((p[axis] - min[axis]) / leaf_size).floor() as i64);
+        let slot = *slots.entry(key).or_insert_with(|| {
+            groups.push(Vec::new());
+            groups.len() - 1
+        });
+        groups[slot].push(i);
+    }
+    groups
+}
+
+/// Collapses each group of points into one point holding the mean of
+/// every column, keeping the cloud's origin.
+pub(crate) fn mean_of_groups(
+    cloud: &TablePointCloud,
+    groups: &[Vec<usize>],
+) -> Result<TablePointCloud, PolarsError> {
+    let (x, y, z) = (cloud.x()?, cloud.y()?, cloud.z()?);
+    let mean = |values: &[f64], group: &[usize]| {
+        group.iter().map(|&i| values[i]).sum::<f64>() / group.len() as f64
+    };
+    let mut out = TablePointCloud::from_xyz(
+        groups.iter().map(|g| mean(&x, g)).collect(),
+        groups.iter().map(|g| mean(&y, g)).collect(),
+        groups.iter().map(|g| mean(&z, g)).collect(),
+    )?;
+    out.set_origin(cloud.origin());
+    for name in cloud.attribute_names() {
+        let column = cloud.data().column(&name)?.cast(&DataType::Float64)?;
+        let values: Vec<Option<f64>> = column.f64()?.iter().collect();
+        let means: Vec<Option<f64>> = groups
+            .iter()
+            .map(|group| {
+                let present: Vec<f64> = group.iter().filter_map(|&i| values[i]).collect();
+                (!present.is_empty()).then(|| present.iter().sum::<f64>() / present.len() as f64)
+            })
+            .collect();
+        out.set_attribute(Series::new(name.as_str().into(), means))?;
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn suggested_size_lands_near_target() {
+        let cloud = TablePointCloud::sample_box([0.0; 3], [10.0; 3], 20_000, 7).unwrap();
+        for target in [100, 1000] {
+            let size = suggest_voxel_size(&cloud, target).unwrap();
+            let kept = voxel_downsample(&cloud, size).unwrap().len() as f64;
+            let target = target as f64;
+            assert!(
+                (kept - target).abs() <= 0.3 * target,
+                "kept {kept} for a target of {target}"
+            );
+        }
+    }
+
+    #[test]
+    fn max_by_keeps_each_voxels_brightest_point() {
+        let mut cloud = TablePointCloud::from_xyz(
+            vec![0.0, 0.5, 0.9, 2.2, 2.7, 0.1],
+            vec![0.0, 0.5, 0.1, 0.0, 0.3, 2.5],
+            vec![0.0, 0.5, 0.2, 0.0, 0.1, 0.0],
+        )
+        .unwrap();
+        cloud
+            .set_attribute(Series::new(
+                "intensity".into(),
+                vec![Some(5u16), Some(9), None, Some(3), Some(1), None],
+            ))
+            .unwrap();
+        cloud
+            .set_attribute(Series::new("label".into(), vec![10i32, 11, 12, 13, 14, 15]))
+            .unwrap();
+
+        let brightest =
+            voxel_downsample_with(&cloud, 1.0, &VoxelReduction::MaxBy("intensity".to_string()))
+                .unwrap();
+        assert_eq!(brightest.len(), 3);
+        assert!(brightest.approx_eq(&cloud.take(&[1, 3, 5]).unwrap(), 0.0));
+        assert_eq!(
+            brightest.data().column("intensity").unwrap().dtype(),
+            &DataType::UInt16
+        );
+        assert_eq!(brightest.attribute("label").unwrap(), [11.0, 13.0, 15.0]);
+
+        let dimmest =
+            voxel_downsample_with(&cloud, 1.0, &VoxelReduction::MinBy("intensity".to_string()))
+                .unwrap();
+        assert!(dimmest.approx_eq(&cloud.take(&[0, 4, 5]).unwrap(), 0.0));
+
+        let missing = VoxelReduction::MaxBy("missing".to_string());
+        assert!(voxel_downsample_with(&cloud, 1.0, &missing).is_err());
+    }
+}
@@ -0,0 +1,271 @@
+use pcl_rustic_core::{PcdError, TablePointCloud};
+use polars::prelude::*;
+
+/// Scalar storage types shared by the PCD and PLY formats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ScalarType {
+    I8,
+    U8,
+    I16,
+    U16,
+    I32,
+    U32,
+    I64,
+    U64,
+    F32,
+    F64,
+}
+
+impl ScalarType {
+    pub(crate) fn size(self) -> usize {
+        match self {
+            ScalarType::I8 | ScalarType::U8 => 1,
+            ScalarType::I16 | ScalarType::U16 => 2,
+            ScalarType::I32 | ScalarType::U32 | ScalarType::F32 => 4,
+            ScalarType::I64 | ScalarType::U64 | ScalarType::F64 => 8,
+        }
+    }
+
+    /// Maps a PCD `TYPE`/`SIZE` pair such as `F 4`.
+    pub(crate) fn from_pcd(kind: &str, size: usize) -> Option<Self> {
+        Some(match (kind, size) {
+            ("I", 1) => ScalarType::I8,
+            ("U", 1) => ScalarType::U8,
+            ("I", 2) => ScalarType::I16,
+            ("U", 2) => ScalarType::U16,
+            ("I", 4) => ScalarType::I32,
+            ("U", 4) => ScalarType::U32,
+            ("I", 8) => ScalarType::I64,
+            ("U", 8) => ScalarType::U64,
+            ("F", 4) => ScalarType::F32,
+            ("F", 8) => ScalarType::F64,
+            _ => return None,
+        })
+    }
+
+    /// Storage type for writing a column of `dtype`, if it is numeric.
+    /// Booleans are stored as `U8`.
+    pub(crate) fn from_dtype(dtype: &DataType) -> Option<Self> {
+        Some(match dtype {
+            DataType::Boolean | DataType::UInt8 => ScalarType::U8,
+            DataType::Int8 => ScalarType::I8,
+            DataType::Int16 => ScalarType::I16,
+            DataType::UInt16 => ScalarType::U16,
+            DataType::Int32 => ScalarType::I32,
+            DataType::UInt32 => ScalarType::U32,
+            DataType::Int64 => ScalarType::I64,
+            DataType::UInt64 => ScalarType::U64,
+            DataType::Float32 => ScalarType::F32,
+            DataType::Float64 => ScalarType::F64,
+            _ => return None,
+        })
+    }
+
+    /// The PCD `TYPE` letter: `I`, `U` or `F`.
+    pub(crate) fn pcd_kind(self) -> &'static str {
+        match self {
+            ScalarType::I8 | ScalarType::I16 | ScalarType::I32 | ScalarType::I64 => "I",
+            ScalarType::U8 | ScalarType::U16 | ScalarType::U32 | ScalarType::U64 => "U",
+            ScalarType::F32 | ScalarType::F64 => "F",
+        }
+    }
+
+    /// The PLY type name; PLY has no 64-bit integers.
+    pub(crate) fn ply_name(self) -> Option<&'static str> {
+        Some(match self {
+            ScalarType::I8 => "char",
+            ScalarType::U8 => "uchar",
+            ScalarType::I16 => "short",
+            ScalarType::U16 => "ushort",
+            ScalarType::I32 => "int",
+            ScalarType::U32 => "uint",
+            ScalarType::F32 => "float",
+            ScalarType::F64 => "double",
+            ScalarType::I64 | ScalarType::U64 => return None,
+        })
+    }
+
+    /// Maps a PLY property type name, accepting both naming styles.
+    pub(crate) fn from_ply(name: &str) -> Option<Self> {
+        Some(match name {
+            "char" | "int8" => ScalarType::I8,
+            "uchar" | "uint8" => ScalarType::U8,
+            "short" | "int16" => ScalarType::I16,
+            "ushort" | "uint16" => ScalarType::U16,
+            "int" | "int32" => ScalarType::I32,
+            "uint" | "uint32" => ScalarType::U32,
+            "float" | "float32" => ScalarType::F32,
+            "double" | "float64" => ScalarType::F64,
+            _ => return None,
+        })
+    }
+}
+
+/// Most values a [`ColumnBuffer`] reserves up front. Capacities come from
+/// file headers, which may be corrupt or hostile, so longer columns grow as
+/// their data is actually read.
+pub(crate) const MAX_PREALLOCATED_VALUES: usize = 1 << 20;
+
+macro_rules! column_buffer {
+    ($($variant:ident => $ty:ty),* $(,)?) => {
+        /// Growable typed storage for one column while a file is parsed.
+        pub(crate) enum ColumnBuffer {
+            $($variant(Vec<$ty>)),*
+        }
+
+        impl ColumnBuffer {
+            /// An empty buffer reserving room for `capacity` values, up to
+            /// [`MAX_PREALLOCATED_VALUES`].
+            pub(crate) fn new(ty: ScalarType, capacity: usize) -> Self {
+                let capacity = capacity.min(MAX_PREALLOCATED_VALUES);
+                match ty {
+                    $(ScalarType::$variant => ColumnBuffer::$variant(Vec::with_capacity(capacity))),*
+                }
+            }
+
+            /// The value at `index` converted to `f64`.
+            pub(crate) fn get_f64(&self, index: usize) -> Option<f64> {
+                match self {
+                    $(ColumnBuffer::$variant(values) => values.get(index).map(|&v| v as f64)),*
+                }
+            }
+
+            /// Appends one value decoded from exactly `size()` bytes.
+            pub(crate) fn push_bytes(&mut self, bytes: &[u8], little_endian: bool) {
+                match self {
+                    $(ColumnBuffer::$variant(values) => {
+                        let raw = bytes.try_into().expect("field width matches its type");
+                        values.push(if little_endian {
+                            <$ty>::from_le_bytes(raw)
+                        } else {
+                            <$ty>::from_be_bytes(raw)
+                        });
+                    }),*
+                }
+            }
+
+            /// Appends one value parsed from ASCII text.
+            pub(crate) fn push_str(&mut self, token: &str) -> Result<(), PcdError> {
+                match self {
+                    $(ColumnBuffer::$variant(values) => values.push(parse_token::<$ty>(token)?)),*
+                }
+                Ok(())
+            }
+
+            pub(crate) fn into_series(self, name: &str) -> Series {
+                match self {
+                    $(ColumnBuffer::$variant(values) => Series::new(name.into(), values)),*
+                }
+            }
+        }
+    };
+}
+
+column_buffer! {
+    I8 => i8,
+    U8 => u8,
+    I16 => i16,
+    U16 => u16,
+    I32 => i32,
+    U32 => u32,
+    I64 => i64,
+    U64 => u64,
+    F32 => f32,
+    F64 => f64,
+}
+
+/// Little-endian encoding of `column` stored as `ty`, one value after
+/// another. Nulls are written as 0, or NaN for floating-point types.
+pub(crate) fn column_le_bytes(column: &Column, ty: ScalarType) -> Result<Vec<u8>, PcdError> {
+    macro_rules! encode {
+        ($dtype:ident, $accessor:ident, $null:expr) => {
+            column
+                .cast(&DataType::$dtype)?
+                .$accessor()?
+                .iter()
+                .flat_map(|v| v.unwrap_or($null).to_le_bytes())
+                .collect()
+        };
+    }
+    Ok(match ty {
+        ScalarType::I8 => encode!(Int8, i8, 0),
+        ScalarType::U8 => encode!(UInt8, u8, 0),
+        ScalarType::I16 => encode!(Int16, i16, 0),
+        ScalarType::U16 => encode!(UInt16, u16, 0),
+        ScalarType::I32 => encode!(Int32, i32, 0),
+        ScalarType::U32 => encode!(UInt32, u32, 0),
+        ScalarType::I64 => encode!(Int64, i64, 0),
+        ScalarType::U64 => encode!(UInt64, u64, 0),
+        ScalarType::F32 => encode!(Float32, f32, f32::NAN),
+        ScalarType::F64 => encode!(Float64, f64, f64::NAN),
+    })
+}
+
+/// Parses a value, accepting integers written with a fractional part.
+fn parse_token<T>(token: &str) -> Result<T, PcdError>
+where
+    T: std::str::FromStr + num_cast::FromF64,
+{
+    token
+        .parse::<T>()
+        .ok()
+        .or_else(|| token.parse::<f64>().ok().map(T::from_f64))
+        .ok_or_else(|| PcdError::Format(format!("cannot parse value '{token}'")))
+}
+
+mod num_cast {
+    /// Lossy `as` conversion from `f64`, used for integers stored as text
+    /// like `3.0`.
+    pub(crate) trait FromF64 {
+        fn from_f64(value: f64) -> Self;
+    }
+
+    macro_rules! impl_from_f64 {
+        ($($ty:ty),*) => {
+            $(impl FromF64 for $ty {
+                fn from_f64(value: f64) -> Self {
+                    value as $ty
+                }
+            })*
+        };
+    }
+
+    impl_from_f64!(i8, u8, i16, u16, i32, u32, i64, u64, f32, f64);
+}
+
+/// Parses the text of an `origin <x> <y> <z>` header comment, used by the
+/// PCD and PLY formats to carry [`TablePointCloud::origin`].
+pub(crate) fn parse_origin(comment: &str) -> Option<[f64; 3]> {
+    let mut tokens = comment.split_whitespace();
+    if tokens.next() != Some("origin") {
+        return None;
+    }
+    let values: Vec<f64> = tokens.map(|t| t.parse().ok()).collect::<Option<_>>()?;
+    values.try_into().ok()
+}
+
+/// Assembles a cloud from parsed columns, which must include `x`, `y` and
+/// `z`. Coordinates are cast to `f64`; attributes keep their file type.
+pub(crate) fn build_cloud(columns: Vec<(String, Series)>) -> Result<TablePointCloud, PcdError> {
+    let coordinate = |name: &str| -> Result<Vec<f64>, PcdError> {
+        let series = columns
+            .iter()
+            .find(|(field, _)| field == name)
+            .map(|(_, series)| series)
+            .ok_or_else(|| PcdError::Format(format!("missing coordinate field '{name}'")))?;
+        let series = series.cast(&DataType::Float64)?;
+        Ok(series
+            .f64()?
+            .iter()
+            .map(|v| v.unwrap_or(f64::NAN))
+            .collect())
+    };
+    let mut cloud =
+        TablePointCloud::from_xyz(coordinate("x")?, coordinate("y")?, coordinate("z")?)?;
+    for (name, series) in columns {
+        if !matches!(name.as_str(), "x" | "y" | "z") {
+            cloud.set_attribute(series)?;
+        }
+    }
+    Ok(cloud)
+}
@@ -1,3 +1,229 @@
-pub fn read_pcdata() {
-    // placeholder for IO functionality
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+
+use flate2::read::MultiGzDecoder;
+use pcl_rustic_core::{Colormap, PcdError, TablePointCloud};
+
+mod column;
+mod pcd;
+mod pcd_writer;
+mod ply;
+mod xyz;
+
+pub use pcd_writer::PcdStreamWriter;
+
+/// Reading point clouds from PCD, PLY and XYZ files, and writing PCD and PLY.
+///
+/// The `*_reader` functions parse any buffered stream. The `*_path`
+/// functions open a file and transparently decompress it when the path ends
+/// in `.gz`; the `*_path_gz` variants always decompress.
+pub trait PointCloudIo: Sized {
+    /// Parses a PCD stream (`ascii`, `binary` or `binary_compressed`).
+    ///
+    /// Packed `rgb`/`rgba` fields are unpacked into `red`, `green`, `blue`
+    /// (and `alpha`) columns.
+    fn from_pcd_reader<R: BufRead>(reader: R) -> Result<Self, PcdError>;
+
+    fn from_pcd_path<P: AsRef<Path>>(path: P) -> Result<Self, PcdError> {
+        Self::from_pcd_reader(open(path.as_ref(), false)?)
+    }
+
+    fn from_pcd_path_gz<P: AsRef<Path>>(path: P) -> Result<Self, PcdError> {
+        Self::from_pcd_reader(open(path.as_ref(), true)?)
+    }
+
+    /// Streams a PCD body as clouds of at most `chunk_size` points, so
+    /// files larger than memory can be processed chunk by chunk.
+    fn from_pcd_chunks<R: BufRead>(
+        reader: R,
+        chunk_size: usize,
+    ) -> impl Iterator<Item = Result<Self, PcdError>>;
+
+    /// Parses the vertex element of an ASCII or binary PLY stream.
+    fn from_ply_reader<R: BufRead>(reader: R) -> Result<Self, PcdError>;
+
+    fn from_ply_path<P: AsRef<Path>>(path: P) -> Result<Self, PcdError> {
+        Self::from_ply_reader(open(path.as_ref(), false)?)
+    }
+
+    fn from_ply_path_gz<P: AsRef<Path>>(path: P) -> Result<Self, PcdError> {
+        Self::from_ply_reader(open(path.as_ref(), true)?)
+    }
+
+    /// Parses `x y z` text rows.
+    fn from_xyz_reader<R: BufRead>(reader: R) -> Result<Self, PcdError>;
+
+    fn from_xyz_path<P: AsRef<Path>>(path: P) -> Result<Self, PcdError> {
+        Self::from_xyz_reader(open(path.as_ref(), false)?)
+    }
+
+    fn from_xyz_path_gz<P: AsRef<Path>>(path: P) -> Result<Self, PcdError> {
+        Self::from_xyz_reader(open(path.as_ref(), true)?)
+    }
+
+    /// Writes a binary PCD with one field per column, recording a
+    /// non-zero [`TablePointCloud::origin`] in a `# origin` comment.
+    fn write_pcd<W: Write>(&self, writer: W) -> Result<(), PcdError>;
+
+    fn write_pcd_path<P: AsRef<Path>>(&self, path: P) -> Result<(), PcdError> {
+        self.write_pcd(BufWriter::new(File::create(path)?))
+    }
+
+    /// Writes a binary little-endian PLY with one vertex property per
+    /// column. 64-bit integers are widened to `double`.
+    fn write_ply<W: Write>(&self, writer: W) -> Result<(), PcdError>;
+
+    fn write_ply_path<P: AsRef<Path>>(&self, path: P) -> Result<(), PcdError> {
+        self.write_ply(BufWriter::new(File::create(path)?))
+    }
+
+    /// Writes a PLY false-colored by the numeric `channel` (e.g. `z` or
+    /// `intensity`), ready to open in a viewer such as MeshLab. Existing
+    /// color columns are overwritten in the written file only.
+    fn export_colored_ply<P: AsRef<Path>>(
+        &self,
+        path: P,
+        channel: &str,
+        colormap: Colormap,
+    ) -> Result<(), PcdError>;
+}
+
+impl PointCloudIo for TablePointCloud {
+    fn from_pcd_reader<R: BufRead>(reader: R) -> Result<Self, PcdError> {
+        pcd::read_pcd(reader)
+    }
+
+    fn from_pcd_chunks<R: BufRead>(
+        reader: R,
+        chunk_size: usize,
+    ) -> impl Iterator<Item = Result<Self, PcdError>> {
+        pcd::PcdChunks::new(reader, chunk_size)
+    }
+
+    fn from_ply_reader<R: BufRead>(reader: R) -> Result<Self, PcdError> {
+        ply::read_ply(reader)
+    }
+
+    fn from_xyz_reader<R: BufRead>(reader: R) -> Result<Self, PcdError> {
+        xyz::read_xyz(reader)
+    }
+
+    fn write_pcd<W: Write>(&self, writer: W) -> Result<(), PcdError> {
+        pcd::write_pcd(self, writer)
+    }
+
+    fn write_ply<W: Write>(&self, writer: W) -> Result<(), PcdError> {
+        ply::write_ply(self, writer)
+    }
+
+    fn export_colored_ply<P: AsRef<Path>>(
+        &self,
+        path: P,
+        channel: &str,
+        colormap: Colormap,
+    ) -> Result<(), PcdError> {
+        let mut colored = self.clone();
+        colored.colorize_by_scalar(channel, colormap)?;
+        colored.write_ply_path(path)
+    }
+}
+
+/// Opens `path` for buffered reading, wrapping it in a gzip decoder when
+/// `gzip` is set or the file name ends in `.gz`.
+fn open(path: &Path, gzip: bool) -> Result<Box<dyn BufRead>, PcdError> {
+    let file = File::open(path)?;
+    let gzip = gzip
+        || path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("gz"));
+    Ok(if gzip {
+        Box::new(BufReader::new(MultiGzDecoder::new(file)))
+    } else {
+        Box::new(BufReader::new(file))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+
+    use super::*;
+
+    pub(crate) const SMALL_PCD: &str = "\
+# .PCD v0.7 - Point Cloud Data file format
+VERSION 0.7
+FIELDS x y z intensity
+SIZE 4 4 4 4
+TYPE F F F F
+COUNT 1 1 1 1
+WIDTH 3
+HEIGHT 1
+VIEWPOINT 0 0 0 1 0 0 0
+POINTS 3
+DATA ascii
+0 0 0 1
+1 2 3 2
+-1.5 0.5 4 3
+";
+
+    /// A path under the system temp directory unique to this process.
+    pub(crate) fn temp_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("pcl_rustic_io_{}_{name}", std::process::id()))
+    }
+
+    #[test]
+    fn reads_gzipped_pcd() {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(SMALL_PCD.as_bytes()).unwrap();
+        let gzipped = encoder.finish().unwrap();
+
+        let plain = TablePointCloud::from_pcd_reader(Cursor::new(SMALL_PCD)).unwrap();
+        let by_extension = temp_path("small.pcd.gz");
+        let explicit = temp_path("small.pcd.z");
+        std::fs::write(&by_extension, &gzipped).unwrap();
+        std::fs::write(&explicit, &gzipped).unwrap();
+        let results = [
+            TablePointCloud::from_pcd_path(&by_extension),
+            TablePointCloud::from_pcd_path_gz(&explicit),
+        ];
+        std::fs::remove_file(&by_extension).unwrap();
+        std::fs::remove_file(&explicit).unwrap();
+
+        assert_eq!(plain.len(), 3);
+        for cloud in results {
+            assert!(cloud.unwrap().approx_eq(&plain, 0.0));
+        }
+    }
+
+    #[test]
+    fn exports_height_colored_ply() {
+        let cloud =
+            TablePointCloud::from_xyz(vec![0.0; 5], vec![0.0; 5], vec![0.0, 1.0, 2.0, 3.0, 4.0])
+                .unwrap();
+        let path = temp_path("height.ply");
+        cloud.export_colored_ply(&path, "z", Colormap::Jet).unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+        let read = TablePointCloud::from_ply_path(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        let header = String::from_utf8_lossy(&bytes[..bytes.len().min(256)]).into_owned();
+        for channel in ["red", "green", "blue"] {
+            assert!(
+                header.contains(&format!("property uchar {channel}\n")),
+                "{header}"
+            );
+        }
+        let read = read.unwrap();
+        let [r, g, b] = ["red", "green", "blue"].map(|c| read.attribute(c).unwrap());
+        let color = |i: usize| [r[i], g[i], b[i]].map(|c| c as u8);
+        assert_eq!(color(0), Colormap::Jet.color(0.0));
+        assert_eq!(color(2), Colormap::Jet.color(0.5));
+        assert_eq!(color(4), Colormap::Jet.color(1.0));
+        // The source cloud itself is left uncolored.
+        assert!(cloud.attribute_names().is_empty());
+    }
 }
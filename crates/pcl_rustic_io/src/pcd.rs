@@ -0,0 +1,719 @@
+use std::io::{BufRead, Read, Write};
+
+use pcl_rustic_core::{PcdError, TablePointCloud};
+use polars::prelude::*;
+
+use crate::column::{
+    build_cloud, column_le_bytes, parse_origin, ColumnBuffer, ScalarType, MAX_PREALLOCATED_VALUES,
+};
+
+/// Most columns a PCD header may declare across all fields and counts.
+/// Real descriptors stay in the hundreds; a corrupt `COUNT` would otherwise
+/// allocate one buffer per claimed column.
+const MAX_FIELD_ELEMENTS: usize = 1 << 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum PcdEncoding {
+    Ascii,
+    Binary,
+    BinaryCompressed,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct PcdField {
+    pub(crate) name: String,
+    pub(crate) ty: ScalarType,
+    pub(crate) count: usize,
+}
+
+impl PcdField {
+    /// Column names for this field; multi-count fields get an index suffix.
+    fn column_names(&self) -> Vec<String> {
+        if self.count == 1 {
+            vec![self.name.clone()]
+        } else {
+            (0..self.count)
+                .map(|i| format!("{}_{i}", self.name))
+                .collect()
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct PcdHeader {
+    pub(crate) fields: Vec<PcdField>,
+    pub(crate) points: usize,
+    pub(crate) encoding: PcdEncoding,
+    pub(crate) origin: Option<[f64; 3]>,
+    /// `(WIDTH, HEIGHT)` when `HEIGHT > 1`, i.e. the cloud is organized.
+    pub(crate) organized: Option<(usize, usize)>,
+}
+
+impl PcdHeader {
+    /// Bytes per point record. Cannot overflow, since [`read_header`] caps
+    /// the number of field elements.
+    fn point_size(&self) -> usize {
+        self.fields.iter().map(|f| f.ty.size() * f.count).sum()
+    }
+
+    /// Bytes of a binary body holding every point, or a format error when
+    /// the declared point count makes it overflow.
+    fn body_size(&self) -> Result<usize, PcdError> {
+        self.point_size()
+            .checked_mul(self.points)
+            .ok_or_else(|| format_error(format!("{} points overflow the body size", self.points)))
+    }
+}
+
+fn format_error(message: impl Into<String>) -> PcdError {
+    PcdError::Format(message.into())
+}
+
+/// Reads header lines up to and including `DATA`.
+pub(crate) fn read_header<R: BufRead>(reader: &mut R) -> Result<PcdHeader, PcdError> {
+    let mut names: Vec<String> = Vec::new();
+    let mut sizes: Vec<usize> = Vec::new();
+    let mut types: Vec<String> = Vec::new();
+    let mut counts: Vec<usize> = Vec::new();
+    let (mut width, mut height, mut points) = (None, None, None);
+    let mut origin = None;
+
+    let mut line = String::new();
+    let encoding = loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            return Err(format_error("PCD header ended before the DATA line"));
+        }
+        let trimmed = line.trim();
+        if let Some(comment) = trimmed.strip_prefix('#') {
+            origin = parse_origin(comment).or(origin);
+            continue;
+        }
+        if trimmed.is_empty() {
+            continue;
+        }
+        let mut tokens = trimmed.split_whitespace();
+        let key = tokens.next().unwrap_or_default().to_ascii_uppercase();
+        let values: Vec<&str> = tokens.collect();
+        let parse_usize = |v: &str| {
+            v.parse::<usize>()
+                .map_err(|_| format_error(format!("invalid {key} value '{v}'")))
+        };
+        match key.as_str() {
+            "VERSION" | "VIEWPOINT" => {}
+            "FIELDS" | "COLUMNS" => names = values.iter().map(|v| v.to_string()).collect(),
+            "SIZE" => {
+                sizes = values
+                    .iter()
+                    .map(|v| parse_usize(v))
+                    .collect::<Result<_, _>>()?
+            }
+            "TYPE" => types = values.iter().map(|v| v.to_ascii_uppercase()).collect(),
+            "COUNT" => {
+                counts = values
+                    .iter()
+                    .map(|v| parse_usize(v))
+                    .collect::<Result<_, _>>()?
+            }
+            "WIDTH" => width = Some(parse_usize(values.first().copied().unwrap_or_default())?),
+            "HEIGHT" => height = Some(parse_usize(values.first().copied().unwrap_or_default())?),
+            "POINTS" => points = Some(parse_usize(values.first().copied().unwrap_or_default())?),
+            "DATA" => {
+                break match values.first().map(|v| v.to_ascii_lowercase()).as_deref() {
+                    Some("ascii") => PcdEncoding::Ascii,
+                    Some("binary") => PcdEncoding::Binary,
+                    Some("binary_compressed") => PcdEncoding::BinaryCompressed,
+                    other => return Err(format_error(format!("unsupported DATA type {other:?}"))),
+                };
+            }
+            _ => return Err(format_error(format!("unknown header line '{trimmed}'"))),
+        }
+    };
+
+    if counts.is_empty() {
+        counts = vec![1; names.len()];
+    }
+    if counts
+        .iter()
+        .try_fold(0usize, |total, &count| total.checked_add(count))
+        .is_none_or(|total| total > MAX_FIELD_ELEMENTS)
+    {
+        return Err(format_error(format!(
+            "COUNT declares more than {MAX_FIELD_ELEMENTS} columns"
+        )));
+    }
+    if names.is_empty()
+        || sizes.len() != names.len()
+        || types.len() != names.len()
+        || counts.len() != names.len()
+    {
+        return Err(format_error(
+            "FIELDS, SIZE, TYPE and COUNT must list the same number of entries",
+        ));
+    }
+    let fields = names
+        .into_iter()
+        .zip(sizes)
+        .zip(types)
+        .zip(counts)
+        .map(|(((name, size), kind), count)| {
+            let ty = ScalarType::from_pcd(&kind, size).ok_or_else(|| {
+                format_error(format!("unsupported field type {kind}{size} for '{name}'"))
+            })?;
+            Ok(PcdField { name, ty, count })
+        })
+        .collect::<Result<Vec<_>, PcdError>>()?;
+
+    let width = width.ok_or_else(|| format_error("PCD header has no WIDTH"))?;
+    let height = height.unwrap_or(1);
+    let points = match points {
+        Some(points) => points,
+        None => width
+            .checked_mul(height)
+            .ok_or_else(|| format_error(format!("WIDTH {width} x HEIGHT {height} overflows")))?,
+    };
+    Ok(PcdHeader {
+        fields,
+        points,
+        encoding,
+        origin,
+        organized: (height > 1).then_some((width, height)),
+    })
+}
+
+/// Reads a complete PCD stream.
+pub(crate) fn read_pcd<R: BufRead>(mut reader: R) -> Result<TablePointCloud, PcdError> {
+    let header = read_header(&mut reader)?;
+    let mut buffers = new_buffers(&header, header.points);
+    match header.encoding {
+        PcdEncoding::Ascii => read_ascii(&mut reader, header.points, &mut buffers)?,
+        PcdEncoding::Binary => read_binary(&mut reader, &header, header.points, &mut buffers)?,
+        PcdEncoding::BinaryCompressed => read_compressed(&mut reader, &header, &mut buffers)?,
+    }
+    finish(&header, buffers)
+}
+
+/// Streams a PCD body as clouds of at most `chunk_size` points.
+///
+/// ASCII and binary bodies are read incrementally; a `binary_compressed`
+/// body is a single LZF block, so it is decompressed once and then handed
+/// out in slices. The first error ends the iteration.
+pub(crate) struct PcdChunks<R> {
+    reader: R,
+    chunk_size: usize,
+    header: Option<PcdHeader>,
+    remaining: usize,
+    decompressed: Option<TablePointCloud>,
+    done: bool,
+}
+
+impl<R: BufRead> PcdChunks<R> {
+    pub(crate) fn new(reader: R, chunk_size: usize) -> Self {
+        Self {
+            reader,
+            chunk_size,
+            header: None,
+            remaining: 0,
+            decompressed: None,
+            done: false,
+        }
+    }
+
+    fn next_chunk(&mut self) -> Result<Option<TablePointCloud>, PcdError> {
+        if self.chunk_size == 0 {
+            return Err(PcdError::InvalidInput(
+                "chunk size must be positive".to_string(),
+            ));
+        }
+        if self.header.is_none() {
+            let header = read_header(&mut self.reader)?;
+            self.remaining = header.points;
+            if header.encoding == PcdEncoding::BinaryCompressed {
+                let mut buffers = new_buffers(&header, header.points);
+                read_compressed(&mut self.reader, &header, &mut buffers)?;
+                self.decompressed = Some(finish(&header, buffers)?);
+            }
+            self.header = Some(header);
+        }
+        let Some(header) = &self.header else {
+            unreachable!("header is read above");
+        };
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+
+        let n = self.chunk_size.min(self.remaining);
+        let chunk = match (&self.decompressed, header.encoding) {
+            (Some(cloud), _) => cloud.slice(header.points - self.remaining, n),
+            (None, PcdEncoding::Ascii) => {
+                let mut buffers = new_buffers(header, n);
+                read_ascii(&mut self.reader, n, &mut buffers)?;
+                finish(header, buffers)?
+            }
+            (None, _) => {
+                let mut buffers = new_buffers(header, n);
+                read_binary(&mut self.reader, header, n, &mut buffers)?;
+                finish(header, buffers)?
+            }
+        };
+        self.remaining -= n;
+        Ok(Some(chunk))
+    }
+}
+
+impl<R: BufRead> Iterator for PcdChunks<R> {
+    type Item = Result<TablePointCloud, PcdError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let chunk = self.next_chunk().transpose();
+        self.done = !matches!(chunk, Some(Ok(_)));
+        chunk
+    }
+}
+
+/// One buffer per column, in field order.
+pub(crate) fn new_buffers(header: &PcdHeader, capacity: usize) -> Vec<ColumnBuffer> {
+    header
+        .fields
+        .iter()
+        .flat_map(|field| (0..field.count).map(|_| ColumnBuffer::new(field.ty, capacity)))
+        .collect()
+}
+
+/// Reads `limit` ASCII points.
+pub(crate) fn read_ascii<R: BufRead>(
+    reader: &mut R,
+    limit: usize,
+    buffers: &mut [ColumnBuffer],
+) -> Result<(), PcdError> {
+    let mut line = String::new();
+    let mut read = 0;
+    while read < limit {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            break;
+        }
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        if tokens.is_empty() {
+            continue;
+        }
+        if tokens.len() != buffers.len() {
+            return Err(format_error(format!(
+                "expected {} values per point, found {} in '{}'",
+                buffers.len(),
+                tokens.len(),
+                line.trim()
+            )));
+        }
+        for (buffer, token) in buffers.iter_mut().zip(tokens) {
+            buffer.push_str(token)?;
+        }
+        read += 1;
+    }
+    if read < limit {
+        return Err(format_error(format!(
+            "ASCII body ended after {read} of {limit} points"
+        )));
+    }
+    Ok(())
+}
+
+/// Reads `limit` points stored as packed little-endian records.
+pub(crate) fn read_binary<R: BufRead>(
+    reader: &mut R,
+    header: &PcdHeader,
+    limit: usize,
+    buffers: &mut [ColumnBuffer],
+) -> Result<(), PcdError> {
+    let widths: Vec<usize> = header
+        .fields
+        .iter()
+        .flat_map(|field| std::iter::repeat_n(field.ty.size(), field.count))
+        .collect();
+    let mut record = vec![0u8; header.point_size()];
+    for _ in 0..limit {
+        reader.read_exact(&mut record).map_err(|e| {
+            if e.kind() == std::io::ErrorKind::UnexpectedEof {
+                format_error(format!("binary body ended before {limit} points were read"))
+            } else {
+                e.into()
+            }
+        })?;
+        let mut offset = 0;
+        for (buffer, width) in buffers.iter_mut().zip(&widths) {
+            buffer.push_bytes(&record[offset..offset + width], true);
+            offset += width;
+        }
+    }
+    Ok(())
+}
+
+/// Reads the whole LZF-compressed, column-major body.
+pub(crate) fn read_compressed<R: BufRead>(
+    reader: &mut R,
+    header: &PcdHeader,
+    buffers: &mut [ColumnBuffer],
+) -> Result<(), PcdError> {
+    let mut sizes = [0u8; 8];
+    reader.read_exact(&mut sizes)?;
+    let compressed_size = u32::from_le_bytes(sizes[..4].try_into().unwrap()) as usize;
+    let uncompressed_size = u32::from_le_bytes(sizes[4..].try_into().unwrap()) as usize;
+    let expected = header.body_size()?;
+    if uncompressed_size != expected {
+        return Err(format_error(format!(
+            "compressed body holds {uncompressed_size} bytes, expected {expected} for {} points",
+            header.points
+        )));
+    }
+    // Read through `take` so a corrupt size cannot allocate more than the
+    // stream actually holds.
+    let mut compressed = Vec::new();
+    reader
+        .take(compressed_size as u64)
+        .read_to_end(&mut compressed)?;
+    if compressed.len() != compressed_size {
+        return Err(format_error(format!(
+            "compressed body ended after {} of {compressed_size} bytes",
+            compressed.len()
+        )));
+    }
+    let data = lzf_decompress(&compressed, uncompressed_size)?;
+
+    // Each field is stored as one contiguous block covering all points.
+    let mut block_start = 0;
+    let mut buffers = buffers.iter_mut();
+    for field in &header.fields {
+        let size = field.ty.size();
+        let stride = size * field.count;
+        for element in 0..field.count {
+            let buffer = buffers.next().expect("one buffer per field element");
+            for point in 0..header.points {
+                let offset = block_start + point * stride + element * size;
+                buffer.push_bytes(&data[offset..offset + size], true);
+            }
+        }
+        block_start += stride * header.points;
+    }
+    Ok(())
+}
+
+/// Turns parsed buffers into a cloud: drops `_` padding fields, unpacks
+/// packed `rgb`/`rgba` fields into `red`, `green`, `blue` (and `alpha`)
+/// `u8` columns and applies a `# origin` header comment.
+pub(crate) fn finish(
+    header: &PcdHeader,
+    buffers: Vec<ColumnBuffer>,
+) -> Result<TablePointCloud, PcdError> {
+    let mut columns: Vec<(String, Series)> = Vec::new();
+    let mut buffers = buffers.into_iter();
+    for field in &header.fields {
+        for name in field.column_names() {
+            let series = buffers
+                .next()
+                .expect("one buffer per field element")
+                .into_series(&name);
+            if field.name == "_" {
+                continue;
+            }
+            let packed = matches!(field.name.as_str(), "rgb" | "rgba") && field.count == 1;
+            if packed && field.ty.size() == 4 {
+                columns.extend(unpack_rgb(&series, field.name == "rgba")?);
+            } else {
+                columns.push((name, series));
+            }
+        }
+    }
+    let mut cloud = build_cloud(columns)?;
+    if let Some(origin) = header.origin {
+        cloud.set_origin(origin);
+    }
+    if let Some((width, height)) = header.organized {
+        // A POINTS count disagreeing with WIDTH x HEIGHT leaves the cloud
+        // unorganized rather than failing the read.
+        if width.checked_mul(height) == Some(cloud.len()) {
+            cloud.set_organized(width, height)?;
+        }
+    }
+    Ok(cloud)
+}
+
+fn unpack_rgb(series: &Series, with_alpha: bool) -> Result<Vec<(String, Series)>, PcdError> {
+    let bits: Vec<u32> = match series.dtype() {
+        DataType::Float32 => series
+            .f32()?
+            .iter()
+            .map(|v| v.unwrap_or(0.0).to_bits())
+            .collect(),
+        _ => series
+            .cast(&DataType::UInt32)?
+            .u32()?
+            .iter()
+            .map(|v| v.unwrap_or(0))
+            .collect(),
+    };
+    let channel = |name: &str, shift: u32| {
+        let values: Vec<u8> = bits.iter().map(|b| ((b >> shift) & 0xff) as u8).collect();
+        (name.to_string(), Series::new(name.into(), values))
+    };
+    let mut columns = vec![channel("red", 16), channel("green", 8), channel("blue", 0)];
+    if with_alpha {
+        columns.push(channel("alpha", 24));
+    }
+    Ok(columns)
+}
+
+/// Decompresses an LZF block as written by PCL.
+fn lzf_decompress(input: &[u8], expected: usize) -> Result<Vec<u8>, PcdError> {
+    let corrupt = || format_error("corrupt LZF data in binary_compressed body");
+    let mut output = Vec::with_capacity(expected.min(MAX_PREALLOCATED_VALUES));
+    let mut i = 0;
+    while i < input.len() {
+        let ctrl = input[i] as usize;
+        i += 1;
+        if ctrl < 32 {
+            let run = ctrl + 1;
+            let literal = input.get(i..i + run).ok_or_else(corrupt)?;
+            output.extend_from_slice(literal);
+            i += run;
+        } else {
+            let mut len = ctrl >> 5;
+            if len == 7 {
+                len += *input.get(i).ok_or_else(corrupt)? as usize;
+                i += 1;
+            }
+            let low = *input.get(i).ok_or_else(corrupt)? as usize;
+            i += 1;
+            let back = ((ctrl & 0x1f) << 8) + low + 1;
+            let start = output.len().checked_sub(back).ok_or_else(corrupt)?;
+            for k in 0..len + 2 {
+                let byte = output[start + k];
+                output.push(byte);
+            }
+        }
+        if output.len() > expected {
+            return Err(corrupt());
+        }
+    }
+    if output.len() != expected {
+        return Err(corrupt());
+    }
+    Ok(output)
+}
+
+/// Writes every column as a field of a binary PCD body, keeping its storage
+/// type; booleans become `U 1`. Non-numeric columns and names containing
+/// whitespace are rejected. A non-zero origin is stored as a `# origin`
+/// comment and an organized cloud keeps its `WIDTH`/`HEIGHT`.
+pub(crate) fn write_pcd<W: Write>(cloud: &TablePointCloud, mut writer: W) -> Result<(), PcdError> {
+    let mut fields: Vec<(String, ScalarType, Vec<u8>)> = Vec::new();
+    for column in cloud.data().columns() {
+        let name = column.name().to_string();
+        if name.is_empty() || name.contains(char::is_whitespace) {
+            return Err(PcdError::InvalidInput(format!(
+                "invalid PCD field name {name:?}"
+            )));
+        }
+        let ty = ScalarType::from_dtype(column.dtype()).ok_or_else(|| {
+            PcdError::InvalidInput(format!(
+                "column '{name}' of type {} cannot be written to PCD",
+                column.dtype()
+            ))
+        })?;
+        let bytes = column_le_bytes(column, ty)?;
+        fields.push((name, ty, bytes));
+    }
+
+    let (width, height) = cloud.organized_dims().unwrap_or((cloud.len(), 1));
+    let (mut names, mut sizes, mut types) = (Vec::new(), Vec::new(), Vec::new());
+    for (name, ty, _) in &fields {
+        names.push(name.as_str());
+        sizes.push(ty.size().to_string());
+        types.push(ty.pcd_kind());
+    }
+    writeln!(writer, "# .PCD v0.7 - Point Cloud Data file format")?;
+    let [ox, oy, oz] = cloud.origin();
+    if cloud.origin() != [0.0; 3] {
+        writeln!(writer, "# origin {ox} {oy} {oz}")?;
+    }
+    writeln!(writer, "VERSION 0.7")?;
+    writeln!(writer, "FIELDS {}", names.join(" "))?;
+    writeln!(writer, "SIZE {}", sizes.join(" "))?;
+    writeln!(writer, "TYPE {}", types.join(" "))?;
+    writeln!(writer, "COUNT{}", " 1".repeat(fields.len()))?;
+    writeln!(writer, "WIDTH {width}")?;
+    writeln!(writer, "HEIGHT {height}")?;
+    writeln!(writer, "VIEWPOINT 0 0 0 1 0 0 0")?;
+    writeln!(writer, "POINTS {}", cloud.len())?;
+    writeln!(writer, "DATA binary")?;
+    for row in 0..cloud.len() {
+        for (_, ty, bytes) in &fields {
+            writer.write_all(&bytes[row * ty.size()..(row + 1) * ty.size()])?;
+        }
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    fn header(fields: &str, counts: &str, size: &str, data: &str) -> String {
+        format!(
+            "VERSION 0.7\nFIELDS {fields}\nSIZE {size}\nTYPE {}\nCOUNT {counts}\n{data}",
+            vec!["F"; fields.split_whitespace().count()].join(" ")
+        )
+    }
+
+    #[test]
+    fn rejects_overflowing_dimensions() {
+        let text = header(
+            "x y z",
+            "1 1 1",
+            "4 4 4",
+            &format!("WIDTH {}\nHEIGHT 2\nDATA ascii\n", usize::MAX),
+        );
+        let err = read_pcd(Cursor::new(text)).unwrap_err();
+        assert!(matches!(err, PcdError::Format(_)), "{err}");
+    }
+
+    #[test]
+    fn rejects_absurd_field_counts() {
+        let text = header(
+            "x y z",
+            &format!("1 1 {}", usize::MAX),
+            "4 4 4",
+            "WIDTH 1\nDATA ascii\n0 0 0\n",
+        );
+        let err = read_pcd(Cursor::new(text)).unwrap_err();
+        assert!(matches!(err, PcdError::Format(_)), "{err}");
+    }
+
+    #[test]
+    fn huge_point_counts_do_not_preallocate() {
+        // The header claims billions of points but the body is truncated;
+        // reading must fail cleanly instead of reserving memory for them.
+        let mut binary = header(
+            "x y z",
+            "1 1 1",
+            "8 8 8",
+            "WIDTH 4000000000\nPOINTS 4000000000\nDATA binary\n",
+        )
+        .into_bytes();
+        binary.extend_from_slice(&[0u8; 24]);
+        assert!(read_pcd(Cursor::new(binary)).is_err());
+
+        let mut compressed = header(
+            "x y z",
+            "1 1 1",
+            "4 4 4",
+            "WIDTH 357913941\nPOINTS 357913941\nDATA binary_compressed\n",
+        )
+        .into_bytes();
+        compressed.extend_from_slice(&u32::MAX.to_le_bytes());
+        compressed.extend_from_slice(&(357_913_941u32 * 12).to_le_bytes());
+        compressed.extend_from_slice(&[0u8; 16]);
+        let err = read_pcd(Cursor::new(compressed)).unwrap_err();
+        assert!(matches!(err, PcdError::Format(_)), "{err}");
+
+        let mut overflowing = header(
+            "x y z",
+            "1 1 1",
+            "8 8 8",
+            &format!(
+                "WIDTH 1\nPOINTS {}\nDATA binary_compressed\n",
+                usize::MAX / 2
+            ),
+        )
+        .into_bytes();
+        overflowing.extend_from_slice(&[0u8; 8]);
+        let err = read_pcd(Cursor::new(overflowing)).unwrap_err();
+        assert!(matches!(err, PcdError::Format(_)), "{err}");
+    }
+
+    #[test]
+    fn chunks_concatenate_to_full_load() {
+        let n = 250;
+        let values = |i: usize| [i as f32, (i * 2) as f32, 0.5 - i as f32];
+        let mut ascii = header(
+            "x y z",
+            "1 1 1",
+            "4 4 4",
+            &format!("WIDTH {n}\nDATA ascii\n"),
+        );
+        let mut binary = header(
+            "x y z",
+            "1 1 1",
+            "4 4 4",
+            &format!("WIDTH {n}\nDATA binary\n"),
+        )
+        .into_bytes();
+        for i in 0..n {
+            let [x, y, z] = values(i);
+            ascii.push_str(&format!("{x} {y} {z}\n"));
+            for v in values(i) {
+                binary.extend_from_slice(&v.to_le_bytes());
+            }
+        }
+
+        for body in [ascii.into_bytes(), binary] {
+            let full = read_pcd(Cursor::new(&body)).unwrap();
+            let chunks: Vec<TablePointCloud> = PcdChunks::new(Cursor::new(&body), 100)
+                .collect::<Result<_, _>>()
+                .unwrap();
+            let sizes: Vec<usize> = chunks.iter().map(TablePointCloud::len).collect();
+            assert_eq!(sizes, [100, 100, 50]);
+            let joined = TablePointCloud::concat(&chunks.iter().collect::<Vec<_>>()).unwrap();
+            assert_eq!(full.len(), n);
+            assert!(joined.approx_eq(&full, 0.0));
+        }
+    }
+
+    fn georeferenced() -> TablePointCloud {
+        let mut cloud = TablePointCloud::from_xyz(
+            vec![1.5, -2.0, 0.25, 4.0],
+            vec![0.0, 3.5, -1.0, 2.0],
+            vec![10.0, 11.0, 12.5, 9.0],
+        )
+        .unwrap();
+        cloud.set_origin([500_000.0, 4_100_000.0, 50.0]);
+        cloud
+            .set_attribute(Series::new("intensity".into(), vec![1.5f32, 2.0, 0.0, 7.0]))
+            .unwrap();
+        cloud
+            .set_attribute(Series::new("ring".into(), vec![0u16, 1, 2, 3]))
+            .unwrap();
+        cloud
+            .set_attribute(Series::new("label".into(), vec![-1i32, 2, 6, 2]))
+            .unwrap();
+        cloud.set_organized(2, 2).unwrap();
+        cloud
+    }
+
+    #[test]
+    fn written_pcd_round_trips_origin_and_types() {
+        let cloud = georeferenced();
+        let mut bytes = Vec::new();
+        write_pcd(&cloud, &mut bytes).unwrap();
+        let text = String::from_utf8_lossy(&bytes);
+        assert!(text.contains("# origin 500000 4100000 50\n"), "{text}");
+        assert!(text.contains("TYPE F F F F U I\n"), "{text}");
+
+        let read = read_pcd(Cursor::new(bytes)).unwrap();
+        assert_eq!(read.origin(), cloud.origin());
+        assert_eq!(read.organized_dims(), Some((2, 2)));
+        assert_eq!(read.global_xyz().unwrap(), cloud.global_xyz().unwrap());
+        assert_eq!(read.data().dtypes(), cloud.data().dtypes());
+        assert!(read.approx_eq(&cloud, 0.0));
+
+        let mut plain = Vec::new();
+        let unshifted = TablePointCloud::from_xyz(vec![1.0], vec![2.0], vec![3.0]).unwrap();
+        write_pcd(&unshifted, &mut plain).unwrap();
+        assert!(!String::from_utf8_lossy(&plain).contains("origin"));
+    }
+}
@@ -0,0 +1,196 @@
+use std::fs::File;
+use std::io::{BufWriter, Seek, SeekFrom, Write};
+use std::path::Path;
+
+use pcl_rustic_core::{PcdError, Point};
+
+/// Digits reserved in the header for the point count, enough for any
+/// `u64`; zero-padded so the final count can be patched in place.
+const COUNT_WIDTH: usize = 20;
+
+/// Writes a binary PCD file incrementally, for clouds too large to
+/// materialize in memory.
+///
+/// The header is written up front with placeholder `WIDTH`/`POINTS` counts,
+/// points are appended batch by batch with
+/// [`PcdStreamWriter::write_points`], and [`PcdStreamWriter::finish`]
+/// patches in the final count. A writer dropped without `finish` leaves a
+/// file that declares zero points.
+///
+/// Every field is stored as `F 8`: `x`, `y`, `z`, then the attributes named
+/// at creation. A point's value for an attribute is its float attribute, or
+/// else its integer attribute converted to `f64`, or else NaN.
+///
+/// Points are written as given, in local coordinates. A writer made with
+/// [`PcdStreamWriter::create_with_origin`] records a non-zero origin as a
+/// `# origin` header comment, which the PCD reader applies on load.
+pub struct PcdStreamWriter {
+    writer: BufWriter<File>,
+    attributes: Vec<String>,
+    points: u64,
+    /// Byte offsets of the `WIDTH` and `POINTS` placeholders.
+    count_offsets: [u64; 2],
+}
+
+impl PcdStreamWriter {
+    /// Creates (or truncates) `path` and writes the header for `x y z`
+    /// followed by `attributes`.
+    pub fn create<P: AsRef<Path>>(path: P, attributes: &[&str]) -> Result<Self, PcdError> {
+        Self::create_with_origin(path, attributes, [0.0; 3])
+    }
+
+    /// [`PcdStreamWriter::create`] for points given relative to `origin`,
+    /// e.g. a georeferenced cloud's [`TablePointCloud::origin`].
+    ///
+    /// [`TablePointCloud::origin`]: pcl_rustic_core::TablePointCloud::origin
+    pub fn create_with_origin<P: AsRef<Path>>(
+        path: P,
+        attributes: &[&str],
+        origin: [f64; 3],
+    ) -> Result<Self, PcdError> {
+        if origin.iter().any(|v| !v.is_finite()) {
+            return Err(PcdError::InvalidInput(format!(
+                "PCD origin must be finite, got {origin:?}"
+            )));
+        }
+        for (i, name) in attributes.iter().enumerate() {
+            if name.is_empty() || name.contains(char::is_whitespace) {
+                return Err(PcdError::InvalidInput(format!(
+                    "invalid PCD field name {name:?}"
+                )));
+            }
+            if ["x", "y", "z"].contains(name) || attributes[..i].contains(name) {
+                return Err(PcdError::InvalidInput(format!(
+                    "PCD field '{name}' would appear twice"
+                )));
+            }
+        }
+        let names: Vec<&str> = ["x", "y", "z"]
+            .into_iter()
+            .chain(attributes.iter().copied())
+            .collect();
+        let fields = names.len();
+        let placeholder = "0".repeat(COUNT_WIDTH);
+        let mut header = String::from("# .PCD v0.7 - Point Cloud Data file format\n");
+        if origin != [0.0; 3] {
+            let [ox, oy, oz] = origin;
+            header.push_str(&format!("# origin {ox} {oy} {oz}\n"));
+        }
+        header.push_str("VERSION 0.7\n");
+        header.push_str(&format!("FIELDS {}\n", names.join(" ")));
+        header.push_str(&format!("SIZE{}\n", " 8".repeat(fields)));
+        header.push_str(&format!("TYPE{}\n", " F".repeat(fields)));
+        header.push_str(&format!("COUNT{}\n", " 1".repeat(fields)));
+        header.push_str("WIDTH ");
+        let width_offset = header.len() as u64;
+        header.push_str(&format!(
+            "{placeholder}\nHEIGHT 1\nVIEWPOINT 0 0 0 1 0 0 0\nPOINTS "
+        ));
+        let points_offset = header.len() as u64;
+        header.push_str(&format!("{placeholder}\nDATA binary\n"));
+
+        let mut writer = BufWriter::new(File::create(path)?);
+        writer.write_all(header.as_bytes())?;
+        Ok(Self {
+            writer,
+            attributes: attributes.iter().map(|name| name.to_string()).collect(),
+            points: 0,
+            count_offsets: [width_offset, points_offset],
+        })
+    }
+
+    /// Appends a batch of points.
+    pub fn write_points(&mut self, points: &[Point<f64>]) -> Result<(), PcdError> {
+        for point in points {
+            for value in [point.x, point.y, point.z] {
+                self.writer.write_all(&value.to_le_bytes())?;
+            }
+            for name in &self.attributes {
+                let value = point
+                    .get_attribute(name)
+                    .copied()
+                    .or_else(|| point.get_int_attribute(name).map(|v| v as f64))
+                    .unwrap_or(f64::NAN);
+                self.writer.write_all(&value.to_le_bytes())?;
+            }
+        }
+        self.points += points.len() as u64;
+        Ok(())
+    }
+
+    /// Number of points written so far.
+    pub fn points_written(&self) -> u64 {
+        self.points
+    }
+
+    /// Writes the final point count into the header and flushes the file.
+    pub fn finish(self) -> Result<(), PcdError> {
+        let mut file = self
+            .writer
+            .into_inner()
+            .map_err(|err| PcdError::Io(err.into_error()))?;
+        let count = format!("{:0width$}", self.points, width = COUNT_WIDTH);
+        for offset in self.count_offsets {
+            file.seek(SeekFrom::Start(offset))?;
+            file.write_all(count.as_bytes())?;
+        }
+        file.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pcl_rustic_core::TablePointCloud;
+
+    use super::*;
+    use crate::tests::temp_path;
+    use crate::PointCloudIo;
+
+    #[test]
+    fn streamed_batches_read_back() {
+        let path = temp_path("streamed.pcd");
+        let origin = [500_000.0, 4_100_000.0, 50.0];
+        let mut writer =
+            PcdStreamWriter::create_with_origin(&path, &["intensity", "ring"], origin).unwrap();
+        for batch in 0..10 {
+            let points: Vec<Point<f64>> = (0..100)
+                .map(|i| {
+                    let v = (batch * 100 + i) as f64;
+                    let mut point = Point::new(v, -v, 0.5 * v).with_attribute("intensity", v);
+                    point.set_int_attribute("ring", i % 16);
+                    point
+                })
+                .collect();
+            writer.write_points(&points).unwrap();
+        }
+        assert_eq!(writer.points_written(), 1000);
+        writer.finish().unwrap();
+        let read = TablePointCloud::from_pcd_path(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        let read = read.unwrap();
+        assert_eq!(read.len(), 1000);
+        assert_eq!(read.origin(), origin);
+        assert_eq!(read.x().unwrap()[999], 999.0);
+        assert_eq!(read.attribute("intensity").unwrap()[250], 250.0);
+        assert_eq!(read.attribute("ring").unwrap()[17], 1.0);
+        assert!(PcdStreamWriter::create(&path, &["x"]).is_err());
+    }
+
+    #[test]
+    fn zero_origin_writes_no_comment() {
+        let path = temp_path("streamed_local.pcd");
+        PcdStreamWriter::create(&path, &[])
+            .unwrap()
+            .finish()
+            .unwrap();
+        let text = std::fs::read_to_string(&path).unwrap();
+        let read = TablePointCloud::from_pcd_path(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(!text.contains("origin"), "{text}");
+        assert!(text.contains("POINTS 00000000000000000000\n"), "{text}");
+        assert!(read.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,325 @@
+use std::io::{self, BufRead, Read, Write};
+
+use pcl_rustic_core::{PcdError, TablePointCloud};
+
+use crate::column::{build_cloud, column_le_bytes, parse_origin, ColumnBuffer, ScalarType};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PlyFormat {
+    Ascii,
+    BinaryLittleEndian,
+    BinaryBigEndian,
+}
+
+#[derive(Debug, Clone)]
+enum PlyProperty {
+    Scalar { name: String, ty: ScalarType },
+    List { count: ScalarType, item: ScalarType },
+}
+
+#[derive(Debug, Clone)]
+struct PlyElement {
+    name: String,
+    count: usize,
+    properties: Vec<PlyProperty>,
+}
+
+fn format_error(message: impl Into<String>) -> PcdError {
+    PcdError::Format(message.into())
+}
+
+fn parse_type(name: &str) -> Result<ScalarType, PcdError> {
+    ScalarType::from_ply(name).ok_or_else(|| format_error(format!("unknown PLY type '{name}'")))
+}
+
+struct PlyHeader {
+    format: PlyFormat,
+    elements: Vec<PlyElement>,
+    origin: Option<[f64; 3]>,
+}
+
+fn read_header<R: BufRead>(reader: &mut R) -> Result<PlyHeader, PcdError> {
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    if line.trim() != "ply" {
+        return Err(format_error("missing 'ply' magic line"));
+    }
+    let mut format = None;
+    let mut origin = None;
+    let mut elements: Vec<PlyElement> = Vec::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            return Err(format_error("PLY header ended before 'end_header'"));
+        }
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        match tokens.as_slice() {
+            ["comment", ..] => {
+                let comment = line.trim().trim_start_matches("comment");
+                origin = parse_origin(comment).or(origin);
+            }
+            [] | ["obj_info", ..] => {}
+            ["format", kind, _version] => {
+                format = Some(match *kind {
+                    "ascii" => PlyFormat::Ascii,
+                    "binary_little_endian" => PlyFormat::BinaryLittleEndian,
+                    "binary_big_endian" => PlyFormat::BinaryBigEndian,
+                    other => return Err(format_error(format!("unsupported PLY format '{other}'"))),
+                });
+            }
+            ["element", name, count] => elements.push(PlyElement {
+                name: name.to_string(),
+                count: count
+                    .parse()
+                    .map_err(|_| format_error(format!("invalid element count '{count}'")))?,
+                properties: Vec::new(),
+            }),
+            ["property", "list", count, item, _name] => {
+                let element = elements
+                    .last_mut()
+                    .ok_or_else(|| format_error("property declared before any element"))?;
+                element.properties.push(PlyProperty::List {
+                    count: parse_type(count)?,
+                    item: parse_type(item)?,
+                });
+            }
+            ["property", ty, name] => {
+                let element = elements
+                    .last_mut()
+                    .ok_or_else(|| format_error("property declared before any element"))?;
+                element.properties.push(PlyProperty::Scalar {
+                    name: name.to_string(),
+                    ty: parse_type(ty)?,
+                });
+            }
+            ["end_header"] => break,
+            _ => {
+                return Err(format_error(format!(
+                    "unexpected header line '{}'",
+                    line.trim()
+                )))
+            }
+        }
+    }
+    let format = format.ok_or_else(|| format_error("PLY header has no format line"))?;
+    Ok(PlyHeader {
+        format,
+        elements,
+        origin,
+    })
+}
+
+/// Reads the `vertex` element of a PLY stream. Elements before it are
+/// skipped and reading stops once it is parsed; list properties on vertices
+/// are ignored. A `comment origin` header line sets the cloud's origin.
+pub(crate) fn read_ply<R: BufRead>(mut reader: R) -> Result<TablePointCloud, PcdError> {
+    let PlyHeader {
+        format,
+        elements,
+        origin,
+    } = read_header(&mut reader)?;
+    for element in &elements {
+        if element.name != "vertex" {
+            for _ in 0..element.count {
+                read_row(&mut reader, format, element, &mut [])?;
+            }
+            continue;
+        }
+        let mut buffers: Vec<ColumnBuffer> = element
+            .properties
+            .iter()
+            .filter_map(|p| match p {
+                PlyProperty::Scalar { ty, .. } => Some(ColumnBuffer::new(*ty, element.count)),
+                PlyProperty::List { .. } => None,
+            })
+            .collect();
+        for _ in 0..element.count {
+            read_row(&mut reader, format, element, &mut buffers)?;
+        }
+        let names = element.properties.iter().filter_map(|p| match p {
+            PlyProperty::Scalar { name, .. } => Some(name.as_str()),
+            PlyProperty::List { .. } => None,
+        });
+        let columns = names
+            .zip(buffers)
+            .map(|(name, buffer)| (name.to_string(), buffer.into_series(name)))
+            .collect();
+        let mut cloud = build_cloud(columns)?;
+        if let Some(origin) = origin {
+            cloud.set_origin(origin);
+        }
+        return Ok(cloud);
+    }
+    Err(format_error("PLY file has no vertex element"))
+}
+
+/// Reads one element row, pushing scalar properties into `buffers` (if any
+/// are given) in declaration order.
+fn read_row<R: BufRead>(
+    reader: &mut R,
+    format: PlyFormat,
+    element: &PlyElement,
+    buffers: &mut [ColumnBuffer],
+) -> Result<(), PcdError> {
+    let collect = !buffers.is_empty();
+    let mut buffers = buffers.iter_mut();
+    if format == PlyFormat::Ascii {
+        let mut line = String::new();
+        while line.trim().is_empty() {
+            line.clear();
+            if reader.read_line(&mut line)? == 0 {
+                return Err(format_error(format!(
+                    "unexpected end of '{}' data",
+                    element.name
+                )));
+            }
+        }
+        let mut tokens = line.split_whitespace();
+        let mut next = || {
+            tokens
+                .next()
+                .ok_or_else(|| format_error(format!("short '{}' row", element.name)))
+        };
+        for property in &element.properties {
+            match property {
+                PlyProperty::Scalar { .. } => {
+                    let token = next()?;
+                    if collect {
+                        buffers
+                            .next()
+                            .expect("one buffer per scalar")
+                            .push_str(token)?;
+                    }
+                }
+                PlyProperty::List { .. } => {
+                    let len: usize = next()?
+                        .parse()
+                        .map_err(|_| format_error("invalid list length"))?;
+                    for _ in 0..len {
+                        next()?;
+                    }
+                }
+            }
+        }
+        return Ok(());
+    }
+
+    let little_endian = format == PlyFormat::BinaryLittleEndian;
+    let mut bytes = [0u8; 8];
+    for property in &element.properties {
+        match property {
+            PlyProperty::Scalar { ty, .. } => {
+                let raw = &mut bytes[..ty.size()];
+                reader.read_exact(raw)?;
+                if collect {
+                    buffers
+                        .next()
+                        .expect("one buffer per scalar")
+                        .push_bytes(raw, little_endian);
+                }
+            }
+            PlyProperty::List { count, item } => {
+                let mut length = ColumnBuffer::new(*count, 1);
+                let raw = &mut bytes[..count.size()];
+                reader.read_exact(raw)?;
+                length.push_bytes(raw, little_endian);
+                let len = length
+                    .get_f64(0)
+                    .filter(|&v| v >= 0.0)
+                    .ok_or_else(|| format_error("invalid list length"))?
+                    as usize;
+                // The length is untrusted, so stream the items past instead
+                // of allocating room for them.
+                let skip = (len as u64).saturating_mul(item.size() as u64);
+                if io::copy(&mut reader.by_ref().take(skip), &mut io::sink())? != skip {
+                    return Err(format_error(format!(
+                        "unexpected end of '{}' data",
+                        element.name
+                    )));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Writes every column as a property of a binary little-endian `vertex`
+/// element. 64-bit integer columns are widened to `double`, since PLY has
+/// no 64-bit integer type; non-numeric columns are rejected. A non-zero
+/// origin is stored as a `comment origin` line.
+pub(crate) fn write_ply<W: Write>(cloud: &TablePointCloud, mut writer: W) -> Result<(), PcdError> {
+    let mut properties: Vec<(String, ScalarType, Vec<u8>)> = Vec::new();
+    for column in cloud.data().columns() {
+        let ty = ScalarType::from_dtype(column.dtype()).ok_or_else(|| {
+            PcdError::InvalidInput(format!(
+                "column '{}' of type {} cannot be written to PLY",
+                column.name(),
+                column.dtype()
+            ))
+        })?;
+        let ty = if ty.ply_name().is_some() {
+            ty
+        } else {
+            ScalarType::F64
+        };
+        properties.push((column.name().to_string(), ty, column_le_bytes(column, ty)?));
+    }
+
+    writeln!(writer, "ply")?;
+    writeln!(writer, "format binary_little_endian 1.0")?;
+    let [ox, oy, oz] = cloud.origin();
+    if cloud.origin() != [0.0; 3] {
+        writeln!(writer, "comment origin {ox} {oy} {oz}")?;
+    }
+    writeln!(writer, "element vertex {}", cloud.len())?;
+    for (name, ty, _) in &properties {
+        writeln!(
+            writer,
+            "property {} {}",
+            ty.ply_name().expect("PLY type"),
+            name
+        )?;
+    }
+    writeln!(writer, "end_header")?;
+    for row in 0..cloud.len() {
+        for (_, ty, bytes) in &properties {
+            writer.write_all(&bytes[row * ty.size()..(row + 1) * ty.size()])?;
+        }
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn skips_lists_without_trusting_their_length() {
+        let mut file = b"ply\nformat binary_little_endian 1.0\n\
+element face 1\nproperty list uint uint vertex_indices\n\
+element vertex 1\nproperty float x\nproperty float y\nproperty float z\nend_header\n"
+            .to_vec();
+        // A face claiming 2^32 - 1 indices, followed by far fewer bytes.
+        file.extend_from_slice(&u32::MAX.to_le_bytes());
+        file.extend_from_slice(&[0u8; 64]);
+        let err = read_ply(Cursor::new(file)).unwrap_err();
+        assert!(matches!(err, PcdError::Format(_)), "{err}");
+    }
+
+    #[test]
+    fn written_ply_round_trips_origin() {
+        let mut cloud =
+            TablePointCloud::from_xyz(vec![1.5, -2.0], vec![0.0, 3.5], vec![10.0, 11.0]).unwrap();
+        cloud.set_origin([500_000.0, 4_100_000.0, 50.0]);
+        let mut bytes = Vec::new();
+        write_ply(&cloud, &mut bytes).unwrap();
+        assert!(String::from_utf8_lossy(&bytes).contains("comment origin 500000 4100000 50\n"));
+
+        let read = read_ply(Cursor::new(bytes)).unwrap();
+        assert_eq!(read.origin(), cloud.origin());
+        assert_eq!(read.global_xyz().unwrap(), cloud.global_xyz().unwrap());
+    }
+}
@@ -0,0 +1,38 @@
+use std::io::BufRead;
+
+use pcl_rustic_core::{PcdError, TablePointCloud};
+
+/// Reads whitespace- or comma-separated `x y z` rows.
+///
+/// Empty lines and `#` comments are skipped; values after the third
+/// column are ignored.
+pub(crate) fn read_xyz<R: BufRead>(reader: R) -> Result<TablePointCloud, PcdError> {
+    let (mut x, mut y, mut z) = (Vec::new(), Vec::new(), Vec::new());
+    for (number, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let values: Vec<f64> = line
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|token| !token.is_empty())
+            .take(3)
+            .map(|token| {
+                token.parse::<f64>().map_err(|_| {
+                    PcdError::Format(format!("line {}: cannot parse '{token}'", number + 1))
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        let [px, py, pz] = values[..] else {
+            return Err(PcdError::Format(format!(
+                "line {}: expected at least 3 values",
+                number + 1
+            )));
+        };
+        x.push(px);
+        y.push(py);
+        z.push(pz);
+    }
+    Ok(TablePointCloud::from_xyz(x, y, z)?)
+}
@@ -1,14 +1,172 @@
+// The wrappers pyo3 0.22 generates for `PyResult` methods trip this lint.
+#![allow(clippy::useless_conversion)]
+
+use pyo3::exceptions::{PyIOError, PyValueError};
 use pyo3::prelude::*;
+use pyo3::types::PyDict;
 
-use pcl_rustic_core::hello_from_core;
+use pcl_rustic_core::cloud::COLOR_COLUMNS;
+use pcl_rustic_core::{hello_from_core, PcdError, TablePointCloud};
+use pcl_rustic_io::PointCloudIo;
+use polars::prelude::{NamedFrom, PolarsError, Series};
 
 #[pyfunction]
 fn hello_from_bind() -> String {
     hello_from_core()
 }
 
+fn polars_err(err: PolarsError) -> PyErr {
+    PyValueError::new_err(err.to_string())
+}
+
+fn pcd_err(err: PcdError) -> PyErr {
+    match err {
+        PcdError::Io(err) => PyIOError::new_err(err.to_string()),
+        err => PyValueError::new_err(err.to_string()),
+    }
+}
+
+/// A point cloud with `x`, `y`, `z` coordinates and named per-point
+/// attributes.
+#[pyclass(name = "PointCloud")]
+struct PyPointCloud {
+    inner: TablePointCloud,
+}
+
+#[pymethods]
+impl PyPointCloud {
+    #[new]
+    fn new(x: Vec<f64>, y: Vec<f64>, z: Vec<f64>) -> PyResult<Self> {
+        let inner = TablePointCloud::from_xyz(x, y, z).map_err(polars_err)?;
+        Ok(Self { inner })
+    }
+
+    #[staticmethod]
+    fn from_pcd(path: &str) -> PyResult<Self> {
+        let inner = TablePointCloud::from_pcd_path(path).map_err(pcd_err)?;
+        Ok(Self { inner })
+    }
+
+    #[staticmethod]
+    fn from_ply(path: &str) -> PyResult<Self> {
+        let inner = TablePointCloud::from_ply_path(path).map_err(pcd_err)?;
+        Ok(Self { inner })
+    }
+
+    #[staticmethod]
+    fn from_xyz(path: &str) -> PyResult<Self> {
+        let inner = TablePointCloud::from_xyz_path(path).map_err(pcd_err)?;
+        Ok(Self { inner })
+    }
+
+    fn __len__(&self) -> usize {
+        self.inner.len()
+    }
+
+    fn attribute_names(&self) -> Vec<String> {
+        self.inner.attribute_names()
+    }
+
+    /// Adds or replaces a float attribute column.
+    fn set_attribute(&mut self, name: &str, values: Vec<f64>) -> PyResult<()> {
+        self.inner
+            .set_attribute(Series::new(name.into(), values))
+            .map_err(polars_err)
+    }
+
+    /// ASPRS class name of each point's `classification` code, e.g.
+    /// `"Ground"` for 2; unknown codes become `"Unassigned(n)"`.
+    fn classification_names(&self) -> PyResult<Vec<String>> {
+        self.inner.classification_names().map_err(polars_err)
+    }
+
+    /// numpy arrays laid out for `open3d.geometry.PointCloud`:
+    /// `points` is `(N, 3)`, `colors` is `(N, 3)` in `[0, 1]` (from
+    /// `red`/`green`/`blue`) and `intensity` is `(N,)`; absent columns map
+    /// to `None`. Requires numpy to be installed.
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let numpy = PyModule::import_bound(py, "numpy")?;
+        let array = |rows: PyObject| -> PyResult<PyObject> {
+            Ok(numpy.call_method1("asarray", (rows, "float64"))?.unbind())
+        };
+        let arrays = Open3dArrays::new(&self.inner).map_err(polars_err)?;
+
+        let dict = PyDict::new_bound(py);
+        dict.set_item("points", array(arrays.points.into_py(py))?)?;
+        match arrays.colors {
+            Some(colors) => dict.set_item("colors", array(colors.into_py(py))?)?,
+            None => dict.set_item("colors", py.None())?,
+        }
+        match arrays.intensity {
+            Some(intensity) => dict.set_item("intensity", array(intensity.into_py(py))?)?,
+            None => dict.set_item("intensity", py.None())?,
+        }
+        Ok(dict)
+    }
+}
+
+/// The columns [`PyPointCloud::to_dict`] exports, gathered on the Rust side
+/// so the layout can be checked without a Python interpreter.
+#[derive(Debug)]
+struct Open3dArrays {
+    points: Vec<[f64; 3]>,
+    /// Colors scaled from 0-255 to `[0, 1]`.
+    colors: Option<Vec<[f64; 3]>>,
+    intensity: Option<Vec<f64>>,
+}
+
+impl Open3dArrays {
+    fn new(cloud: &TablePointCloud) -> Result<Self, PolarsError> {
+        let colors = if COLOR_COLUMNS.iter().all(|c| cloud.has_column(c)) {
+            let [r, g, b] = COLOR_COLUMNS.map(|c| cloud.attribute(c));
+            let (r, g, b) = (r?, g?, b?);
+            Some(
+                (0..cloud.len())
+                    .map(|i| [r[i], g[i], b[i]].map(|v| (v / 255.0).clamp(0.0, 1.0)))
+                    .collect(),
+            )
+        } else {
+            None
+        };
+        let intensity = if cloud.has_column("intensity") {
+            Some(cloud.attribute("intensity")?)
+        } else {
+            None
+        };
+        Ok(Self {
+            points: cloud.xyz()?,
+            colors,
+            intensity,
+        })
+    }
+}
+
 #[pymodule]
 fn _core(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(hello_from_bind, m)?)?;
+    m.add_class::<PyPointCloud>()?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn open3d_arrays_of_a_colored_cloud() {
+        let mut cloud =
+            TablePointCloud::from_xyz(vec![0.0, 1.0], vec![2.0, 3.0], vec![4.0, 5.0]).unwrap();
+        let arrays = Open3dArrays::new(&cloud).unwrap();
+        assert_eq!(arrays.points, [[0.0, 2.0, 4.0], [1.0, 3.0, 5.0]]);
+        assert!(arrays.colors.is_none() && arrays.intensity.is_none());
+
+        for (name, values) in [("red", [255u8, 0]), ("green", [0, 51]), ("blue", [0, 255])] {
+            cloud
+                .set_attribute(Series::new(name.into(), values.to_vec()))
+                .unwrap();
+        }
+        let arrays = Open3dArrays::new(&cloud).unwrap();
+        assert_eq!(arrays.colors.unwrap(), [[1.0, 0.0, 0.0], [0.0, 0.2, 1.0]]);
+        assert!(arrays.intensity.is_none());
+    }
+}
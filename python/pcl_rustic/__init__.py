@@ -1,4 +1,6 @@
-from pcl_rustic._core import hello_from_bind
+from pcl_rustic._core import PointCloud, hello_from_bind
+
+__all__ = ["PointCloud", "hello_from_bind", "main"]
 
 
 def main() -> None: